@@ -1,5 +1,14 @@
+use crate::breadcrumb;
+use crate::catalog::Catalog;
+use crate::clipboard;
+use crate::diff::Marks;
+use crate::example;
+use crate::fts::GlobalSearch;
+use crate::keymap::{Action, Key, Keymap};
+use crate::search::SearchState;
 use crate::spec::LoadedSpec;
-use crate::tree::TreeState;
+use crate::tree::{NodeKind, TreeState, find_descendant};
+use crate::validate::{self, Violation};
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Pane {
@@ -7,45 +16,239 @@ pub enum Pane {
     Detail,
 }
 
+/// What the left pane is browsing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum View {
+    Schemas,
+    Operations,
+    Services, // multi-service mode: the services in `index.db`
+}
+
+impl View {
+    pub fn label(&self) -> &'static str {
+        match self {
+            View::Schemas => "schemas",
+            View::Operations => "operations",
+            View::Services => "services",
+        }
+    }
+}
+
+/// A remembered cursor location for the jump list (`Ctrl-o` / `Ctrl-i`).
+/// Stored as a node path rather than a flat index so it survives expand/collapse.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CursorState {
+    pub view: View,
+    pub path: Vec<usize>,
+    pub detail_scroll: u16,
+}
+
+/// The result of `V`: a payload checked against the selected schema.
+pub struct Validation {
+    pub schema: String, // name of the node checked against
+    pub source: String, // the payload's file, or "clipboard"
+    pub violations: Vec<Violation>,
+    pub selected: usize,
+}
+
+/// The `gr` popup: everywhere a component schema is used.
+pub struct UsageList {
+    pub schema: usize, // index into `LoadedSpec::schema_nodes`
+    pub selected: usize,
+}
+
 pub struct App {
     pub spec: LoadedSpec,
-    pub tree: TreeState,
+    pub tree: TreeState,    // schemas view
+    pub op_tree: TreeState, // operations view
+    pub view: View,
     pub focused_pane: Pane,
     pub should_quit: bool,
     pub detail_scroll: u16,
-    pub pending_key: Option<char>,
+    pub keymap: Keymap,
+    pub pending_keys: Vec<Key>, // an unfinished multi-key sequence (`g` of `gd`)
+    pub status_message: Option<String>, // one-shot message shown in the status bar
+    pub jump_list: Vec<CursorState>,
+    pub jump_index: usize, // == jump_list.len() when not walking the history
+    pub search: Option<SearchState>,
+    pub filter_typing: bool, // `f` prompt open; the query lives on the active tree's filter
+    pub catalog: Option<Catalog>, // set when browsing the index rather than one file
+    pub service_tree: TreeState, // services view
+    pub global: Option<GlobalSearch>, // cross-service search popup, when open
+    pub diagnostics_panel: Option<usize>, // selected row of the diagnostics panel, when open
+    pub diff: Option<Marks>, // `--diff`: `spec` is the union of two specs, marked here
+    pub validate_prompt: Option<String>, // `V` prompt open: the payload file typed so far
+    pub validation: Option<Validation>, // violations panel, when open
+    pub usages: Option<UsageList>, // `gr` popup, when open
 }
 
 impl App {
     pub fn new(spec: LoadedSpec) -> Self {
         let tree = TreeState::new(spec.schema_nodes.clone());
+        let op_tree = TreeState::new(spec.operation_nodes.clone());
         App {
             spec,
             tree,
+            op_tree,
+            view: View::Schemas,
             focused_pane: Pane::SchemaList,
             should_quit: false,
             detail_scroll: 0,
-            pending_key: None,
+            keymap: Keymap::default(),
+            pending_keys: Vec::new(),
+            status_message: None,
+            jump_list: Vec::new(),
+            jump_index: 0,
+            search: None,
+            filter_typing: false,
+            catalog: None,
+            service_tree: TreeState::new(Vec::new()),
+            global: None,
+            diagnostics_panel: None,
+            diff: None,
+            validate_prompt: None,
+            validation: None,
+            usages: None,
         }
     }
 
+    /// Diff mode: browse the union of two specs, with what changed marked.
+    pub fn with_diff(old: LoadedSpec, new: LoadedSpec) -> Self {
+        let (spec, marks) = crate::diff::union(old, new);
+        let mut app = App::new(spec);
+        app.diff = Some(marks);
+        app
+    }
+
+    /// Multi-service mode: start on the service list, with no spec loaded yet.
+    pub fn with_catalog(catalog: Catalog) -> Self {
+        let mut app = App::new(LoadedSpec::empty());
+        app.service_tree = TreeState::new(catalog.tree());
+        app.catalog = Some(catalog);
+        app.view = View::Services;
+        app
+    }
+
+    /// Run a normal-mode action from the keymap.
+    pub fn perform(&mut self, action: Action) {
+        match action {
+            Action::MoveDown => self.move_down(),
+            Action::MoveUp => self.move_up(),
+            Action::GotoTop => self.goto_top(),
+            Action::GotoBottom => self.goto_bottom(),
+            Action::ToggleNode => self.toggle_expand(),
+            Action::ExpandNode => self.expand_node(),
+            Action::CollapseNode => self.collapse_node(),
+            Action::ExpandAll => self.expand_all(),
+            Action::CollapseAll => self.collapse_all(),
+            Action::FollowRef => self.follow_ref(),
+            Action::JumpBack => self.jump_back(),
+            Action::JumpForward => self.jump_forward(),
+            Action::YankPointer => self.yank_pointer(),
+            Action::YankExample => self.yank_example(),
+            Action::Search => self.start_search(),
+            Action::SearchNext => self.next_match(true),
+            Action::SearchPrev => self.next_match(false),
+            Action::Filter => self.start_filter(),
+            Action::ClearFilter => self.clear_filter(),
+            Action::Cancel => self.cancel_search(),
+            Action::ScrollDown => self.scroll_detail_down(),
+            Action::ScrollUp => self.scroll_detail_up(),
+            Action::FocusNextPane => self.toggle_pane(),
+            Action::ToggleView => self.toggle_view(),
+            Action::SwitchService => self.switch_service(),
+            Action::GlobalSearch => self.start_global_search(),
+            Action::Diagnostics => self.toggle_diagnostics(),
+            Action::Validate => self.start_validate(),
+            Action::Usages => self.show_usages(),
+            Action::Quit => self.should_quit = true,
+            Action::Noop => {}
+        }
+    }
+
+    /// Run a popup-mode action in whichever list popup is open. Only moving,
+    /// opening the selected entry and closing mean anything there.
+    pub fn perform_popup(&mut self, action: Action) {
+        let down = action == Action::MoveDown;
+        match action {
+            Action::MoveDown | Action::MoveUp => {
+                if self.global.is_some() {
+                    self.global_move(down);
+                } else if self.diagnostics_panel.is_some() {
+                    self.diagnostics_move(down);
+                } else if self.usages.is_some() {
+                    self.usages_move(down);
+                } else if self.validation.is_some() {
+                    self.validation_move(down);
+                }
+            }
+            Action::ToggleNode => {
+                if self.global.is_some() {
+                    self.open_global_hit();
+                } else if self.diagnostics_panel.is_some() {
+                    self.open_diagnostic();
+                } else if self.usages.is_some() {
+                    self.open_usage();
+                }
+            }
+            Action::Cancel => {
+                self.global = None;
+                self.diagnostics_panel = None;
+                self.usages = None;
+                self.validation = None;
+            }
+            Action::Quit => self.should_quit = true,
+            _ => {}
+        }
+    }
+
+    /// The tree shown in the left pane for the current view.
+    pub fn active_tree(&self) -> &TreeState {
+        match self.view {
+            View::Schemas => &self.tree,
+            View::Operations => &self.op_tree,
+            View::Services => &self.service_tree,
+        }
+    }
+
+    fn active_tree_mut(&mut self) -> &mut TreeState {
+        match self.view {
+            View::Schemas => &mut self.tree,
+            View::Operations => &mut self.op_tree,
+            View::Services => &mut self.service_tree,
+        }
+    }
+
+    /// Switch the left pane between schemas and operations. Each view keeps its
+    /// own cursor and expansion state.
+    pub fn toggle_view(&mut self) {
+        self.view = match self.view {
+            View::Schemas => View::Operations,
+            View::Operations => View::Schemas,
+            View::Services => return,
+        };
+        self.detail_scroll = 0;
+        self.search = None;
+        self.filter_typing = false;
+    }
+
     pub fn move_up(&mut self) {
-        self.tree.move_up();
+        self.active_tree_mut().move_up();
         self.detail_scroll = 0;
     }
 
     pub fn move_down(&mut self) {
-        self.tree.move_down();
+        self.active_tree_mut().move_down();
         self.detail_scroll = 0;
     }
 
     pub fn goto_top(&mut self) {
-        self.tree.goto_top();
+        self.active_tree_mut().goto_top();
         self.detail_scroll = 0;
     }
 
     pub fn goto_bottom(&mut self) {
-        self.tree.goto_bottom();
+        self.active_tree_mut().goto_bottom();
         self.detail_scroll = 0;
     }
 
@@ -56,26 +259,613 @@ impl App {
         };
     }
 
+    /// `l` / `Enter`: expand or collapse, or open the service under the cursor.
     pub fn toggle_expand(&mut self) {
-        self.tree.toggle_at_cursor();
+        if let Some(NodeKind::Service(index)) = self
+            .active_tree()
+            .selected_node()
+            .map(|n| n.info.kind.clone())
+        {
+            self.open_service(index);
+            return;
+        }
+        self.active_tree_mut().toggle_at_cursor();
         self.detail_scroll = 0;
     }
 
     pub fn expand_node(&mut self) {
-        self.tree.expand_at_cursor();
+        self.active_tree_mut().expand_at_cursor();
     }
 
     pub fn collapse_node(&mut self) {
-        self.tree.collapse_at_cursor();
+        self.active_tree_mut().collapse_at_cursor();
     }
 
     pub fn expand_all(&mut self) {
-        self.tree.expand_all();
+        self.active_tree_mut().expand_all();
     }
 
     pub fn collapse_all(&mut self) {
-        self.tree.collapse_all();
+        self.active_tree_mut().collapse_all();
+        self.detail_scroll = 0;
+    }
+
+    // ── services ─────────────────────────────────────────────────────────────
+
+    /// Load a service from the index and show its schemas. Jumps, search and
+    /// filters all refer to the previous service's trees, so they are dropped.
+    pub fn open_service(&mut self, index: usize) {
+        let Some(catalog) = self.catalog.as_mut() else {
+            return;
+        };
+        let spec = match catalog.load(index) {
+            Ok(spec) => spec,
+            Err(e) => {
+                self.status_message = Some(format!("{e:#}"));
+                return;
+            }
+        };
+        catalog.active = Some(index);
+        self.tree = TreeState::new(spec.schema_nodes.clone());
+        self.op_tree = TreeState::new(spec.operation_nodes.clone());
+        self.spec = spec;
+        self.view = View::Schemas;
+        self.detail_scroll = 0;
+        self.jump_list.clear();
+        self.jump_index = 0;
+        self.search = None;
+        self.filter_typing = false;
+        self.diagnostics_panel = None;
+        self.validation = None;
+        self.usages = None;
+    }
+
+    /// `S`: back to the service list, keeping its cursor.
+    pub fn switch_service(&mut self) {
+        if self.catalog.is_none() {
+            self.status_message =
+                Some("not browsing the index (run speq with no file)".to_string());
+            return;
+        }
+        self.view = View::Services;
+        self.detail_scroll = 0;
+        self.search = None;
+        self.filter_typing = false;
+    }
+
+    // ── cross-service search ─────────────────────────────────────────────────
+
+    /// `gs`: open the search popup over every indexed service.
+    pub fn start_global_search(&mut self) {
+        if self.catalog.is_none() {
+            self.status_message =
+                Some("not browsing the index (run speq with no file)".to_string());
+            return;
+        }
+        self.global = Some(GlobalSearch::default());
+    }
+
+    pub fn global_push(&mut self, c: char) {
+        if let Some(global) = self.global.as_mut() {
+            global.query.push(c);
+        }
+        self.refresh_global();
+    }
+
+    /// Backspace in the popup; on an empty query it closes the popup.
+    pub fn global_pop(&mut self) {
+        match self.global.as_mut() {
+            Some(global) if !global.query.is_empty() => {
+                global.query.pop();
+                self.refresh_global();
+            }
+            _ => self.global = None,
+        }
+    }
+
+    pub fn global_move(&mut self, down: bool) {
+        if let Some(global) = self.global.as_mut() {
+            let last = global.hits.len().saturating_sub(1);
+            global.selected = if down {
+                (global.selected + 1).min(last)
+            } else {
+                global.selected.saturating_sub(1)
+            };
+        }
+    }
+
+    /// `Enter`: open the selected hit's service and select the schema or property.
+    pub fn open_global_hit(&mut self) {
+        let Some(hit) = self
+            .global
+            .take()
+            .and_then(|g| g.hits.get(g.selected).cloned())
+        else {
+            return;
+        };
+        let Some(catalog) = &self.catalog else {
+            return;
+        };
+        let Some(index) = catalog.position(&hit.service) else {
+            self.status_message = Some(format!("{} is no longer indexed", hit.service));
+            return;
+        };
+        if catalog.active == Some(index) {
+            self.push_jump();
+        } else {
+            self.open_service(index);
+            if self.catalog.as_ref().and_then(|c| c.active) != Some(index) {
+                return; // failed to load; open_service set the status
+            }
+        }
+
+        self.view = View::Schemas;
+        self.detail_scroll = 0;
+        let Some(root) = self.tree.root_index(&hit.schema_name) else {
+            self.status_message = Some(format!("no schema named {}", hit.schema_name));
+            return;
+        };
+        let path = hit
+            .property_name
+            .and_then(|name| find_descendant(&self.tree.roots, root, &name))
+            .unwrap_or_else(|| vec![root]);
+        self.tree.select_path(&path);
+    }
+
+    fn refresh_global(&mut self) {
+        let (Some(global), Some(catalog)) = (self.global.as_mut(), self.catalog.as_ref()) else {
+            return;
+        };
+        global.selected = 0;
+        match catalog.search(&global.query, 100) {
+            Ok(hits) => global.hits = hits,
+            Err(e) => {
+                global.hits.clear();
+                self.status_message = Some(format!("search failed: {e:#}"));
+            }
+        }
+    }
+
+    // ── diagnostics ──────────────────────────────────────────────────────────
+
+    /// `D`: open or close the list of the spec's diagnostics.
+    pub fn toggle_diagnostics(&mut self) {
+        if self.diagnostics_panel.take().is_some() {
+            return;
+        }
+        if self.spec.diagnostics.is_empty() {
+            self.status_message = Some("no diagnostics".to_string());
+            return;
+        }
+        self.diagnostics_panel = Some(0);
+    }
+
+    pub fn diagnostics_move(&mut self, down: bool) {
+        let last = self.spec.diagnostics.list.len().saturating_sub(1);
+        if let Some(selected) = self.diagnostics_panel.as_mut() {
+            *selected = if down {
+                (*selected + 1).min(last)
+            } else {
+                selected.saturating_sub(1)
+            };
+        }
+    }
+
+    /// `Enter` in the panel: jump to the node the selected diagnostic is about.
+    pub fn open_diagnostic(&mut self) {
+        let Some(diagnostic) = self
+            .diagnostics_panel
+            .take()
+            .and_then(|i| self.spec.diagnostics.list.get(i))
+        else {
+            return;
+        };
+        let (view, path) = (diagnostic.view, diagnostic.path.clone());
+        self.push_jump();
+        self.view = view;
+        self.detail_scroll = 0;
+        self.active_tree_mut().select_path(&path);
+    }
+
+    // ── usages ───────────────────────────────────────────────────────────────
+
+    /// `gr`: list everywhere the selected schema is used — the target of a
+    /// `$ref`, or in the schemas view the component the cursor is in.
+    pub fn show_usages(&mut self) {
+        let Some(node) = self.active_tree().selected_node() else {
+            return;
+        };
+        let name = match &node.info.kind {
+            NodeKind::Ref(target) => target.clone(),
+            _ if self.view == View::Schemas => self.active_tree().selected_chain()[0].name.clone(),
+            _ => {
+                self.status_message = Some(format!("{} is not a component schema", node.name));
+                return;
+            }
+        };
+        let Some(schema) = self.spec.schema_nodes.iter().position(|s| s.name == name) else {
+            self.status_message = Some(format!("no schema named {name}"));
+            return;
+        };
+        if self.spec.usages[schema].is_empty() {
+            self.status_message = Some(format!("{name} is not used anywhere"));
+            return;
+        }
+        self.usages = Some(UsageList {
+            schema,
+            selected: 0,
+        });
+    }
+
+    pub fn usages_move(&mut self, down: bool) {
+        if let Some(list) = self.usages.as_mut() {
+            let last = self.spec.usages[list.schema].len().saturating_sub(1);
+            list.selected = if down {
+                (list.selected + 1).min(last)
+            } else {
+                list.selected.saturating_sub(1)
+            };
+        }
+    }
+
+    /// `Enter` in the popup: jump to the selected use.
+    pub fn open_usage(&mut self) {
+        let Some(usage) = self
+            .usages
+            .take()
+            .and_then(|list| self.spec.usages[list.schema].get(list.selected))
+        else {
+            return;
+        };
+        let (view, path) = (usage.view, usage.path.clone());
+        self.push_jump();
+        self.view = view;
         self.detail_scroll = 0;
+        self.active_tree_mut().select_path(&path);
+    }
+
+    // ── payload validation ───────────────────────────────────────────────────
+
+    /// `V`: ask for a payload to check against the selected schema.
+    pub fn start_validate(&mut self) {
+        let Some(node) = self.active_tree().selected_node() else {
+            return;
+        };
+        if !node.info.kind.is_schema() {
+            self.status_message = Some(format!("{} is not a schema", node.name));
+            return;
+        }
+        self.validate_prompt = Some(String::new());
+    }
+
+    pub fn validate_push(&mut self, c: char) {
+        if let Some(input) = self.validate_prompt.as_mut() {
+            input.push(c);
+        }
+    }
+
+    /// Backspace in the prompt; on empty input it closes the prompt.
+    pub fn validate_pop(&mut self) {
+        match self.validate_prompt.as_mut() {
+            Some(input) if !input.is_empty() => {
+                input.pop();
+            }
+            _ => self.validate_prompt = None,
+        }
+    }
+
+    /// `Enter`: read the payload (the clipboard when no file was given), check
+    /// it, and list what is wrong with it.
+    pub fn confirm_validate(&mut self) {
+        let Some(input) = self.validate_prompt.take() else {
+            return;
+        };
+        let path = input.trim();
+        let (source, text) = if path.is_empty() {
+            ("clipboard".to_string(), clipboard::paste())
+        } else {
+            (path.to_string(), std::fs::read_to_string(path))
+        };
+        let text = match text {
+            Ok(text) => text,
+            Err(e) => {
+                self.status_message = Some(format!("cannot read {source}: {e}"));
+                return;
+            }
+        };
+        let value: serde_json::Value = match serde_json::from_str(&text) {
+            Ok(value) => value,
+            Err(e) => {
+                self.status_message = Some(format!("{source} is not valid JSON: {e}"));
+                return;
+            }
+        };
+        let pointer = self.selected_pointer().unwrap_or_default();
+        let Some(node) = self.active_tree().selected_node() else {
+            return;
+        };
+        let violations = validate::validate(&value, node, &self.spec, &pointer);
+        let schema = node.name.clone();
+        if violations.is_empty() {
+            self.status_message = Some(format!("{source} is a valid {schema}"));
+            return;
+        }
+        self.validation = Some(Validation {
+            schema,
+            source,
+            violations,
+            selected: 0,
+        });
+    }
+
+    pub fn validation_move(&mut self, down: bool) {
+        if let Some(validation) = self.validation.as_mut() {
+            let last = validation.violations.len().saturating_sub(1);
+            validation.selected = if down {
+                (validation.selected + 1).min(last)
+            } else {
+                validation.selected.saturating_sub(1)
+            };
+        }
+    }
+
+    // ── ref following + jump list ────────────────────────────────────────────
+
+    /// `gd`: jump from a `$ref` node to the top-level definition of its target,
+    /// expanding it, and remember where we came from.
+    pub fn follow_ref(&mut self) {
+        let Some(node) = self.active_tree().selected_node() else {
+            return;
+        };
+        let NodeKind::Ref(target) = &node.info.kind else {
+            self.status_message = Some(format!("{} is not a $ref", node.name));
+            return;
+        };
+        let Some(root) = self.tree.root_index(target) else {
+            self.status_message = Some(format!("no schema named {target}"));
+            return;
+        };
+
+        self.push_jump();
+        self.view = View::Schemas;
+        self.tree.select_path(&[root]);
+        self.tree.expand_at_cursor();
+        self.detail_scroll = 0;
+    }
+
+    /// `Ctrl-o`: go back to the previous position in the jump list.
+    pub fn jump_back(&mut self) {
+        if self.jump_index == 0 {
+            return;
+        }
+        if self.jump_index == self.jump_list.len()
+            && let Some(here) = self.cursor_state()
+        {
+            // Remember the current position so Ctrl-i can come back to it.
+            self.jump_list.push(here);
+        }
+        self.jump_index -= 1;
+        self.restore(self.jump_list[self.jump_index].clone());
+    }
+
+    /// `Ctrl-i`: go forward again after `jump_back`.
+    pub fn jump_forward(&mut self) {
+        if self.jump_index + 1 >= self.jump_list.len() {
+            return;
+        }
+        self.jump_index += 1;
+        self.restore(self.jump_list[self.jump_index].clone());
+    }
+
+    fn cursor_state(&self) -> Option<CursorState> {
+        Some(CursorState {
+            view: self.view,
+            path: self.active_tree().cursor_path()?,
+            detail_scroll: self.detail_scroll,
+        })
+    }
+
+    /// Record the current position as a new jump, dropping any forward history
+    /// (same as nvim's jump list).
+    fn push_jump(&mut self) {
+        if let Some(here) = self.cursor_state() {
+            self.push_jump_state(here);
+        }
+    }
+
+    fn push_jump_state(&mut self, here: CursorState) {
+        self.jump_list.truncate(self.jump_index);
+        self.jump_list.push(here);
+        self.jump_index = self.jump_list.len();
+    }
+
+    fn restore(&mut self, state: CursorState) {
+        self.view = state.view;
+        self.active_tree_mut().select_path(&state.path);
+        self.detail_scroll = state.detail_scroll;
+    }
+
+    // ── `/` search ───────────────────────────────────────────────────────────
+
+    /// `/`: open the search prompt, remembering where we started.
+    pub fn start_search(&mut self) {
+        let tree = self.active_tree();
+        let origin = tree.cursor_path().unwrap_or_default();
+        self.search = Some(SearchState::new(
+            self.view,
+            origin,
+            self.detail_scroll,
+            tree.expanded_paths(),
+        ));
+    }
+
+    pub fn search_push(&mut self, c: char) {
+        if let Some(search) = self.search.as_mut() {
+            search.query.push(c);
+        }
+        self.refresh_search();
+    }
+
+    /// Backspace in the prompt; on an empty query it cancels, like nvim.
+    pub fn search_pop(&mut self) {
+        match self.search.as_mut() {
+            Some(search) if !search.query.is_empty() => {
+                search.query.pop();
+                self.refresh_search();
+            }
+            _ => self.cancel_search(),
+        }
+    }
+
+    /// `Enter`: close the prompt, keep the matches for `n` / `N`, and record the
+    /// starting point in the jump list.
+    pub fn confirm_search(&mut self) {
+        let Some(search) = self.search.as_mut() else {
+            return;
+        };
+        search.typing = false;
+        let origin = CursorState {
+            view: search.view,
+            path: search.origin.clone(),
+            detail_scroll: search.origin_scroll,
+        };
+        if search.matches.is_empty() {
+            self.status_message = Some(format!("pattern not found: {}", search.query));
+            self.search = None;
+        } else if origin.path != self.active_tree().cursor_path().unwrap_or_default() {
+            self.push_jump_state(origin);
+        }
+    }
+
+    /// `Esc` in the prompt: put the cursor and expansion state back.
+    pub fn cancel_search(&mut self) {
+        let Some(search) = self.search.take() else {
+            return;
+        };
+        if search.typing {
+            let tree = self.active_tree_mut();
+            tree.restore_expanded(&search.origin_expanded);
+            tree.select_path(&search.origin);
+            self.detail_scroll = search.origin_scroll;
+        }
+    }
+
+    /// `n` / `N`: move to the next / previous match of the last search.
+    pub fn next_match(&mut self, forward: bool) {
+        let Some(search) = self.search.as_mut().filter(|s| s.view == self.view) else {
+            return;
+        };
+        search.step(forward);
+        let Some(path) = search.current_match().map(|m| m.path.clone()) else {
+            return;
+        };
+        let count = format!(
+            "[{}/{}] /{}",
+            search.current + 1,
+            search.matches.len(),
+            search.query
+        );
+        self.push_jump();
+        self.active_tree_mut().select_path(&path);
+        self.detail_scroll = 0;
+        self.status_message = Some(count);
+    }
+
+    /// Incremental search: each keystroke starts again from the original cursor
+    /// and expansion state, then reveals the first match at or after it.
+    fn refresh_search(&mut self) {
+        let Some(mut search) = self.search.take() else {
+            return;
+        };
+        let tree = self.active_tree_mut();
+        tree.restore_expanded(&search.origin_expanded);
+        search.update(&tree.roots, &search.origin.clone());
+        let target = search
+            .current_match()
+            .map(|m| m.path.clone())
+            .unwrap_or_else(|| search.origin.clone());
+        tree.select_path(&target);
+        self.detail_scroll = 0;
+        self.search = Some(search);
+    }
+
+    // ── filter ───────────────────────────────────────────────────────────────
+
+    /// The active tree's filter query, if one is set.
+    pub fn filter_query(&self) -> Option<&str> {
+        self.active_tree().filter.as_ref().map(|f| f.query.as_str())
+    }
+
+    /// `f`: open the filter prompt, editing the current filter if there is one.
+    pub fn start_filter(&mut self) {
+        self.search = None;
+        self.filter_typing = true;
+    }
+
+    pub fn filter_push(&mut self, c: char) {
+        let mut query = self.filter_query().unwrap_or_default().to_string();
+        query.push(c);
+        self.active_tree_mut().set_filter(&query);
+        self.detail_scroll = 0;
+    }
+
+    /// Backspace in the prompt; on an empty query it closes the prompt.
+    pub fn filter_pop(&mut self) {
+        let Some(mut query) = self.filter_query().map(str::to_string) else {
+            self.filter_typing = false;
+            return;
+        };
+        query.pop();
+        self.active_tree_mut().set_filter(&query);
+        self.detail_scroll = 0;
+    }
+
+    /// `Enter`: close the prompt and keep the filter applied.
+    pub fn confirm_filter(&mut self) {
+        self.filter_typing = false;
+    }
+
+    /// `Esc` in the prompt, or `F`: drop the filter and show the full tree again.
+    pub fn clear_filter(&mut self) {
+        self.filter_typing = false;
+        self.active_tree_mut().set_filter("");
+    }
+
+    // ── location ─────────────────────────────────────────────────────────────
+
+    /// JSON Pointer to where the selected node is defined in the document.
+    pub fn selected_pointer(&self) -> Option<String> {
+        let tree = self.active_tree();
+        let path = tree.cursor_path()?;
+        breadcrumb::json_pointer(&self.spec, self.view, &tree.selected_chain(), &path)
+    }
+
+    /// `yp`: copy the selected node's JSON Pointer to the clipboard.
+    pub fn yank_pointer(&mut self) {
+        let Some(pointer) = self.selected_pointer() else {
+            return;
+        };
+        self.status_message = Some(match clipboard::copy(&pointer) {
+            Ok(()) => format!("copied {pointer}"),
+            Err(e) => format!("copy failed: {e}"),
+        });
+    }
+
+    /// `ye`: copy an example payload for the selected schema to the clipboard.
+    pub fn yank_example(&mut self) {
+        let Some(node) = self.active_tree().selected_node() else {
+            return;
+        };
+        let Some(example) = example::generate(node, &self.spec.schema_nodes) else {
+            self.status_message = Some(format!("{} is not a schema", node.name));
+            return;
+        };
+        let name = node.name.clone();
+        let json = serde_json::to_string_pretty(&example).unwrap_or_default();
+        self.status_message = Some(match clipboard::copy(&json) {
+            Ok(()) => format!("copied example {name}"),
+            Err(e) => format!("copy failed: {e}"),
+        });
     }
 
     pub fn scroll_detail_down(&mut self) {
@@ -86,3 +876,271 @@ impl App {
         self.detail_scroll = self.detail_scroll.saturating_sub(3);
     }
 }
+
+// ── tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PETSTORE: &str = include_str!("../fixtures/petstore.yaml");
+
+    fn petstore_app() -> App {
+        App::new(crate::parser::parse_spec(PETSTORE).expect("should parse petstore fixture"))
+    }
+
+    fn selected_name(app: &App) -> String {
+        app.active_tree().selected_node().unwrap().name.clone()
+    }
+
+    #[test]
+    fn follow_ref_jumps_to_target_root() {
+        let mut app = petstore_app();
+        app.tree.select_path(&[3, 0]); // Pets › items →Pet
+        app.follow_ref();
+        assert_eq!(selected_name(&app), "Pet");
+        assert!(app.tree.selected_node().unwrap().expanded);
+        assert_eq!(app.jump_list.len(), 1);
+    }
+
+    #[test]
+    fn jump_back_and_forward_walk_history() {
+        let mut app = petstore_app();
+        app.tree.select_path(&[3, 0]);
+        app.follow_ref();
+
+        app.jump_back();
+        assert_eq!(selected_name(&app), "items");
+        app.jump_forward();
+        assert_eq!(selected_name(&app), "Pet");
+        // Nothing further forward.
+        app.jump_forward();
+        assert_eq!(selected_name(&app), "Pet");
+    }
+
+    #[test]
+    fn follow_ref_from_operations_view_switches_to_schemas() {
+        let mut app = petstore_app();
+        app.toggle_view();
+        app.op_tree.select_path(&[0, 1, 0]); // pets › POST /pets › requestBody →NewPet
+        app.follow_ref();
+        assert_eq!(app.view, View::Schemas);
+        assert_eq!(selected_name(&app), "NewPet");
+
+        app.jump_back();
+        assert_eq!(app.view, View::Operations);
+        assert_eq!(selected_name(&app), "requestBody");
+    }
+
+    fn type_search(app: &mut App, query: &str) {
+        app.start_search();
+        for c in query.chars() {
+            app.search_push(c);
+        }
+    }
+
+    #[test]
+    fn search_reveals_collapsed_match_and_esc_restores() {
+        let mut app = petstore_app();
+        app.tree.select_path(&[3]); // Pets, collapsed
+        type_search(&mut app, "tag");
+        // Wraps past the cursor to the first match inside Pets › items → Pet.
+        assert_eq!(app.tree.cursor_path(), Some(vec![3, 0, 2]));
+
+        app.cancel_search();
+        assert_eq!(selected_name(&app), "Pets");
+        assert!(app.tree.expanded_paths().is_empty());
+    }
+
+    #[test]
+    fn confirmed_search_cycles_with_n() {
+        let mut app = petstore_app();
+        type_search(&mut app, "tag");
+        app.confirm_search();
+        assert_eq!(app.tree.cursor_path(), Some(vec![1, 1]));
+        app.next_match(true);
+        assert_eq!(app.tree.cursor_path(), Some(vec![2, 2]));
+        app.next_match(false);
+        app.next_match(false);
+        assert_eq!(app.tree.cursor_path(), Some(vec![3, 0, 2]));
+
+        // Each `n` / `N` is a jump, as in nvim.
+        app.jump_back();
+        assert_eq!(app.tree.cursor_path(), Some(vec![1, 1]));
+    }
+
+    #[test]
+    fn filter_prompt_edits_the_active_tree_filter() {
+        let mut app = petstore_app();
+        app.start_filter();
+        for c in "tagx".chars() {
+            app.filter_push(c);
+        }
+        assert!(app.tree.filter.as_ref().unwrap().matches.is_empty());
+        app.filter_pop();
+        app.confirm_filter();
+        assert_eq!(app.filter_query(), Some("tag"));
+        assert_eq!(app.tree.visible_count(), 7);
+        // The operations view keeps its own, unfiltered tree.
+        app.toggle_view();
+        assert_eq!(app.filter_query(), None);
+        app.toggle_view();
+        app.clear_filter();
+        assert_eq!(app.tree.visible_count(), 4);
+    }
+
+    #[test]
+    fn opening_a_service_replaces_the_trees() {
+        let mut app = App::with_catalog(crate::catalog::tests::fixture_catalog("app"));
+        assert_eq!(app.view, View::Services);
+        app.service_tree.select_path(&[0, 0]); // retail / pets › pets-v2
+        app.toggle_expand();
+        assert_eq!(app.view, View::Schemas);
+        assert_eq!(app.spec.version, crate::spec::SpecVersion::V20);
+        assert_eq!(app.catalog.as_ref().unwrap().active, Some(0));
+
+        app.tree.select_path(&[1]);
+        app.switch_service();
+        assert_eq!(app.view, View::Services);
+        app.move_down(); // pets-v3
+        app.toggle_expand();
+        assert_eq!(app.spec.version, crate::spec::SpecVersion::V30);
+        assert!(app.jump_list.is_empty());
+        assert_eq!(app.tree.cursor, 0);
+    }
+
+    #[test]
+    fn global_search_opens_the_hit_in_its_service() {
+        let mut app = App::with_catalog(crate::catalog::tests::fixture_catalog("global"));
+        app.start_global_search();
+        for c in "tag".chars() {
+            app.global_push(c);
+        }
+        let global = app.global.as_ref().unwrap();
+        let services: std::collections::HashSet<&str> =
+            global.hits.iter().map(|h| h.service.as_str()).collect();
+        assert!(services.len() >= 2, "{services:?}");
+        let hit = global.hits[0].clone();
+        app.open_global_hit();
+
+        let catalog = app.catalog.as_ref().unwrap();
+        assert_eq!(catalog.services[catalog.active.unwrap()].name, hit.service);
+        assert_eq!(app.view, View::Schemas);
+        assert_eq!(selected_name(&app), "tag");
+        let chain = app.tree.selected_chain();
+        assert_eq!(chain[0].name, hit.schema_name);
+        assert!(app.global.is_none());
+    }
+
+    #[test]
+    fn diagnostics_panel_jumps_to_the_node() {
+        let spec = crate::parser::parse_spec(concat!(
+            "openapi: 3.0.3\ninfo: {title: t, version: '1'}\npaths: {}\n",
+            "components:\n  schemas:\n    A: {type: string}\n",
+            "    B:\n      type: object\n      properties:\n",
+            "        c: {$ref: '#/components/schemas/C'}\n",
+        ))
+        .unwrap();
+        let mut app = App::new(spec);
+        app.perform(Action::Diagnostics);
+        assert_eq!(app.diagnostics_panel, Some(0));
+        app.open_diagnostic();
+        assert_eq!(app.diagnostics_panel, None);
+        assert_eq!(app.active_tree().cursor_path(), Some(vec![1, 0]));
+        app.jump_back();
+        assert_eq!(selected_name(&app), "A");
+
+        let mut clean = petstore_app();
+        clean.perform(Action::Diagnostics);
+        assert_eq!(clean.diagnostics_panel, None);
+        assert_eq!(clean.status_message.as_deref(), Some("no diagnostics"));
+    }
+
+    #[test]
+    fn usages_popup_jumps_to_the_use() {
+        let mut app = petstore_app();
+        app.tree.select_path(&[3, 0]); // Pets › items →Pet
+        app.perform(Action::Usages);
+        let list = app.usages.as_ref().unwrap();
+        assert_eq!(app.spec.schema_nodes[list.schema].name, "Pet");
+        let count = app.spec.usages[list.schema].len();
+        for _ in 0..count + 1 {
+            app.usages_move(true);
+        }
+        assert_eq!(app.usages.as_ref().unwrap().selected, count - 1);
+
+        let usage = app.spec.usages[app.usages.as_ref().unwrap().schema][count - 1].clone();
+        app.open_usage();
+        assert!(app.usages.is_none());
+        assert_eq!(app.view, usage.view);
+        assert_eq!(app.active_tree().cursor_path(), Some(usage.path));
+        app.jump_back();
+        assert_eq!(selected_name(&app), "items");
+
+        app.view = View::Operations;
+        app.op_tree.select_path(&[0]); // a tag
+        app.perform(Action::Usages);
+        assert!(app.usages.is_none());
+        assert!(
+            app.status_message
+                .unwrap()
+                .ends_with("is not a component schema")
+        );
+    }
+
+    #[test]
+    fn follow_ref_on_non_ref_reports_status() {
+        let mut app = petstore_app();
+        app.follow_ref();
+        assert!(app.status_message.is_some());
+        assert!(app.jump_list.is_empty());
+    }
+
+    #[test]
+    fn validate_prompt_lists_violations() {
+        let dir = std::env::temp_dir().join(format!("speq-validate-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let payload = |name: &str, json: &str| {
+            let path = dir.join(name);
+            std::fs::write(&path, json).unwrap();
+            path.to_string_lossy().into_owned()
+        };
+        let bad = payload("bad.json", r#"[{"id": "one"}]"#);
+        let good = payload("good.json", r#"[{"id": 1, "name": "Rex"}]"#);
+
+        let mut app = petstore_app();
+        app.tree.select_path(&[3]); // Pets
+        for path in [&bad, &good] {
+            app.perform(Action::Validate);
+            assert_eq!(app.validate_prompt.as_deref(), Some(""));
+            path.chars().for_each(|c| app.validate_push(c));
+            app.confirm_validate();
+        }
+        assert_eq!(app.status_message, Some(format!("{good} is a valid Pets")));
+        let validation = app
+            .validation
+            .as_ref()
+            .expect("the bad payload opens the panel");
+        let found: Vec<(&str, &str, &str)> = validation
+            .violations
+            .iter()
+            .map(|v| (v.pointer.as_str(), v.schema.as_str(), v.message.as_str()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (
+                    "/0/id",
+                    "#/components/schemas/Pet/properties/id",
+                    "expected integer, got string"
+                ),
+                (
+                    "/0",
+                    "#/components/schemas/Pet/properties/name",
+                    "missing required property name"
+                ),
+            ]
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,378 @@
+use crate::app::View;
+use crate::spec::{LoadedSpec, SpecVersion};
+use crate::tree::{NodeKind, TreeNode};
+
+/// Human-readable location of the selected node, root first:
+/// `Pets › items → Pet › id`.
+pub fn label(chain: &[&TreeNode]) -> String {
+    chain
+        .iter()
+        .map(|node| match &node.info.kind {
+            NodeKind::Ref(target) => format!("{} → {}", node.name, target),
+            _ => node.name.clone(),
+        })
+        .collect::<Vec<_>>()
+        .join(" › ")
+}
+
+/// JSON Pointer (as a URI fragment) to where the selected node is defined.
+///
+/// `chain` is the selected node and its ancestors, `path` the matching child
+/// indices. Passing through a `$ref` restarts the pointer at the ref target, so
+/// `Pets › items → Pet › id` points at `#/components/schemas/Pet/properties/id`.
+pub fn json_pointer(
+    spec: &LoadedSpec,
+    view: View,
+    chain: &[&TreeNode],
+    path: &[usize],
+) -> Option<String> {
+    match view {
+        View::Schemas => {
+            let (root, rest) = chain.split_first()?;
+            Some(descend(spec, schema_pointer(spec, &root.name), root, rest))
+        }
+        View::Operations => operation_pointer(spec, chain, path),
+        // The service list is not part of any document.
+        View::Services => None,
+    }
+}
+
+/// Escape one reference token (RFC 6901): `~` → `~0`, `/` → `~1`.
+pub fn escape(token: &str) -> String {
+    token.replace('~', "~0").replace('/', "~1")
+}
+
+/// Where a top-level schema is defined; for one imported from another file,
+/// its location there (`common.yaml#/components/schemas/Money`).
+fn schema_pointer(spec: &LoadedSpec, name: &str) -> String {
+    if let Some(source) = spec.schema_sources.get(name) {
+        return source.clone();
+    }
+    // A 3.1 `$defs` entry is named by its path below the schemas, already escaped.
+    if spec.version == SpecVersion::V31 && name.contains("/$defs/") {
+        return format!("#/components/schemas/{name}");
+    }
+    let prefix = match spec.version {
+        SpecVersion::V20 => "#/definitions",
+        SpecVersion::V30 | SpecVersion::V31 => "#/components/schemas",
+    };
+    format!("{prefix}/{}", escape(name))
+}
+
+/// The file the selected node is defined in, when that is not the spec itself:
+/// the target of the nearest `$ref` at or above it, else its root schema.
+pub fn source_file<'a>(spec: &'a LoadedSpec, chain: &[&TreeNode]) -> Option<&'a str> {
+    let (last, ancestors) = chain.split_last()?;
+    let defining = std::iter::once(*last)
+        .filter(|n| matches!(n.info.kind, NodeKind::Ref(_)))
+        .chain(ancestors.iter().rev().copied())
+        .find_map(|n| match &n.info.kind {
+            NodeKind::Ref(target) => Some(target.as_str()),
+            _ => None,
+        })
+        .unwrap_or(chain[0].name.as_str());
+    let source = spec.schema_sources.get(defining)?;
+    Some(
+        source
+            .split_once('#')
+            .map_or(source.as_str(), |(file, _)| file),
+    )
+}
+
+/// Operations view chains are `tag › METHOD /path › section › ...`. Each
+/// parameter, body and response knows where its schema is written, which the
+/// `$ref`s, path-level parameters and Swagger 2.0 layout make hard to rebuild.
+fn operation_pointer(spec: &LoadedSpec, chain: &[&TreeNode], path: &[usize]) -> Option<String> {
+    let op_node = chain.get(1)?;
+    let NodeKind::Operation(index) = op_node.info.kind else {
+        return None;
+    };
+    let op = spec.operations.get(index)?;
+    let op_pointer = format!("#/paths/{}/{}", escape(&op.path), op.method);
+
+    let Some(section) = chain.get(2) else {
+        return Some(op_pointer);
+    };
+    let (pointer, schema_node, rest) = match &section.info.kind {
+        NodeKind::Group(keyword) if keyword == "parameters" => {
+            let param = op.parameters.get(*path.get(3)?)?;
+            (param.pointer.clone(), *chain.get(3)?, &chain[4..])
+        }
+        NodeKind::Group(keyword) if keyword == "responses" => {
+            let resp_node = *chain.get(3)?;
+            let resp = op.responses.iter().find(|r| r.status == resp_node.name)?;
+            (resp.pointer.clone(), resp_node, &chain[4..])
+        }
+        _ => match op.request_body.as_ref()?.pointer.clone() {
+            Some(pointer) => (pointer, *section, &chain[3..]),
+            // Swagger 2.0 form fields are parameters of their own; point at the list.
+            None => return Some(format!("{op_pointer}/parameters")),
+        },
+    };
+    Some(descend(spec, pointer, schema_node, rest))
+}
+
+/// Keyword children a 3.1 object node can carry alongside its properties.
+const OBJECT_KEYWORDS: [&str; 13] = [
+    "additionalProperties",
+    "unevaluatedProperties",
+    "propertyNames",
+    "allOf",
+    "oneOf",
+    "anyOf",
+    "if",
+    "then",
+    "else",
+    "not",
+    "dependentSchemas",
+    "patternProperties",
+    "$defs",
+];
+
+/// Append one segment per step below `parent`, restarting at the target
+/// definition whenever a step leaves a `Ref` node.
+pub fn descend(
+    spec: &LoadedSpec,
+    mut pointer: String,
+    parent: &TreeNode,
+    rest: &[&TreeNode],
+) -> String {
+    let mut parent = parent;
+    for child in rest {
+        let mut kind = &parent.info.kind;
+        let mut parent_name = parent.name.as_str();
+        if let NodeKind::Ref(target) = kind {
+            pointer = schema_pointer(spec, target);
+            if let Some(root) = spec.schema_nodes.iter().find(|r| r.name == *target) {
+                kind = &root.info.kind;
+                parent_name = root.name.as_str();
+            }
+        }
+        pointer.push_str(&segment(spec, kind, parent_name, &child.name));
+        parent = child;
+    }
+    pointer
+}
+
+fn segment(spec: &LoadedSpec, parent_kind: &NodeKind, parent_name: &str, child: &str) -> String {
+    let index = child
+        .strip_prefix('[')
+        .and_then(|c| c.strip_suffix(']'))
+        .filter(|i| i.parse::<usize>().is_ok());
+
+    match parent_kind {
+        NodeKind::Object => {
+            // Only 3.1 objects mix keyword children in with their properties;
+            // a 3.0 object has at most an `additionalProperties` schema.
+            if child == "additionalProperties"
+                || (spec.version == SpecVersion::V31 && OBJECT_KEYWORDS.contains(&child))
+            {
+                format!("/{child}")
+            } else {
+                format!("/properties/{}", escape(child))
+            }
+        }
+        NodeKind::Tuple => match index {
+            Some(i) => format!("/prefixItems/{i}"),
+            None => format!("/{}", escape(child)),
+        },
+        NodeKind::AllOf | NodeKind::OneOf | NodeKind::AnyOf => {
+            let keyword = match parent_kind {
+                NodeKind::AllOf => "allOf",
+                NodeKind::OneOf => "oneOf",
+                _ => "anyOf",
+            };
+            match index {
+                // A combiner grouped under an object already contributed `/allOf`.
+                Some(i) if parent_name == keyword => format!("/{i}"),
+                Some(i) => format!("/{keyword}/{i}"),
+                None => format!("/{}", escape(child)),
+            }
+        }
+        _ => format!("/{}", escape(child)),
+    }
+}
+
+// ── tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_spec;
+
+    const PETSTORE: &str = include_str!("../fixtures/petstore.yaml");
+    const PETSTORE_V2: &str = include_str!("../fixtures/petstore-v2.yaml");
+
+    fn locate(spec: &LoadedSpec, view: View, path: &[usize]) -> (String, Option<String>) {
+        let roots = match view {
+            View::Schemas => &spec.schema_nodes,
+            View::Operations => &spec.operation_nodes,
+            View::Services => unreachable!(),
+        };
+        let chain = crate::tree::chain_at(roots, path);
+        (label(&chain), json_pointer(spec, view, &chain, path))
+    }
+
+    #[test]
+    fn breadcrumb_through_ref() {
+        let spec = parse_spec(PETSTORE).unwrap();
+        // Pets › items → Pet › id
+        let (label, pointer) = locate(&spec, View::Schemas, &[3, 0, 0]);
+        assert_eq!(label, "Pets › items → Pet › id");
+        assert_eq!(
+            pointer.as_deref(),
+            Some("#/components/schemas/Pet/properties/id")
+        );
+    }
+
+    #[test]
+    fn pointer_to_ref_node_itself() {
+        let spec = parse_spec(PETSTORE).unwrap();
+        let (_, pointer) = locate(&spec, View::Schemas, &[3, 0]);
+        assert_eq!(pointer.as_deref(), Some("#/components/schemas/Pets/items"));
+    }
+
+    #[test]
+    fn swagger_pointers_use_definitions() {
+        let spec = parse_spec(PETSTORE_V2).unwrap();
+        let (_, pointer) = locate(&spec, View::Schemas, &[2, 1]);
+        assert_eq!(
+            pointer.as_deref(),
+            Some("#/definitions/Pet/properties/name")
+        );
+    }
+
+    #[test]
+    fn operation_pointers() {
+        let spec = parse_spec(PETSTORE).unwrap();
+        // pets › GET /pets › parameters › limit
+        let (label, pointer) = locate(&spec, View::Operations, &[0, 0, 0, 0]);
+        assert_eq!(label, "pets › GET /pets › parameters › limit");
+        assert_eq!(
+            pointer.as_deref(),
+            Some("#/paths/~1pets/get/parameters/0/schema")
+        );
+
+        // pets › GET /pets › responses › 200 → Pets
+        let (_, pointer) = locate(&spec, View::Operations, &[0, 0, 1, 0]);
+        assert_eq!(
+            pointer.as_deref(),
+            Some("#/paths/~1pets/get/responses/200/content/application~1json/schema")
+        );
+
+        // pets › POST /pets › requestBody → NewPet › name
+        let (_, pointer) = locate(&spec, View::Operations, &[0, 1, 0, 0]);
+        assert_eq!(
+            pointer.as_deref(),
+            Some("#/components/schemas/NewPet/properties/name")
+        );
+    }
+
+    #[test]
+    fn operation_pointers_follow_where_parameters_are_written() {
+        let spec = parse_spec(
+            r##"
+openapi: 3.0.3
+info: { title: t, version: "1" }
+paths:
+  /orders/{id}:
+    parameters:
+      - { name: id, in: path, required: true, schema: { type: string } }
+      - { name: trace, in: header, schema: { type: string } }
+    get:
+      tags: [orders]
+      parameters:
+        - { name: trace, in: header, schema: { type: integer } }
+        - $ref: "#/components/parameters/Page"
+        - name: filter
+          in: query
+          content: { application/json: { schema: { type: object } } }
+      requestBody: { $ref: "#/components/requestBodies/Order" }
+      responses:
+        "404": { $ref: "#/components/responses/NotFound" }
+components:
+  parameters:
+    Page: { name: page, in: query, schema: { type: integer } }
+  requestBodies:
+    Order:
+      content: { application/json: { schema: { type: object, properties: { note: { type: string } } } } }
+  responses:
+    NotFound: { description: gone }
+"##,
+        )
+        .unwrap();
+        let pointer = |path: &[usize]| locate(&spec, View::Operations, path).1.unwrap();
+        assert_eq!(
+            pointer(&[0, 0, 0, 0]),
+            "#/paths/~1orders~1{id}/parameters/0/schema"
+        );
+        assert_eq!(
+            pointer(&[0, 0, 0, 1]),
+            "#/paths/~1orders~1{id}/get/parameters/0/schema"
+        );
+        assert_eq!(
+            pointer(&[0, 0, 0, 2]),
+            "#/components/parameters/Page/schema"
+        );
+        assert_eq!(
+            pointer(&[0, 0, 0, 3]),
+            "#/paths/~1orders~1{id}/get/parameters/2/content/application~1json/schema"
+        );
+        assert_eq!(
+            pointer(&[0, 0, 1, 0]),
+            "#/components/requestBodies/Order/content/application~1json/schema/properties/note"
+        );
+        assert_eq!(pointer(&[0, 0, 2, 0]), "#/components/responses/NotFound");
+    }
+
+    #[test]
+    fn swagger_operation_pointers() {
+        let spec = parse_spec(PETSTORE_V2).unwrap();
+        // Every operations-tree path down to the responses' children.
+        let mut paths = vec![vec![0]];
+        for depth in 1..4 {
+            for path in paths.clone().iter().filter(|p| p.len() == depth) {
+                let node = *crate::tree::chain_at(&spec.operation_nodes, path)
+                    .last()
+                    .unwrap();
+                paths.extend((0..node.children.len()).map(|i| [path.as_slice(), &[i]].concat()));
+            }
+        }
+        let pointer = |label: &str| {
+            let path = paths
+                .iter()
+                .find(|path| locate(&spec, View::Operations, path).0 == label)
+                .unwrap();
+            locate(&spec, View::Operations, path).1.unwrap()
+        };
+        assert_eq!(
+            pointer("pets › GET /pets › parameters › limit"),
+            "#/parameters/limitParam"
+        );
+        assert_eq!(
+            pointer("pets › POST /pets › requestBody → NewPet"),
+            "#/paths/~1pets/post/parameters/0/schema"
+        );
+        assert_eq!(
+            pointer("pets › POST /pets › responses › 201"),
+            "#/paths/~1pets/post/responses/201"
+        );
+        assert_eq!(
+            pointer("pets › POST /pets › responses › 404 → Error"),
+            "#/responses/NotFound/schema"
+        );
+        assert_eq!(
+            pointer("pets › GET /pets/{petId} › parameters › petId"),
+            "#/parameters/petIdParam"
+        );
+        assert_eq!(
+            pointer("pets › GET /pets/{petId} › responses › 200 → Pet"),
+            "#/paths/~1pets~1{petId}/get/responses/200/schema"
+        );
+    }
+
+    #[test]
+    fn escapes_tokens() {
+        assert_eq!(escape("a/b~c"), "a~1b~0c");
+    }
+}
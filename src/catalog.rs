@@ -0,0 +1,356 @@
+use std::path::Path;
+
+use anyhow::{Context, Result, bail};
+use rusqlite::{Connection, params};
+use serde_json::{Map, Value, json};
+
+use crate::db;
+use crate::fts::{self, Hit};
+use crate::parser::parse_spec;
+use crate::spec::LoadedSpec;
+use crate::tree::{NodeInfo, NodeKind, TreeNode};
+
+/// One row of `services`, as shown in the service list.
+#[derive(Debug, Clone)]
+pub struct ServiceInfo {
+    pub id: i64,
+    pub name: String,
+    pub repo: Option<String>,
+    pub team: Option<String>,
+    pub domain: Option<String>,
+    pub cached_at: Option<String>,
+    pub indexed_at: Option<String>,
+    pub openapi_version: Option<String>, // "2.0", "3.0", "3.1"
+    pub spec_title: Option<String>,
+    pub spec_version: Option<String>,
+    pub schema_count: usize,
+    pub path_count: usize,
+}
+
+impl ServiceInfo {
+    /// Service list grouping: `domain / team`, whichever of the two are set.
+    pub fn group(&self) -> String {
+        let parts: Vec<&str> = [self.domain.as_deref(), self.team.as_deref()]
+            .into_iter()
+            .flatten()
+            .collect();
+        if parts.is_empty() {
+            "ungrouped".to_string()
+        } else {
+            parts.join(" / ")
+        }
+    }
+}
+
+/// The indexed services in `index.db`, for multi-service browsing. Only the
+/// service list is read up front; a service's schemas are loaded when opened.
+pub struct Catalog {
+    conn: Connection,
+    pub services: Vec<ServiceInfo>,
+    pub active: Option<usize>, // index into `services` of the open service
+}
+
+impl Catalog {
+    pub fn open(path: &Path) -> Result<Catalog> {
+        let conn = db::open(path)?;
+        Catalog::from_connection(conn)
+    }
+
+    pub fn from_connection(conn: Connection) -> Result<Catalog> {
+        let services = {
+            let mut stmt = conn.prepare(
+                "SELECT s.id, s.name, s.repo, s.team, s.domain, s.cached_at, s.indexed_at,
+                        s.openapi_version, s.spec_title, s.spec_version,
+                        (SELECT count(*) FROM schemas WHERE service_id = s.id),
+                        (SELECT count(*) FROM paths WHERE service_id = s.id)
+                 FROM services s
+                 ORDER BY s.name",
+            )?;
+            stmt.query_map([], |row| {
+                Ok(ServiceInfo {
+                    id: row.get(0)?,
+                    name: row.get(1)?,
+                    repo: row.get(2)?,
+                    team: row.get(3)?,
+                    domain: row.get(4)?,
+                    cached_at: row.get(5)?,
+                    indexed_at: row.get(6)?,
+                    openapi_version: row.get(7)?,
+                    spec_title: row.get(8)?,
+                    spec_version: row.get(9)?,
+                    schema_count: row.get::<_, i64>(10)? as usize,
+                    path_count: row.get::<_, i64>(11)? as usize,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?
+        };
+        if services.is_empty() {
+            bail!("the index is empty — run `speq index` first");
+        }
+        Ok(Catalog {
+            conn,
+            services,
+            active: None,
+        })
+    }
+
+    /// Left-pane tree for the service list: one node per group, services inside.
+    pub fn tree(&self) -> Vec<TreeNode> {
+        let mut groups: Vec<(String, Vec<TreeNode>)> = Vec::new();
+        for (index, service) in self.services.iter().enumerate() {
+            let group = service.group();
+            let mut node = plain_node(service.name.clone(), NodeKind::Service(index));
+            node.info.description = service.spec_title.clone();
+            match groups.iter_mut().find(|(g, _)| *g == group) {
+                Some((_, nodes)) => nodes.push(node),
+                None => groups.push((group, vec![node])),
+            }
+        }
+        groups.sort_by(|a, b| a.0.cmp(&b.0));
+
+        groups
+            .into_iter()
+            .map(|(group, children)| {
+                let mut node = plain_node(group, NodeKind::ServiceGroup);
+                node.children = children;
+                node.expanded = true;
+                node
+            })
+            .collect()
+    }
+
+    /// Full-text search over every service in the index.
+    pub fn search(&self, query: &str, limit: usize) -> Result<Vec<Hit>> {
+        fts::search(&self.conn, query, limit)
+    }
+
+    /// Index into `services` of the service called `name`.
+    pub fn position(&self, name: &str) -> Option<usize> {
+        self.services.iter().position(|s| s.name == name)
+    }
+
+    /// Load one service from its indexed `raw_json` schemas and operations.
+    pub fn load(&self, index: usize) -> Result<LoadedSpec> {
+        let service = &self.services[index];
+        let version = service.openapi_version.as_deref().unwrap_or("3.0");
+
+        let mut schemas = Map::new();
+        let mut stmt = self
+            .conn
+            .prepare("SELECT name, raw_json FROM schemas WHERE service_id = ?1 ORDER BY id")?;
+        let rows = stmt.query_map(params![service.id], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, Option<String>>(1)?))
+        })?;
+        for row in rows {
+            let (name, raw_json) = row?;
+            let schema = match raw_json {
+                Some(text) => serde_json::from_str(&text)
+                    .with_context(|| format!("bad raw_json for schema {name}"))?,
+                None => json!({}),
+            };
+            schemas.insert(name, schema);
+        }
+
+        let mut paths = Map::new();
+        let mut stmt = self.conn.prepare(
+            "SELECT path, method, operation_id, summary, description, tags, raw_json
+             FROM paths WHERE service_id = ?1 ORDER BY id",
+        )?;
+        let rows = stmt.query_map(params![service.id], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, Option<String>>(2)?,
+                row.get::<_, Option<String>>(3)?,
+                row.get::<_, Option<String>>(4)?,
+                row.get::<_, Option<String>>(5)?,
+                row.get::<_, Option<String>>(6)?,
+            ))
+        })?;
+        for row in rows {
+            let (path, method, operation_id, summary, description, tags, raw_json) = row?;
+            let item = paths
+                .entry(path)
+                .or_insert_with(|| Value::Object(Map::new()));
+            if let Some(op) = raw_json {
+                let op: Value = serde_json::from_str(&op)
+                    .with_context(|| format!("bad raw_json for {method} operation"))?;
+                if let Some(item) = item.as_object_mut() {
+                    item.insert(method, op);
+                }
+                continue;
+            }
+            // Operations the indexer could not find again keep their summary.
+            let mut op = Map::new();
+            if let Some(tags) = tags.and_then(|t| serde_json::from_str::<Value>(&t).ok()) {
+                op.insert("tags".into(), tags);
+            }
+            for (key, value) in [
+                ("operationId", operation_id),
+                ("summary", summary),
+                ("description", description),
+            ] {
+                if let Some(value) = value {
+                    op.insert(key.into(), Value::String(value));
+                }
+            }
+            op.insert("responses".into(), json!({}));
+            if let Some(item) = item.as_object_mut() {
+                item.insert(method, Value::Object(op));
+            }
+        }
+
+        let info = json!({
+            "title": service.spec_title.clone().unwrap_or_else(|| service.name.clone()),
+            "version": service.spec_version.clone().unwrap_or_default(),
+        });
+        let document = if version.starts_with('2') {
+            json!({ "swagger": "2.0", "info": info, "paths": paths, "definitions": schemas })
+        } else {
+            json!({
+                "openapi": format!("{version}.0"),
+                "info": info,
+                "paths": paths,
+                "components": { "schemas": schemas },
+            })
+        };
+        parse_spec(&document.to_string())
+            .with_context(|| format!("cannot load {} from the index", service.name))
+    }
+}
+
+fn plain_node(name: String, kind: NodeKind) -> TreeNode {
+    TreeNode {
+        name,
+        info: NodeInfo {
+            kind,
+            format: None,
+            description: None,
+            required: false,
+            constraints: Vec::new(),
+            enum_values: Vec::new(),
+            example: None,
+            default_val: None,
+            nullable: false,
+            examples: Vec::new(),
+        },
+        children: Vec::new(),
+        expanded: false,
+    }
+}
+
+// ── tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::indexer::{IndexOptions, index_all};
+    use std::fs;
+
+    /// Index the fixtures into an in-memory database and open it as a catalog.
+    /// `label` keeps the temp directories of tests running in parallel apart.
+    pub(crate) fn fixture_catalog(label: &str) -> Catalog {
+        let dir = std::env::temp_dir().join(format!("speq-catalog-{label}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        for (name, spec, team) in [
+            ("pets-v3", "petstore.yaml", "team: pets\ndomain: retail\n"),
+            (
+                "pets-v2",
+                "petstore-v2.yaml",
+                "team: pets\ndomain: retail\n",
+            ),
+            ("pets-v31", "petstore-3.1.yaml", ""),
+        ] {
+            let service_dir = dir.join(name);
+            fs::create_dir_all(&service_dir).unwrap();
+            fs::copy(
+                Path::new("fixtures").join(spec),
+                service_dir.join("openapi.yaml"),
+            )
+            .unwrap();
+            fs::write(
+                service_dir.join("meta.yaml"),
+                format!("service: {name}\nrepo: r\ncached_at: c\n{team}"),
+            )
+            .unwrap();
+        }
+
+        let mut conn = Connection::open_in_memory().unwrap();
+        db::init(&conn).unwrap();
+        let options = IndexOptions {
+            force: false,
+            service: None,
+        };
+        index_all(&mut conn, &dir, &options, &mut Vec::new()).unwrap();
+        fs::remove_dir_all(dir).unwrap();
+        Catalog::from_connection(conn).unwrap()
+    }
+
+    #[test]
+    fn loaded_operations_keep_parameters_bodies_and_responses() {
+        let catalog = fixture_catalog("operations");
+        for name in ["pets-v3", "pets-v2"] {
+            let spec = catalog.load(catalog.position(name).unwrap()).unwrap();
+            let op = |id: &str| {
+                spec.operations
+                    .iter()
+                    .find(|op| op.operation_id.as_deref() == Some(id))
+                    .unwrap()
+            };
+            let list = op("listPets");
+            assert_eq!(list.parameters[0].schema.name, "limit", "{name}");
+            assert_eq!(list.responses[0].status, "200");
+            assert!(
+                matches!(&list.responses[0].schema.as_ref().unwrap().info.kind, NodeKind::Ref(t) if t == "Pets")
+            );
+            let create = op("createPets");
+            let body = create.request_body.as_ref().unwrap();
+            assert!(
+                matches!(&body.schema.as_ref().unwrap().info.kind, NodeKind::Ref(t) if t == "NewPet")
+            );
+            // In the Swagger 2.0 fixture, a path-level `$ref` parameter.
+            assert_eq!(
+                op("showPetById").parameters[0].schema.name,
+                "petId",
+                "{name}"
+            );
+        }
+    }
+
+    #[test]
+    fn groups_services_by_domain_and_team() {
+        let catalog = fixture_catalog("groups");
+        let tree = catalog.tree();
+        let groups: Vec<(&str, usize)> = tree
+            .iter()
+            .map(|g| (g.name.as_str(), g.children.len()))
+            .collect();
+        assert_eq!(groups, vec![("retail / pets", 2), ("ungrouped", 1)]);
+    }
+
+    #[test]
+    fn loads_every_spec_version_from_raw_json() {
+        let catalog = fixture_catalog("load");
+        for (index, service) in catalog.services.iter().enumerate() {
+            let spec = catalog.load(index).unwrap();
+            assert_eq!(
+                spec.schema_nodes.len(),
+                service.schema_count,
+                "{}",
+                service.name
+            );
+            assert_eq!(spec.operations.len(), service.path_count);
+        }
+
+        // `$ref`s still resolve: Pets › items → Pet has Pet's properties.
+        let v3 = catalog
+            .services
+            .iter()
+            .position(|s| s.name == "pets-v3")
+            .unwrap();
+        let spec = catalog.load(v3).unwrap();
+        let pets = spec.schema_nodes.iter().find(|n| n.name == "Pets").unwrap();
+        assert_eq!(pets.children[0].children.len(), 3);
+        assert_eq!(spec.operation_nodes[0].name, "pets");
+    }
+}
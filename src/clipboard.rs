@@ -0,0 +1,72 @@
+use std::io::{self, Write};
+use std::process::Command;
+
+/// Copy `text` to the system clipboard with an OSC 52 escape sequence.
+/// Works over SSH and inside tmux (with `set-clipboard on`); terminals that
+/// do not support it silently ignore the sequence.
+pub fn copy(text: &str) -> io::Result<()> {
+    let mut stdout = io::stdout();
+    write!(stdout, "\x1b]52;c;{}\x07", base64(text.as_bytes()))?;
+    stdout.flush()
+}
+
+/// Clipboard readers, tried in order. Terminals rarely allow OSC 52 reads, so
+/// pasting goes through the platform's own tool.
+const PASTE_TOOLS: &[&[&str]] = &[
+    &["pbpaste"],
+    &["wl-paste", "--no-newline"],
+    &["xclip", "-selection", "clipboard", "-o"],
+    &["xsel", "--clipboard", "--output"],
+    &["powershell.exe", "-NoProfile", "-Command", "Get-Clipboard"],
+];
+
+/// The text on the system clipboard.
+pub fn paste() -> io::Result<String> {
+    for tool in PASTE_TOOLS {
+        if let Ok(output) = Command::new(tool[0]).args(&tool[1..]).output()
+            && output.status.success()
+        {
+            return Ok(String::from_utf8_lossy(&output.stdout).into_owned());
+        }
+    }
+    Err(io::Error::new(
+        io::ErrorKind::NotFound,
+        "no clipboard tool found (pbpaste, wl-paste, xclip or xsel)",
+    ))
+}
+
+const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+fn base64(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let b = [
+            chunk[0],
+            *chunk.get(1).unwrap_or(&0),
+            *chunk.get(2).unwrap_or(&0),
+        ];
+        let n = (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+// ── tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encodes_base64_with_padding() {
+        assert_eq!(base64(b"#/a"), "Iy9h");
+        assert_eq!(base64(b"#/ab"), "Iy9hYg==");
+        assert_eq!(base64(b"#/abc"), "Iy9hYmM=");
+    }
+}
@@ -0,0 +1,196 @@
+use std::cell::RefCell;
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::{env, fs};
+
+use anyhow::{Context, Result};
+use mlua::Lua;
+
+use crate::keymap::{Action, Keymap, Mode};
+
+/// A call made by `config.lua`, recorded while the script runs and applied after.
+enum ConfigCall {
+    KeymapSet {
+        mode: String,
+        lhs: String,
+        action: String,
+    },
+    Set {
+        key: String,
+        value: String,
+    },
+}
+
+/// `$XDG_CONFIG_HOME/speq/config.lua`, falling back to `~/.config/speq/config.lua`.
+pub fn config_path() -> Option<PathBuf> {
+    let base = env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(base.join("speq").join("config.lua"))
+}
+
+/// `~/.cache/speq`: spec cache and index. Deliberately not configurable.
+pub fn cache_dir() -> Option<PathBuf> {
+    env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache").join("speq"))
+}
+
+/// Load the user's keymap: defaults, with `config.lua` applied on top. A missing
+/// config file is not an error; problems inside it are reported on stderr.
+pub fn load() -> Keymap {
+    let Some(path) = config_path().filter(|p| p.exists()) else {
+        return Keymap::default();
+    };
+    let result = fs::read_to_string(&path)
+        .with_context(|| format!("cannot read {}", path.display()))
+        .and_then(|source| from_lua(&source));
+    match result {
+        Ok((keymap, warnings)) => {
+            for warning in warnings {
+                eprintln!("speq: {}: {warning}", path.display());
+            }
+            keymap
+        }
+        Err(e) => {
+            eprintln!(
+                "speq: {}: {e:#} (using default keybindings)",
+                path.display()
+            );
+            Keymap::default()
+        }
+    }
+}
+
+/// Run a config script and build the keymap from it. Returns the warnings for
+/// anything that was ignored (unknown actions, settings, keys or modes).
+///
+/// Settings are applied before bindings, so `speq.set("leader", ",")` affects
+/// every `<leader>` mapping wherever it appears in the file.
+pub fn from_lua(source: &str) -> Result<(Keymap, Vec<String>)> {
+    let calls = Rc::new(RefCell::new(Vec::new()));
+    let lua = Lua::new();
+
+    let speq = lua.create_table()?;
+    let keymap_table = lua.create_table()?;
+
+    let recorded = Rc::clone(&calls);
+    let keymap_set =
+        lua.create_function(move |_, (mode, lhs, action): (String, String, String)| {
+            recorded
+                .borrow_mut()
+                .push(ConfigCall::KeymapSet { mode, lhs, action });
+            Ok(())
+        })?;
+    keymap_table.set("set", keymap_set)?;
+    speq.set("keymap", keymap_table)?;
+
+    let recorded = Rc::clone(&calls);
+    let set = lua.create_function(move |_, (key, value): (String, String)| {
+        recorded.borrow_mut().push(ConfigCall::Set { key, value });
+        Ok(())
+    })?;
+    speq.set("set", set)?;
+    lua.globals().set("speq", speq)?;
+
+    lua.load(source).set_name("config.lua").exec()?;
+
+    let mut keymap = Keymap::default();
+    let mut warnings = Vec::new();
+    let calls = calls.take();
+
+    for call in &calls {
+        if let ConfigCall::Set { key, value } = call {
+            match key.as_str() {
+                "leader" => {
+                    if let Err(e) = keymap.set_leader(value) {
+                        warnings.push(format!("leader {value:?}: {e}, ignored"));
+                    }
+                }
+                _ => warnings.push(format!("unknown setting \"{key}\", ignored")),
+            }
+        }
+    }
+
+    for call in &calls {
+        let ConfigCall::KeymapSet { mode, lhs, action } = call else {
+            continue;
+        };
+        let Some(mode) = Mode::from_name(mode) else {
+            warnings.push(format!("unsupported mode \"{mode}\" for {lhs}, ignored"));
+            continue;
+        };
+        let Some(action) = Action::from_name(action) else {
+            warnings.push(format!("unknown action \"{action}\" for {lhs}, ignored"));
+            continue;
+        };
+        if let Err(e) = keymap.set(mode, lhs, action) {
+            warnings.push(format!("{lhs}: {e}, ignored"));
+        }
+    }
+
+    Ok((keymap, warnings))
+}
+
+// ── tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keymap::Key;
+    use crossterm::event::{KeyCode, KeyModifiers};
+
+    fn key(c: char) -> Key {
+        Key::new(KeyCode::Char(c), KeyModifiers::NONE)
+    }
+
+    fn feed(keymap: &Keymap, mode: Mode, keys: &str) -> Vec<Action> {
+        let mut pending = Vec::new();
+        keys.chars()
+            .flat_map(|c| keymap.feed(mode, &mut pending, key(c)))
+            .collect()
+    }
+
+    #[test]
+    fn config_bindings_extend_and_override_defaults() {
+        let (keymap, warnings) = from_lua(
+            r#"
+            speq.keymap.set("n", "L", "expand_node")
+            speq.keymap.set("n", "<leader>q", "quit")
+            speq.keymap.set("n", "q", "noop")
+            speq.keymap.set("p", "J", "move_down")
+            speq.set("leader", ",")
+            "#,
+        )
+        .unwrap();
+        assert!(warnings.is_empty());
+        assert_eq!(feed(&keymap, Mode::Normal, "L"), vec![Action::ExpandNode]);
+        assert_eq!(feed(&keymap, Mode::Normal, "l"), vec![Action::ToggleNode]);
+        assert_eq!(feed(&keymap, Mode::Normal, ",q"), vec![Action::Quit]);
+        assert_eq!(feed(&keymap, Mode::Normal, "q"), vec![Action::Noop]);
+        assert_eq!(feed(&keymap, Mode::Popup, "J"), vec![Action::MoveDown]);
+        assert_eq!(feed(&keymap, Mode::Popup, "q"), vec![Action::Cancel]);
+    }
+
+    #[test]
+    fn unknown_names_warn_and_are_ignored() {
+        let (keymap, warnings) = from_lua(
+            r#"
+            speq.keymap.set("n", "x", "explode")
+            speq.keymap.set("v", "j", "move_down")
+            speq.keymap.set("n", "<Nope>", "quit")
+            speq.set("colour", "red")
+            "#,
+        )
+        .unwrap();
+        assert_eq!(warnings.len(), 4);
+        assert!(warnings[0].contains("colour"));
+        assert!(warnings[1].contains("explode"));
+        assert!(feed(&keymap, Mode::Normal, "x").is_empty());
+        assert_eq!(feed(&keymap, Mode::Normal, "j"), vec![Action::MoveDown]);
+    }
+
+    #[test]
+    fn lua_errors_are_reported() {
+        assert!(from_lua("speq.keymap.set(").is_err());
+    }
+}
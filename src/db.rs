@@ -0,0 +1,113 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use rusqlite::Connection;
+
+use crate::config::cache_dir;
+
+/// Bumped whenever the tables below change. The index is only a cache of the
+/// specs on disk, so an older database is dropped and rebuilt rather than migrated.
+const SCHEMA_VERSION: i32 = 3;
+
+/// Tables from the design spec, plus `services.fingerprint` (the mtimes of
+/// the files a service was read from, for incremental indexing),
+/// `paths.raw_json` (the operation, loadable without the rest of its
+/// document) and `properties.combiner` (set for properties flattened out of
+/// `allOf` / `oneOf` / `anyOf`).
+///
+/// `search_index` keeps its own copy of the text rather than being contentless:
+/// rows can then be deleted per service and `snippet()` works on hits.
+const SCHEMA: &str = "
+CREATE TABLE services (
+  id              INTEGER PRIMARY KEY,
+  name            TEXT NOT NULL UNIQUE,
+  repo            TEXT,
+  team            TEXT,
+  domain          TEXT,
+  source_url      TEXT,
+  cached_at       TEXT,
+  indexed_at      TEXT,
+  spec_path       TEXT NOT NULL,
+  fingerprint     TEXT,
+  openapi_version TEXT,
+  spec_title      TEXT,
+  spec_version    TEXT
+);
+
+CREATE TABLE schemas (
+  id          INTEGER PRIMARY KEY,
+  service_id  INTEGER NOT NULL REFERENCES services(id),
+  name        TEXT NOT NULL,
+  description TEXT,
+  type        TEXT,
+  raw_json    TEXT
+);
+CREATE INDEX schemas_service ON schemas(service_id);
+
+CREATE TABLE properties (
+  id          INTEGER PRIMARY KEY,
+  schema_id   INTEGER NOT NULL REFERENCES schemas(id),
+  service_id  INTEGER NOT NULL REFERENCES services(id),
+  name        TEXT NOT NULL,
+  type        TEXT,
+  format      TEXT,
+  description TEXT,
+  required    INTEGER,
+  ref_target  TEXT,
+  combiner    TEXT
+);
+CREATE INDEX properties_service ON properties(service_id);
+
+CREATE TABLE paths (
+  id           INTEGER PRIMARY KEY,
+  service_id   INTEGER NOT NULL REFERENCES services(id),
+  path         TEXT NOT NULL,
+  method       TEXT NOT NULL,
+  operation_id TEXT,
+  summary      TEXT,
+  description  TEXT,
+  tags         TEXT,
+  raw_json     TEXT
+);
+CREATE INDEX paths_service ON paths(service_id);
+
+CREATE VIRTUAL TABLE search_index USING fts5(
+  service_name,
+  schema_name,
+  property_name,
+  description,
+  tokenize='unicode61'
+);
+";
+
+const TABLES: [&str; 5] = ["search_index", "properties", "paths", "schemas", "services"];
+
+/// `~/.cache/speq/index.db`.
+pub fn index_path() -> Option<PathBuf> {
+    cache_dir().map(|dir| dir.join("index.db"))
+}
+
+/// Open (creating if needed) the index database at `path`.
+pub fn open(path: &Path) -> Result<Connection> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).with_context(|| format!("cannot create {}", dir.display()))?;
+    }
+    let conn = Connection::open(path).with_context(|| format!("cannot open {}", path.display()))?;
+    init(&conn)?;
+    Ok(conn)
+}
+
+/// Create the tables, dropping an index written by an older schema version.
+pub fn init(conn: &Connection) -> Result<()> {
+    let version: i32 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+    if version == SCHEMA_VERSION {
+        return Ok(());
+    }
+    for table in TABLES {
+        conn.execute_batch(&format!("DROP TABLE IF EXISTS {table};"))?;
+    }
+    conn.execute_batch(SCHEMA)?;
+    conn.execute_batch(&format!("PRAGMA user_version = {SCHEMA_VERSION};"))?;
+    Ok(())
+}
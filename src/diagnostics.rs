@@ -0,0 +1,527 @@
+use std::collections::{HashMap, HashSet};
+
+use serde_json::Value;
+
+use crate::app::View;
+use crate::breadcrumb;
+use crate::parser::v31;
+use crate::spec::{LoadedSpec, SpecVersion};
+use crate::tree::{NodeKind, TreeNode};
+
+/// What went wrong at a node while turning the document into trees.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DiagnosticKind {
+    DanglingRef,        // `$ref` target not found: the node is an empty leaf
+    CycleCut,           // recursive `$ref` left unexpanded to keep the tree finite
+    UnsupportedKeyword, // schema keyword speq does not show
+    Naming,             // name breaks the conventions in `.speq.toml`
+}
+
+impl DiagnosticKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            DiagnosticKind::DanglingRef => "dangling $ref",
+            DiagnosticKind::CycleCut => "cycle",
+            DiagnosticKind::UnsupportedKeyword => "unsupported",
+            DiagnosticKind::Naming => "naming",
+        }
+    }
+}
+
+/// One finding, at the first node (in tree order) it applies to.
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    pub kind: DiagnosticKind,
+    pub message: String,
+    pub pointer: String, // where in the document, as shown in the detail pane
+    pub view: View,
+    pub path: Vec<usize>,
+}
+
+/// Every diagnostic for a spec, plus each tree node they apply to. A schema
+/// reached through several `$ref`s is one diagnostic marked at every copy.
+#[derive(Default)]
+pub struct Diagnostics {
+    pub list: Vec<Diagnostic>,
+    marks: HashMap<(View, Vec<usize>), Vec<usize>>, // node → indices into `list`
+}
+
+impl Diagnostics {
+    pub fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+
+    /// The diagnostics marked on the node at `path` in `view`.
+    pub fn at(&self, view: View, path: &[usize]) -> Vec<&Diagnostic> {
+        self.marks
+            .get(&(view, path.to_vec()))
+            .map(|indices| indices.iter().map(|&i| &self.list[i]).collect())
+            .unwrap_or_default()
+    }
+
+    fn add(
+        &mut self,
+        kind: DiagnosticKind,
+        message: String,
+        pointer: &str,
+        view: View,
+        path: &[usize],
+    ) {
+        let index = match self
+            .list
+            .iter()
+            .position(|d| d.kind == kind && d.pointer == pointer && d.message == message)
+        {
+            Some(index) => index,
+            None => {
+                self.list.push(Diagnostic {
+                    kind,
+                    message,
+                    pointer: pointer.to_string(),
+                    view,
+                    path: path.to_vec(),
+                });
+                self.list.len() - 1
+            }
+        };
+        let marks = self.marks.entry((view, path.to_vec())).or_default();
+        if !marks.contains(&index) {
+            marks.push(index);
+        }
+    }
+}
+
+/// Keywords that only annotate a schema: nothing is lost by not showing them.
+const ANNOTATIONS: &[&str] = &[
+    "title",
+    "readOnly",
+    "writeOnly",
+    "deprecated",
+    "xml",
+    "externalDocs",
+    "discriminator",
+    "$comment",
+    "$schema",
+    "$id",
+    "$anchor",
+    "contentMediaType",
+    "contentEncoding",
+];
+
+/// Keywords the 3.0 tree shows (Swagger 2.0 is upgraded to 3.0 first).
+const KEYWORDS_30: &[&str] = &[
+    "$ref",
+    "type",
+    "format",
+    "description",
+    "properties",
+    "required",
+    "items",
+    "minProperties",
+    "maxProperties",
+    "minItems",
+    "maxItems",
+    "uniqueItems",
+    "minLength",
+    "maxLength",
+    "pattern",
+    "enum",
+    "minimum",
+    "maximum",
+    "exclusiveMinimum",
+    "exclusiveMaximum",
+    "multipleOf",
+    "allOf",
+    "oneOf",
+    "anyOf",
+    "nullable",
+    "example",
+    "default",
+    "additionalProperties",
+];
+
+/// Keywords the 3.1 (JSON Schema 2020-12) tree shows.
+const KEYWORDS_31: &[&str] = &[
+    "$ref",
+    "$defs",
+    "type",
+    "format",
+    "description",
+    "properties",
+    "required",
+    "items",
+    "prefixItems",
+    "contains",
+    "minContains",
+    "maxContains",
+    "unevaluatedItems",
+    "additionalProperties",
+    "unevaluatedProperties",
+    "patternProperties",
+    "propertyNames",
+    "dependentSchemas",
+    "dependentRequired",
+    "minProperties",
+    "maxProperties",
+    "minItems",
+    "maxItems",
+    "uniqueItems",
+    "minLength",
+    "maxLength",
+    "pattern",
+    "enum",
+    "const",
+    "minimum",
+    "maximum",
+    "exclusiveMinimum",
+    "exclusiveMaximum",
+    "multipleOf",
+    "allOf",
+    "oneOf",
+    "anyOf",
+    "not",
+    "if",
+    "then",
+    "else",
+    "example",
+    "examples",
+    "default",
+];
+
+/// Walk both trees of a parsed spec and collect what its parser had to paper
+/// over: `$ref`s left as empty leaves, recursion cut points, and schema
+/// keywords (read from `document`, the bundled source) the tree leaves out.
+pub fn collect(spec: &LoadedSpec, document: &Value) -> Diagnostics {
+    let known = ref_targets(spec, document);
+    let keywords = match spec.version {
+        SpecVersion::V20 | SpecVersion::V30 => KEYWORDS_30,
+        SpecVersion::V31 => KEYWORDS_31,
+    };
+    let mut check = Check {
+        spec,
+        document,
+        known,
+        keywords,
+        out: Diagnostics::default(),
+    };
+    each_node(spec, &mut |view, node, ancestors, pointer, path| {
+        check.check(view, node, ancestors, pointer, path)
+    });
+    check.out
+}
+
+/// Mark findings made against the bundled document (`(local pointer,
+/// message)`, e.g. from `naming::check`) on every tree node showing them.
+pub fn mark(spec: &mut LoadedSpec, kind: DiagnosticKind, found: &[(String, String)]) {
+    let mut by_pointer: HashMap<&str, Vec<&str>> = HashMap::new();
+    for (pointer, message) in found {
+        by_pointer
+            .entry(pointer.as_str())
+            .or_default()
+            .push(message.as_str());
+    }
+    let mut out = std::mem::take(&mut spec.diagnostics);
+    each_node(spec, &mut |view, _, _, pointer, path| {
+        // Swagger 2.0 operations are upgraded before parsing (see `Check`).
+        if view == View::Operations && spec.version == SpecVersion::V20 {
+            return;
+        }
+        let Some(messages) =
+            local_pointer(spec, pointer).and_then(|local| by_pointer.get(local.as_str()))
+        else {
+            return;
+        };
+        for message in messages {
+            out.add(kind, message.to_string(), pointer, view, path);
+        }
+    });
+    spec.diagnostics = out;
+}
+
+/// Called with each node's view, node, ancestors, display pointer and path.
+type Visit<'a, 'f> = dyn FnMut(View, &'a TreeNode, &[&'a TreeNode], &str, &[usize]) + 'f;
+
+/// Visit every node of both trees with its ancestors, display pointer and path.
+fn each_node<'a>(spec: &'a LoadedSpec, visit: &mut Visit<'a, '_>) {
+    fn walk<'a>(
+        spec: &'a LoadedSpec,
+        view: View,
+        node: &'a TreeNode,
+        ancestors: &mut Vec<&'a TreeNode>,
+        path: &mut Vec<usize>,
+        visit: &mut Visit<'a, '_>,
+    ) {
+        ancestors.push(node);
+        if let Some(pointer) = breadcrumb::json_pointer(spec, view, ancestors, path) {
+            visit(
+                view,
+                node,
+                &ancestors[..ancestors.len() - 1],
+                &pointer,
+                path,
+            );
+        }
+        for (i, child) in node.children.iter().enumerate() {
+            path.push(i);
+            walk(spec, view, child, ancestors, path, visit);
+            path.pop();
+        }
+        ancestors.pop();
+    }
+    for (view, roots) in [
+        (View::Schemas, &spec.schema_nodes),
+        (View::Operations, &spec.operation_nodes),
+    ] {
+        for (i, root) in roots.iter().enumerate() {
+            walk(spec, view, root, &mut vec![], &mut vec![i], visit);
+        }
+    }
+}
+
+/// A pointer from `breadcrumb::json_pointer`, which names schemas imported
+/// from other files by their original location, as a pointer into the
+/// bundled document.
+fn local_pointer(spec: &LoadedSpec, pointer: &str) -> Option<String> {
+    if let Some(local) = pointer.strip_prefix('#') {
+        return Some(local.to_string());
+    }
+    let (name, rest) = spec.schema_sources.iter().find_map(|(name, source)| {
+        let rest = pointer.strip_prefix(source.as_str())?;
+        (rest.is_empty() || rest.starts_with('/')).then_some((name, rest))
+    })?;
+    let container = match spec.version {
+        SpecVersion::V20 => "/definitions",
+        SpecVersion::V30 | SpecVersion::V31 => "/components/schemas",
+    };
+    Some(format!("{container}/{}{rest}", breadcrumb::escape(name)))
+}
+
+struct Check<'a> {
+    spec: &'a LoadedSpec,
+    document: &'a Value,
+    known: HashSet<String>,
+    keywords: &'static [&'static str],
+    out: Diagnostics,
+}
+
+impl<'a> Check<'a> {
+    fn check(
+        &mut self,
+        view: View,
+        node: &TreeNode,
+        ancestors: &[&TreeNode],
+        pointer: &str,
+        path: &[usize],
+    ) {
+        match &node.info.kind {
+            NodeKind::Ref(target) if node.children.is_empty() => {
+                if !self.known.contains(target) {
+                    let message = format!("$ref to {target} does not resolve");
+                    self.out
+                        .add(DiagnosticKind::DanglingRef, message, pointer, view, path);
+                } else if ancestors
+                    .iter()
+                    .any(|a| matches!(&a.info.kind, NodeKind::Ref(t) if t == target))
+                {
+                    let message = format!("{target} contains itself; not expanded again here");
+                    self.out
+                        .add(DiagnosticKind::CycleCut, message, pointer, view, path);
+                }
+            }
+            // `$ref` siblings, keyword groups and operation scaffolding are not schemas.
+            NodeKind::Ref(_)
+            | NodeKind::Group(_)
+            | NodeKind::Tag
+            | NodeKind::Operation(_)
+            | NodeKind::NoContent
+            | NodeKind::ServiceGroup
+            | NodeKind::Service(_) => {}
+            // Swagger 2.0 parameters mix `name`, `in`, `collectionFormat`
+            // and the like in with their schema keywords.
+            _ if self.spec.version == SpecVersion::V20
+                && ancestors.get(2).is_some_and(
+                    |a| matches!(&a.info.kind, NodeKind::Group(g) if g == "parameters"),
+                ) => {}
+            _ => {
+                let Some(schema) = self.lookup(pointer).and_then(Value::as_object) else {
+                    return;
+                };
+                for key in schema.keys() {
+                    if key.starts_with("x-")
+                        || self.keywords.contains(&key.as_str())
+                        || ANNOTATIONS.contains(&key.as_str())
+                    {
+                        continue;
+                    }
+                    let message = format!("unsupported keyword {key} (not shown)");
+                    self.out.add(
+                        DiagnosticKind::UnsupportedKeyword,
+                        message,
+                        pointer,
+                        view,
+                        path,
+                    );
+                }
+            }
+        }
+    }
+
+    /// The value at a pointer from `breadcrumb::json_pointer`.
+    fn lookup(&self, pointer: &str) -> Option<&'a Value> {
+        self.document.pointer(&local_pointer(self.spec, pointer)?)
+    }
+}
+
+/// Names a `Ref` node can resolve to: the top-level schemas, and for 3.1 the
+/// `$defs` nested inside them.
+fn ref_targets(spec: &LoadedSpec, document: &Value) -> HashSet<String> {
+    let container = match spec.version {
+        SpecVersion::V20 => document.get("definitions"),
+        SpecVersion::V30 | SpecVersion::V31 => document.pointer("/components/schemas"),
+    };
+    let mut known = HashSet::new();
+    if let Some(schemas) = container.and_then(Value::as_object) {
+        for (name, schema) in schemas {
+            known.insert(name.clone());
+            if spec.version == SpecVersion::V31 {
+                let mut defs = HashMap::new();
+                v31::collect_defs(&breadcrumb::escape(name), schema, &mut defs);
+                known.extend(defs.into_keys());
+            }
+        }
+    }
+    known
+}
+
+// ── tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_spec;
+
+    const PETSTORE: &str = include_str!("../fixtures/petstore.yaml");
+    const PETSTORE_V2: &str = include_str!("../fixtures/petstore-v2.yaml");
+    const PETSTORE_31: &str = include_str!("../fixtures/petstore-3.1.yaml");
+
+    const BROKEN: &str = r##"
+openapi: 3.0.3
+info: { title: Broken, version: "1" }
+paths:
+  /nodes:
+    get:
+      responses:
+        "200":
+          description: ok
+          content:
+            application/json:
+              schema: { $ref: "#/components/schemas/Node" }
+components:
+  schemas:
+    Node:
+      type: object
+      properties:
+        next: { $ref: "#/components/schemas/Node" }
+        owner: { $ref: "#/components/schemas/Owner" }
+        labels:
+          type: string
+          not: { enum: [""] }
+        tags:
+          type: object
+          additionalProperties: { type: string }
+"##;
+
+    fn summary(diagnostics: &Diagnostics) -> Vec<(DiagnosticKind, &str)> {
+        diagnostics
+            .list
+            .iter()
+            .map(|d| (d.kind, d.pointer.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn well_formed_fixtures_are_clean() {
+        assert!(parse_spec(PETSTORE).unwrap().diagnostics.is_empty());
+        assert!(parse_spec(PETSTORE_V2).unwrap().diagnostics.is_empty());
+        assert!(parse_spec(PETSTORE_31).unwrap().diagnostics.is_empty());
+    }
+
+    #[test]
+    fn finds_dangling_refs_cycles_and_unsupported_keywords() {
+        let spec = parse_spec(BROKEN).unwrap();
+        let diagnostics = &spec.diagnostics;
+        assert_eq!(
+            summary(diagnostics),
+            vec![
+                (
+                    DiagnosticKind::CycleCut,
+                    "#/components/schemas/Node/properties/next"
+                ),
+                (
+                    DiagnosticKind::DanglingRef,
+                    "#/components/schemas/Node/properties/owner"
+                ),
+                (
+                    DiagnosticKind::UnsupportedKeyword,
+                    "#/components/schemas/Node/properties/labels"
+                ),
+            ]
+        );
+        assert_eq!(
+            diagnostics.list[1].message,
+            "$ref to Owner does not resolve"
+        );
+        assert_eq!(
+            diagnostics.list[2].message,
+            "unsupported keyword not (not shown)"
+        );
+
+        // Node › next → Node › next is where the recursion stops; the same
+        // missing Owner is marked under every copy of Node, in both views.
+        let first = &diagnostics.list[0];
+        assert_eq!(
+            (first.view, first.path.as_slice()),
+            (View::Schemas, &[0, 0, 0][..])
+        );
+        assert_eq!(diagnostics.at(View::Schemas, &[0, 1]).len(), 1);
+        assert_eq!(diagnostics.at(View::Schemas, &[0, 0, 1]).len(), 1);
+        assert!(
+            !diagnostics
+                .at(View::Operations, &[0, 0, 0, 0, 1])
+                .is_empty()
+        );
+        assert!(diagnostics.at(View::Schemas, &[0]).is_empty());
+    }
+
+    #[test]
+    fn marks_document_findings_on_every_copy() {
+        let mut spec = parse_spec(BROKEN).unwrap();
+        let found = vec![(
+            "/components/schemas/Node/properties/owner".to_string(),
+            "property owner is not PascalCase (rename to Owner)".to_string(),
+        )];
+        mark(&mut spec, DiagnosticKind::Naming, &found);
+        let diagnostics = &spec.diagnostics;
+        assert_eq!(diagnostics.list.len(), 4);
+        assert_eq!(diagnostics.list[3].kind, DiagnosticKind::Naming);
+        // Alongside the dangling-ref diagnostic already there, in both views.
+        assert_eq!(diagnostics.at(View::Schemas, &[0, 1]).len(), 2);
+        assert_eq!(diagnostics.at(View::Operations, &[0, 0, 0, 0, 1]).len(), 2);
+    }
+
+    #[test]
+    fn keywords_depend_on_the_version() {
+        // `nullable` is 3.0 only; 3.1 spells it `type: [.., "null"]`.
+        let spec = parse_spec(
+            "openapi: 3.1.0\ninfo: {title: t, version: '1'}\ncomponents:\n  schemas:\n    A: {type: string, nullable: true, title: A}\n",
+        )
+        .unwrap();
+        let messages: Vec<&str> = spec
+            .diagnostics
+            .list
+            .iter()
+            .map(|d| d.message.as_str())
+            .collect();
+        assert_eq!(messages, vec!["unsupported keyword nullable (not shown)"]);
+    }
+}
@@ -0,0 +1,1196 @@
+use std::collections::{HashMap, HashSet};
+use std::io::{self, Write};
+
+use clap::ValueEnum;
+use serde::Serialize;
+use serde_json::{Value, json};
+
+use crate::app::View;
+use crate::diagnostics::Diagnostics;
+use crate::graph::Graph;
+use crate::spec::{LoadedSpec, Operation, Parameter};
+use crate::tree::{NodeInfo, NodeKind, TreeNode};
+use crate::usage;
+
+/// What a change means for an existing client.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Class {
+    Breaking,
+    NonBreaking,
+    Unknown, // depends on how clients and the server treat it
+}
+
+impl Class {
+    pub fn label(self) -> &'static str {
+        match self {
+            Class::Breaking => "breaking",
+            Class::NonBreaking => "non-breaking",
+            Class::Unknown => "unknown",
+        }
+    }
+
+    fn heading(self) -> &'static str {
+        match self {
+            Class::Breaking => "Breaking changes",
+            Class::NonBreaking => "Non-breaking changes",
+            Class::Unknown => "Changes of unknown impact",
+        }
+    }
+
+    const ALL: [Class; 3] = [Class::Breaking, Class::NonBreaking, Class::Unknown];
+}
+
+/// `speq diff --format`.
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum Format {
+    Text,
+    Json,
+    Markdown,
+}
+
+/// One difference between the two specs.
+#[derive(Debug, Clone, Serialize)]
+pub struct Change {
+    pub class: Class,
+    pub location: String, // `Pet › tags › items`, `GET /pets › responses › 200`
+    pub message: String,
+}
+
+/// Which way a schema's data flows, which decides whether accepting more or
+/// fewer values breaks clients: they send requests and read responses.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Flow {
+    Request,
+    Response,
+    Both, // used both ways, or not used by any operation
+}
+
+impl Flow {
+    /// A change that lets fewer values through (a new constraint, a removed
+    /// enum value) breaks clients sending them; one that lets more through
+    /// breaks clients reading them.
+    pub fn class(self, narrows: bool) -> Class {
+        match (self, narrows) {
+            (Flow::Request, false) | (Flow::Response, true) => Class::NonBreaking,
+            _ => Class::Breaking,
+        }
+    }
+}
+
+/// Compare two specs: component schemas, then operations.
+pub fn diff(old: &LoadedSpec, new: &LoadedSpec) -> Vec<Change> {
+    let flows = flows(&[old, new]);
+    let mut diff = Diff {
+        changes: Vec::new(),
+    };
+
+    let new_schemas: HashMap<&str, &TreeNode> = new
+        .schema_nodes
+        .iter()
+        .map(|n| (n.name.as_str(), n))
+        .collect();
+    let old_names: HashSet<&str> = old.schema_nodes.iter().map(|n| n.name.as_str()).collect();
+    for old_node in &old.schema_nodes {
+        match new_schemas.get(old_node.name.as_str()) {
+            Some(new_node) => {
+                let flow = flows
+                    .get(old_node.name.as_str())
+                    .copied()
+                    .unwrap_or(Flow::Both);
+                diff.node(old_node, new_node, &old_node.name, flow);
+            }
+            None => diff.push(
+                Class::Breaking,
+                &old_node.name,
+                "schema removed".to_string(),
+            ),
+        }
+    }
+    for new_node in new
+        .schema_nodes
+        .iter()
+        .filter(|n| !old_names.contains(n.name.as_str()))
+    {
+        diff.push(
+            Class::NonBreaking,
+            &new_node.name,
+            "schema added".to_string(),
+        );
+    }
+
+    let key = |op: &Operation| (op.method.clone(), op.path.clone());
+    let new_ops: HashMap<(String, String), &Operation> =
+        new.operations.iter().map(|op| (key(op), op)).collect();
+    let old_ops: HashSet<(String, String)> = old.operations.iter().map(key).collect();
+    for old_op in &old.operations {
+        let label = operation_label(old_op);
+        match new_ops.get(&key(old_op)) {
+            Some(new_op) => diff.operation(old_op, new_op, &label),
+            None => diff.push(Class::Breaking, &label, "operation removed".to_string()),
+        }
+    }
+    for new_op in new
+        .operations
+        .iter()
+        .filter(|op| !old_ops.contains(&key(op)))
+    {
+        diff.push(
+            Class::NonBreaking,
+            &operation_label(new_op),
+            "operation added".to_string(),
+        );
+    }
+    diff.changes
+}
+
+fn operation_label(op: &Operation) -> String {
+    format!("{} {}", op.method.to_uppercase(), op.path)
+}
+
+/// How each component schema is used across the operations of `specs`,
+/// following `$ref`s (already expanded in the trees) transitively.
+fn flows(specs: &[&LoadedSpec]) -> HashMap<String, Flow> {
+    let mut requests = HashSet::new();
+    let mut responses = HashSet::new();
+    for spec in specs {
+        for op in &spec.operations {
+            for param in &op.parameters {
+                collect_refs(&param.schema, &mut requests);
+            }
+            if let Some(schema) = op.request_body.as_ref().and_then(|b| b.schema.as_ref()) {
+                collect_refs(schema, &mut requests);
+            }
+            for schema in op.responses.iter().filter_map(|r| r.schema.as_ref()) {
+                collect_refs(schema, &mut responses);
+            }
+        }
+    }
+    let mut flows = HashMap::new();
+    for name in requests.union(&responses) {
+        let flow = match (requests.contains(name), responses.contains(name)) {
+            (true, false) => Flow::Request,
+            (false, true) => Flow::Response,
+            _ => Flow::Both,
+        };
+        flows.insert(name.clone(), flow);
+    }
+    flows
+}
+
+fn collect_refs(node: &TreeNode, out: &mut HashSet<String>) {
+    if let NodeKind::Ref(target) = &node.info.kind {
+        out.insert(target.clone());
+    }
+    for child in &node.children {
+        collect_refs(child, out);
+    }
+}
+
+struct Diff {
+    changes: Vec<Change>,
+}
+
+impl Diff {
+    fn push(&mut self, class: Class, location: &str, message: String) {
+        self.changes.push(Change {
+            class,
+            location: location.to_string(),
+            message,
+        });
+    }
+
+    fn operation(&mut self, old: &Operation, new: &Operation, label: &str) {
+        if !old.deprecated && new.deprecated {
+            self.push(
+                Class::NonBreaking,
+                label,
+                "operation deprecated".to_string(),
+            );
+        }
+        if old.operation_id != new.operation_id {
+            let message = format!(
+                "operationId changed from {} to {}",
+                old.operation_id.as_deref().unwrap_or("(none)"),
+                new.operation_id.as_deref().unwrap_or("(none)")
+            );
+            // Generated clients name methods after it; plain HTTP clients do not care.
+            self.push(Class::Unknown, label, message);
+        }
+
+        let key = |p: &Parameter| (p.location.clone(), p.schema.name.clone());
+        let at =
+            |p: &Parameter| format!("{label} › parameters › {} ({})", p.schema.name, p.location);
+        for old_param in &old.parameters {
+            match new.parameters.iter().find(|p| key(p) == key(old_param)) {
+                Some(new_param) => {
+                    let at = at(new_param);
+                    self.required(
+                        old_param.schema.info.required,
+                        new_param.schema.info.required,
+                        &at,
+                        Flow::Request,
+                    );
+                    self.node(&old_param.schema, &new_param.schema, &at, Flow::Request);
+                }
+                // The server may now reject it, or ignore it.
+                None => self.push(
+                    Class::Unknown,
+                    &at(old_param),
+                    "parameter removed".to_string(),
+                ),
+            }
+        }
+        for new_param in new
+            .parameters
+            .iter()
+            .filter(|p| !old.parameters.iter().any(|o| key(o) == key(p)))
+        {
+            let (class, message) = match new_param.schema.info.required {
+                true => (Class::Breaking, "required parameter added"),
+                false => (Class::NonBreaking, "optional parameter added"),
+            };
+            self.push(class, &at(new_param), message.to_string());
+        }
+
+        let body_at = format!("{label} › requestBody");
+        match (&old.request_body, &new.request_body) {
+            (None, Some(body)) if body.required => self.push(
+                Class::Breaking,
+                &body_at,
+                "required request body added".to_string(),
+            ),
+            (None, Some(_)) => self.push(
+                Class::NonBreaking,
+                &body_at,
+                "optional request body added".to_string(),
+            ),
+            (Some(_), None) => {
+                self.push(Class::Unknown, &body_at, "request body removed".to_string())
+            }
+            (Some(old_body), Some(new_body)) => {
+                self.required(
+                    old_body.required,
+                    new_body.required,
+                    &body_at,
+                    Flow::Request,
+                );
+                self.content_type(&old_body.content_type, &new_body.content_type, &body_at);
+                self.schema(
+                    old_body.schema.as_ref(),
+                    new_body.schema.as_ref(),
+                    &body_at,
+                    Flow::Request,
+                );
+            }
+            (None, None) => {}
+        }
+
+        for old_resp in &old.responses {
+            let at = format!("{label} › responses › {}", old_resp.status);
+            match new.responses.iter().find(|r| r.status == old_resp.status) {
+                Some(new_resp) => {
+                    self.content_type(&old_resp.content_type, &new_resp.content_type, &at);
+                    self.schema(
+                        old_resp.schema.as_ref(),
+                        new_resp.schema.as_ref(),
+                        &at,
+                        Flow::Response,
+                    );
+                }
+                // Clients relying on a success response lose it; a dropped
+                // error response just falls through to their generic handling.
+                None if old_resp.status.starts_with('2') => {
+                    self.push(Class::Breaking, &at, "success response removed".to_string())
+                }
+                None => self.push(Class::NonBreaking, &at, "response removed".to_string()),
+            }
+        }
+        for new_resp in new
+            .responses
+            .iter()
+            .filter(|r| !old.responses.iter().any(|o| o.status == r.status))
+        {
+            let at = format!("{label} › responses › {}", new_resp.status);
+            self.push(Class::NonBreaking, &at, "response added".to_string());
+        }
+    }
+
+    fn content_type(&mut self, old: &Option<String>, new: &Option<String>, at: &str) {
+        if let (Some(old), Some(new)) = (old, new)
+            && old != new
+        {
+            self.push(
+                Class::Breaking,
+                at,
+                format!("content type changed from {old} to {new}"),
+            );
+        }
+    }
+
+    /// A body or response schema, which either side may lack.
+    fn schema(&mut self, old: Option<&TreeNode>, new: Option<&TreeNode>, at: &str, flow: Flow) {
+        match (old, new) {
+            (Some(old), Some(new)) => self.node(old, new, at, flow),
+            (None, Some(_)) => self.push(flow.class(false), at, "schema added".to_string()),
+            (Some(_), None) => self.push(flow.class(true), at, "schema removed".to_string()),
+            (None, None) => {}
+        }
+    }
+
+    fn required(&mut self, old: bool, new: bool, at: &str, flow: Flow) {
+        match (old, new) {
+            (false, true) => self.push(flow.class(true), at, "now required".to_string()),
+            (true, false) => self.push(flow.class(false), at, "no longer required".to_string()),
+            _ => {}
+        }
+    }
+
+    /// Compare two schema nodes at the same place. `$ref`s are compared by
+    /// target only: the targets are compared once, as component schemas.
+    fn node(&mut self, old: &TreeNode, new: &TreeNode, at: &str, flow: Flow) {
+        for (class, message) in info_changes(&old.info, &new.info, flow) {
+            self.push(class, at, message);
+        }
+        if kind_changed(&old.info.kind, &new.info.kind) || matches!(new.info.kind, NodeKind::Ref(_))
+        {
+            return;
+        }
+
+        let properties = matches!(new.info.kind, NodeKind::Object);
+        for old_child in &old.children {
+            let child_at = format!("{at} › {}", old_child.name);
+            match new.children.iter().find(|c| c.name == old_child.name) {
+                Some(new_child) => {
+                    if properties {
+                        self.required(
+                            old_child.info.required,
+                            new_child.info.required,
+                            &child_at,
+                            flow,
+                        );
+                    }
+                    self.node(old_child, new_child, &child_at, flow);
+                }
+                None => {
+                    let (class, message) = match &new.info.kind {
+                        // A request property dropped may still be sent: the
+                        // server may ignore it, or reject it.
+                        NodeKind::Object if flow == Flow::Request => {
+                            (Class::Unknown, "property removed")
+                        }
+                        NodeKind::Object => (Class::Breaking, "property removed"),
+                        NodeKind::OneOf | NodeKind::AnyOf => (flow.class(true), "variant removed"),
+                        NodeKind::AllOf => (flow.class(false), "allOf member removed"),
+                        _ => (Class::Unknown, "removed"),
+                    };
+                    self.push(class, &child_at, message.to_string());
+                }
+            }
+        }
+        let old_names: HashSet<&str> = old.children.iter().map(|c| c.name.as_str()).collect();
+        for new_child in new
+            .children
+            .iter()
+            .filter(|c| !old_names.contains(c.name.as_str()))
+        {
+            let child_at = format!("{at} › {}", new_child.name);
+            let (class, message) = match &new.info.kind {
+                NodeKind::Object if new_child.info.required => {
+                    (flow.class(true), "required property added")
+                }
+                NodeKind::Object => (Class::NonBreaking, "optional property added"),
+                NodeKind::OneOf | NodeKind::AnyOf => (flow.class(false), "variant added"),
+                NodeKind::AllOf => (flow.class(true), "allOf member added"),
+                _ => (Class::Unknown, "added"),
+            };
+            self.push(class, &child_at, message.to_string());
+        }
+    }
+}
+
+/// Whether two nodes are different kinds of schema (a changed `$ref` target
+/// counts), past which their children are not compared.
+fn kind_changed(old: &NodeKind, new: &NodeKind) -> bool {
+    match (old, new) {
+        (NodeKind::Ref(a), NodeKind::Ref(b)) => a != b,
+        (NodeKind::Multi(a), NodeKind::Multi(b)) => a != b,
+        _ => std::mem::discriminant(old) != std::mem::discriminant(new),
+    }
+}
+
+/// Differences in a node's own fields: type, nullability, format, enum and
+/// constraints. Shared with the TUI diff view.
+pub fn info_changes(old: &NodeInfo, new: &NodeInfo, flow: Flow) -> Vec<(Class, String)> {
+    let mut changes = Vec::new();
+    if kind_changed(&old.kind, &new.kind) {
+        let message = match (&old.kind, &new.kind) {
+            (NodeKind::Ref(a), NodeKind::Ref(b)) => format!("$ref changed from {a} to {b}"),
+            _ => format!(
+                "type changed from {} to {}",
+                old.kind.label(),
+                new.kind.label()
+            ),
+        };
+        changes.push((Class::Breaking, message));
+        return changes;
+    }
+
+    match (old.nullable, new.nullable) {
+        (false, true) => changes.push((flow.class(false), "now nullable".to_string())),
+        (true, false) => changes.push((flow.class(true), "no longer nullable".to_string())),
+        _ => {}
+    }
+
+    match (&old.format, &new.format) {
+        (Some(a), Some(b)) if a != b => {
+            changes.push((Class::Breaking, format!("format changed from {a} to {b}")))
+        }
+        (None, Some(b)) => changes.push((flow.class(true), format!("format {b} added"))),
+        (Some(a), None) => changes.push((flow.class(false), format!("format {a} removed"))),
+        _ => {}
+    }
+
+    let removed: Vec<&String> = old
+        .enum_values
+        .iter()
+        .filter(|v| !new.enum_values.contains(v))
+        .collect();
+    let added: Vec<&String> = new
+        .enum_values
+        .iter()
+        .filter(|v| !old.enum_values.contains(v))
+        .collect();
+    let join = |values: &[&String]| {
+        values
+            .iter()
+            .map(|v| v.as_str())
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    if old.enum_values.is_empty() && !new.enum_values.is_empty() {
+        changes.push((
+            flow.class(true),
+            format!("now restricted to enum {}", join(&added)),
+        ));
+    } else if !old.enum_values.is_empty() && new.enum_values.is_empty() {
+        changes.push((flow.class(false), "enum restriction removed".to_string()));
+    } else {
+        if !removed.is_empty() {
+            changes.push((
+                flow.class(true),
+                format!("enum values removed: {}", join(&removed)),
+            ));
+        }
+        if !added.is_empty() {
+            changes.push((
+                flow.class(false),
+                format!("enum values added: {}", join(&added)),
+            ));
+        }
+    }
+
+    let split = |c: &String| -> (String, String) {
+        match c.split_once(": ") {
+            Some((key, value)) => (key.to_string(), value.to_string()),
+            None => (c.clone(), String::new()),
+        }
+    };
+    let old_constraints: Vec<(String, String)> = old.constraints.iter().map(split).collect();
+    let new_constraints: Vec<(String, String)> = new.constraints.iter().map(split).collect();
+    for (key, old_value) in &old_constraints {
+        match new_constraints.iter().find(|(k, _)| k == key) {
+            Some((_, new_value)) if new_value != old_value => {
+                let class = match narrows(key, old_value, new_value) {
+                    Some(narrows) => flow.class(narrows),
+                    None => Class::Unknown,
+                };
+                changes.push((
+                    class,
+                    format!("{key} changed from {old_value} to {new_value}"),
+                ));
+            }
+            Some(_) => {}
+            None => changes.push((
+                flow.class(false),
+                format!("constraint removed: {key}: {old_value}"),
+            )),
+        }
+    }
+    for (key, value) in new_constraints
+        .iter()
+        .filter(|(k, _)| !old_constraints.iter().any(|(o, _)| o == k))
+    {
+        changes.push((
+            flow.class(true),
+            format!("constraint added: {key}: {value}"),
+        ));
+    }
+    changes
+}
+
+/// Whether moving a `min…` / `max…` bound from `old` to `new` lets fewer
+/// values through. `None` for anything else (a new pattern, multipleOf).
+fn narrows(key: &str, old: &str, new: &str) -> Option<bool> {
+    // `min: >0` is exclusive: treat it as a hair above the bound.
+    let bound = |value: &str| -> Option<(f64, bool)> {
+        let exclusive = value.starts_with(['>', '<']);
+        Some((
+            value.trim_start_matches(['>', '<']).parse().ok()?,
+            exclusive,
+        ))
+    };
+    let ((old, old_exclusive), (new, new_exclusive)) = (bound(old)?, bound(new)?);
+    if key.starts_with("min") {
+        Some(new > old || (new == old && new_exclusive && !old_exclusive))
+    } else if key.starts_with("max") {
+        Some(new < old || (new == old && new_exclusive && !old_exclusive))
+    } else {
+        None
+    }
+}
+
+// ── union tree (TUI diff mode) ────────────────────────────────────────────────
+
+/// How a node of the union tree differs between the two specs.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Status {
+    Added,
+    Removed,
+    Changed, // its own fields differ
+    Inside,  // only something below it differs
+}
+
+/// A node of the union tree that differs, with its fields in each spec.
+pub struct Mark {
+    pub status: Status,
+    pub old: Option<NodeInfo>,
+    pub new: Option<NodeInfo>,
+}
+
+/// The union tree's differences, by node.
+#[derive(Default)]
+pub struct Marks {
+    marks: HashMap<(View, Vec<usize>), Mark>,
+}
+
+impl Marks {
+    pub fn at(&self, view: View, path: &[usize]) -> Option<&Mark> {
+        self.marks.get(&(view, path.to_vec()))
+    }
+
+    /// How many top-level schemas were added, removed and changed.
+    pub fn counts(&self) -> (usize, usize, usize) {
+        let count = |status: Status| {
+            self.marks
+                .iter()
+                .filter(|((view, path), mark)| {
+                    *view == View::Schemas && path.len() == 1 && mark.status == status
+                })
+                .count()
+        };
+        (
+            count(Status::Added),
+            count(Status::Removed),
+            count(Status::Changed) + count(Status::Inside),
+        )
+    }
+}
+
+/// Merge two specs into one whose trees hold every node of either, in the
+/// new spec's order with removed nodes after their old predecessor, and mark
+/// each node that differs. Removed operations are appended to `operations`.
+pub fn union(old: LoadedSpec, new: LoadedSpec) -> (LoadedSpec, Marks) {
+    let mut operations = new.operations.clone();
+    let mut moved: HashMap<usize, usize> = HashMap::new(); // old operation index → merged
+    for (index, op) in old.operations.iter().enumerate() {
+        if !new
+            .operations
+            .iter()
+            .any(|o| o.method == op.method && o.path == op.path)
+        {
+            moved.insert(index, operations.len());
+            operations.push(op.clone());
+        }
+    }
+
+    let mut merge = Merge {
+        marks: Marks::default(),
+        moved,
+    };
+    let schema_nodes = merge.children(
+        View::Schemas,
+        &old.schema_nodes,
+        &new.schema_nodes,
+        &mut Vec::new(),
+    );
+    let operation_nodes = merge.children(
+        View::Operations,
+        &old.operation_nodes,
+        &new.operation_nodes,
+        &mut Vec::new(),
+    );
+
+    let mut schema_sources = old.schema_sources;
+    schema_sources.extend(new.schema_sources);
+    let mut schema_names = new.schema_names;
+    for name in old.schema_names {
+        if !schema_names.contains(&name) {
+            schema_names.push(name);
+        }
+    }
+    schema_names.sort();
+    let graph = Graph::build(&schema_nodes);
+    let usages = usage::collect(&schema_nodes, &operation_nodes);
+    let spec = LoadedSpec {
+        schema_names,
+        schema_nodes,
+        graph,
+        usages,
+        operations,
+        operation_nodes,
+        schema_sources,
+        // Diagnostics are marked by path in one spec's trees, which the
+        // union tree does not keep.
+        diagnostics: Diagnostics::default(),
+        ..new
+    };
+    (spec, merge.marks)
+}
+
+struct Merge {
+    marks: Marks,
+    moved: HashMap<usize, usize>,
+}
+
+impl Merge {
+    fn children(
+        &mut self,
+        view: View,
+        old: &[TreeNode],
+        new: &[TreeNode],
+        path: &mut Vec<usize>,
+    ) -> Vec<TreeNode> {
+        let mut pairs: Vec<(Option<&TreeNode>, Option<&TreeNode>)> = new
+            .iter()
+            .map(|n| (old.iter().find(|o| o.name == n.name), Some(n)))
+            .collect();
+        let mut at = 0;
+        for o in old {
+            match pairs
+                .iter()
+                .position(|(_, n)| n.is_some_and(|n| n.name == o.name))
+            {
+                Some(i) => at = i + 1,
+                None => {
+                    pairs.insert(at, (Some(o), None));
+                    at += 1;
+                }
+            }
+        }
+
+        let mut nodes = Vec::new();
+        for (i, pair) in pairs.into_iter().enumerate() {
+            path.push(i);
+            let node = match pair {
+                (Some(o), Some(n)) => {
+                    let children = self.children(view, &o.children, &n.children, path);
+                    let own = o.info.required != n.info.required
+                        || !info_changes(&o.info, &n.info, Flow::Both).is_empty();
+                    let inside = (0..children.len()).any(|c| {
+                        path.push(c);
+                        let marked = self.marks.at(view, path).is_some();
+                        path.pop();
+                        marked
+                    });
+                    let status = if own {
+                        Some(Status::Changed)
+                    } else {
+                        inside.then_some(Status::Inside)
+                    };
+                    if let Some(status) = status {
+                        self.mark(view, path, status, Some(&o.info), Some(&n.info));
+                    }
+                    TreeNode {
+                        children,
+                        ..n.clone()
+                    }
+                }
+                (None, Some(n)) => {
+                    self.mark_all(view, n, path, Status::Added);
+                    n.clone()
+                }
+                (Some(o), None) => {
+                    let mut node = o.clone();
+                    self.relocate(&mut node);
+                    self.mark_all(view, &node, path, Status::Removed);
+                    node
+                }
+                (None, None) => unreachable!(),
+            };
+            nodes.push(node);
+            path.pop();
+        }
+        nodes
+    }
+
+    fn mark(
+        &mut self,
+        view: View,
+        path: &[usize],
+        status: Status,
+        old: Option<&NodeInfo>,
+        new: Option<&NodeInfo>,
+    ) {
+        let mark = Mark {
+            status,
+            old: old.cloned(),
+            new: new.cloned(),
+        };
+        self.marks.marks.insert((view, path.to_vec()), mark);
+    }
+
+    /// Mark a node only one spec has, and everything below it.
+    fn mark_all(&mut self, view: View, node: &TreeNode, path: &mut Vec<usize>, status: Status) {
+        let (old, new) = match status {
+            Status::Added => (None, Some(&node.info)),
+            _ => (Some(&node.info), None),
+        };
+        self.mark(view, path, status, old, new);
+        for (i, child) in node.children.iter().enumerate() {
+            path.push(i);
+            self.mark_all(view, child, path, status);
+            path.pop();
+        }
+    }
+
+    /// Point a removed operation's nodes at its copy in the merged spec.
+    fn relocate(&self, node: &mut TreeNode) {
+        if let NodeKind::Operation(index) = &mut node.info.kind
+            && let Some(&moved) = self.moved.get(index)
+        {
+            *index = moved;
+        }
+        for child in &mut node.children {
+            self.relocate(child);
+        }
+    }
+}
+
+// ── output ────────────────────────────────────────────────────────────────────
+
+pub fn write_text(changes: &[Change], out: &mut impl Write, color: bool) -> io::Result<()> {
+    if changes.is_empty() {
+        return writeln!(out, "no changes");
+    }
+    for class in Class::ALL {
+        let changes: Vec<&Change> = changes.iter().filter(|c| c.class == class).collect();
+        if changes.is_empty() {
+            continue;
+        }
+        let heading = format!("{} ({})", class.heading(), changes.len());
+        if color {
+            let code = match class {
+                Class::Breaking => "1;31",
+                Class::NonBreaking => "1;32",
+                Class::Unknown => "1;33",
+            };
+            writeln!(out, "\x1b[{code}m{heading}\x1b[0m")?;
+        } else {
+            writeln!(out, "{heading}")?;
+        }
+        for change in changes {
+            writeln!(out, "  {}: {}", change.location, change.message)?;
+        }
+        writeln!(out)?;
+    }
+    writeln!(out, "{}", summary(changes))
+}
+
+/// `2 breaking, 5 non-breaking, 0 unknown`.
+fn summary(changes: &[Change]) -> String {
+    Class::ALL
+        .iter()
+        .map(|&class| {
+            format!(
+                "{} {}",
+                changes.iter().filter(|c| c.class == class).count(),
+                class.label()
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+pub fn to_json(changes: &[Change]) -> Value {
+    let count = |class: Class| changes.iter().filter(|c| c.class == class).count();
+    json!({
+        "breaking": count(Class::Breaking),
+        "non-breaking": count(Class::NonBreaking),
+        "unknown": count(Class::Unknown),
+        "changes": changes,
+    })
+}
+
+/// A Markdown report, e.g. for a pull request comment.
+pub fn to_markdown(changes: &[Change]) -> String {
+    let mut out = String::from("# API changes\n\n");
+    if changes.is_empty() {
+        out.push_str("No changes.\n");
+        return out;
+    }
+    out.push_str(&format!("{}\n", summary(changes)));
+    for class in Class::ALL {
+        let changes: Vec<&Change> = changes.iter().filter(|c| c.class == class).collect();
+        if changes.is_empty() {
+            continue;
+        }
+        out.push_str(&format!("\n## {} ({})\n\n", class.heading(), changes.len()));
+        for change in changes {
+            out.push_str(&format!(
+                "- `{}`: {}\n",
+                change.location.replace('`', "'"),
+                change.message
+            ));
+        }
+    }
+    out
+}
+
+// ── tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_spec;
+
+    const OLD: &str = r##"
+openapi: 3.0.3
+info: { title: Shop, version: "1" }
+paths:
+  /orders:
+    get:
+      operationId: listOrders
+      parameters:
+        - { name: limit, in: query, schema: { type: integer, maximum: 100 } }
+      responses:
+        "200":
+          description: ok
+          content:
+            application/json:
+              schema: { type: array, items: { $ref: "#/components/schemas/Order" } }
+    post:
+      operationId: createOrder
+      requestBody:
+        required: true
+        content:
+          application/json:
+            schema: { $ref: "#/components/schemas/NewOrder" }
+      responses:
+        "201": { description: created }
+  /legacy:
+    get:
+      responses:
+        "200": { description: ok }
+components:
+  schemas:
+    Order:
+      type: object
+      required: [id]
+      properties:
+        id: { type: string }
+        status: { type: string, enum: [open, shipped] }
+        note: { type: string }
+    NewOrder:
+      type: object
+      properties:
+        quantity: { type: integer, minimum: 1 }
+        coupon: { type: string }
+    Unused: { type: string }
+"##;
+
+    const NEW: &str = r##"
+openapi: 3.0.3
+info: { title: Shop, version: "2" }
+paths:
+  /orders:
+    get:
+      operationId: listOrders
+      parameters:
+        - { name: limit, in: query, schema: { type: integer, maximum: 50 } }
+        - { name: tenant, in: header, required: true, schema: { type: string } }
+      responses:
+        "200":
+          description: ok
+          content:
+            application/json:
+              schema: { type: array, items: { $ref: "#/components/schemas/Order" } }
+    post:
+      operationId: createOrder
+      requestBody:
+        required: true
+        content:
+          application/json:
+            schema: { $ref: "#/components/schemas/NewOrder" }
+      responses:
+        "201": { description: created }
+        "409": { description: conflict }
+components:
+  schemas:
+    Order:
+      type: object
+      required: [id]
+      properties:
+        id: { type: integer, format: int64 }
+        status: { type: string, enum: [open, shipped, cancelled] }
+        total: { type: number }
+    NewOrder:
+      type: object
+      required: [quantity]
+      properties:
+        quantity: { type: integer, minimum: 1, maximum: 10 }
+        coupon: { type: string }
+        gift: { type: boolean }
+"##;
+
+    fn changes() -> Vec<(Class, String, String)> {
+        let old = parse_spec(OLD).unwrap();
+        let new = parse_spec(NEW).unwrap();
+        diff(&old, &new)
+            .into_iter()
+            .map(|c| (c.class, c.location, c.message))
+            .collect()
+    }
+
+    fn class_of(changes: &[(Class, String, String)], location: &str, message: &str) -> Class {
+        changes
+            .iter()
+            .find(|(_, l, m)| l == location && m == message)
+            .unwrap_or_else(|| panic!("no change {location}: {message} in {changes:#?}"))
+            .0
+    }
+
+    #[test]
+    fn classifies_schema_changes_by_how_they_are_used() {
+        let changes = changes();
+        // Order is only read: fewer guarantees break clients, more values too.
+        assert_eq!(
+            class_of(
+                &changes,
+                "Order › id",
+                "type changed from string to integer"
+            ),
+            Class::Breaking
+        );
+        assert_eq!(
+            class_of(
+                &changes,
+                "Order › status",
+                "enum values added: \"cancelled\""
+            ),
+            Class::Breaking
+        );
+        assert_eq!(
+            class_of(&changes, "Order › note", "property removed"),
+            Class::Breaking
+        );
+        assert_eq!(
+            class_of(&changes, "Order › total", "optional property added"),
+            Class::NonBreaking
+        );
+        // NewOrder is only sent: tighter rules break clients.
+        assert_eq!(
+            class_of(&changes, "NewOrder › quantity", "now required"),
+            Class::Breaking
+        );
+        assert_eq!(
+            class_of(&changes, "NewOrder › quantity", "constraint added: max: 10"),
+            Class::Breaking
+        );
+        assert_eq!(
+            class_of(&changes, "NewOrder › gift", "optional property added"),
+            Class::NonBreaking
+        );
+        assert_eq!(
+            class_of(&changes, "Unused", "schema removed"),
+            Class::Breaking
+        );
+    }
+
+    #[test]
+    fn classifies_operation_changes() {
+        let changes = changes();
+        assert_eq!(
+            class_of(&changes, "GET /legacy", "operation removed"),
+            Class::Breaking
+        );
+        assert_eq!(
+            class_of(
+                &changes,
+                "GET /orders › parameters › tenant (header)",
+                "required parameter added"
+            ),
+            Class::Breaking
+        );
+        assert_eq!(
+            class_of(
+                &changes,
+                "GET /orders › parameters › limit (query)",
+                "max changed from 100 to 50"
+            ),
+            Class::Breaking
+        );
+        assert_eq!(
+            class_of(&changes, "POST /orders › responses › 409", "response added"),
+            Class::NonBreaking
+        );
+        // Changes inside Order are reported once, at the schema, not again
+        // under every operation that refers to it.
+        assert!(
+            !changes
+                .iter()
+                .any(|(_, l, _)| l.starts_with("GET /orders › responses"))
+        );
+    }
+
+    #[test]
+    fn identical_specs_have_no_changes() {
+        let spec = parse_spec(OLD).unwrap();
+        assert!(diff(&spec, &parse_spec(OLD).unwrap()).is_empty());
+        let mut out = Vec::new();
+        write_text(&[], &mut out, false).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "no changes\n");
+    }
+
+    #[test]
+    fn union_tree_keeps_both_sides_and_marks_them() {
+        let (spec, marks) = union(parse_spec(OLD).unwrap(), parse_spec(NEW).unwrap());
+        let names = |nodes: &[TreeNode]| nodes.iter().map(|n| n.name.clone()).collect::<Vec<_>>();
+        assert_eq!(
+            names(&spec.schema_nodes),
+            vec!["NewOrder", "Order", "Unused"]
+        );
+        // A removed property sits after its old predecessor.
+        assert_eq!(
+            names(&spec.schema_nodes[1].children),
+            vec!["id", "status", "note", "total"]
+        );
+
+        let status = |path: &[usize]| marks.at(View::Schemas, path).map(|m| m.status);
+        assert_eq!(status(&[1]), Some(Status::Inside));
+        assert_eq!(status(&[1, 0]), Some(Status::Changed));
+        assert_eq!(status(&[1, 2]), Some(Status::Removed));
+        assert_eq!(status(&[1, 3]), Some(Status::Added));
+        assert_eq!(status(&[2]), Some(Status::Removed));
+        let id = marks.at(View::Schemas, &[1, 0]).unwrap();
+        assert!(matches!(id.old.as_ref().unwrap().kind, NodeKind::Str));
+        assert!(matches!(id.new.as_ref().unwrap().kind, NodeKind::Integer));
+        assert_eq!(marks.counts(), (0, 1, 2));
+
+        // A removed operation still opens: it is appended to the operations.
+        let legacy = spec.operation_nodes[0]
+            .children
+            .iter()
+            .find(|n| n.name == "GET /legacy")
+            .unwrap();
+        let NodeKind::Operation(index) = legacy.info.kind else {
+            panic!("not an operation");
+        };
+        assert_eq!(spec.operations[index].path, "/legacy");
+    }
+
+    #[test]
+    fn union_tree_marks_added_removed_and_changed_operations() {
+        let (spec, marks) = union(parse_spec(OLD).unwrap(), parse_spec(NEW).unwrap());
+        let tag = &spec.operation_nodes[0];
+        let names: Vec<&str> = tag.children.iter().map(|n| n.name.as_str()).collect();
+        assert_eq!(names, vec!["GET /orders", "POST /orders", "GET /legacy"]);
+
+        let status = |path: &[usize]| marks.at(View::Operations, path).map(|m| m.status);
+        assert_eq!(status(&[0]), Some(Status::Inside));
+        // GET /orders › parameters › limit (max changed) and tenant (added).
+        assert_eq!(status(&[0, 0]), Some(Status::Inside));
+        assert_eq!(status(&[0, 0, 0]), Some(Status::Inside));
+        assert_eq!(status(&[0, 0, 0, 0]), Some(Status::Changed));
+        assert_eq!(status(&[0, 0, 0, 1]), Some(Status::Added));
+        let added = marks.at(View::Operations, &[0, 0, 0, 1]).unwrap();
+        assert!(added.old.is_none() && added.new.is_some());
+        // The 200 response is written the same, but the Order it refers to changed.
+        assert_eq!(status(&[0, 0, 1, 0]), Some(Status::Inside));
+        // POST /orders › responses: 201 is the same in both, 409 is new.
+        assert_eq!(status(&[0, 1, 1, 0]), None);
+        assert_eq!(status(&[0, 1, 1, 1]), Some(Status::Added));
+        // A removed operation is marked all the way down.
+        assert_eq!(status(&[0, 2]), Some(Status::Removed));
+        assert_eq!(status(&[0, 2, 0]), Some(Status::Removed));
+        assert_eq!(status(&[0, 2, 0, 0]), Some(Status::Removed));
+        let removed = marks.at(View::Operations, &[0, 2]).unwrap();
+        assert!(removed.old.is_some() && removed.new.is_none());
+    }
+
+    #[test]
+    fn union_tree_marks_required_flag_changes() {
+        let (spec, marks) = union(parse_spec(OLD).unwrap(), parse_spec(NEW).unwrap());
+        let new_order = &spec.schema_nodes[0];
+        let names: Vec<&str> = new_order.children.iter().map(|n| n.name.as_str()).collect();
+        assert_eq!(names, vec!["quantity", "coupon", "gift"]);
+
+        let quantity = marks.at(View::Schemas, &[0, 0]).unwrap();
+        assert_eq!(quantity.status, Status::Changed);
+        assert!(!quantity.old.as_ref().unwrap().required);
+        assert!(quantity.new.as_ref().unwrap().required);
+        // The union tree shows the new side of a changed node.
+        assert!(new_order.children[0].info.required);
+        assert!(marks.at(View::Schemas, &[0, 1]).is_none());
+        assert_eq!(
+            marks.at(View::Schemas, &[0, 2]).unwrap().status,
+            Status::Added
+        );
+
+        // Only the required flag differs: still a change of the node's own.
+        let flip = |required: &str| {
+            let spec = r#"
+openapi: 3.0.3
+info: { title: Flags, version: "1" }
+paths: {}
+components:
+  schemas:
+    A:
+      type: object
+      required: [REQUIRED]
+      properties:
+        x: { type: string }
+        y: { type: string }
+"#;
+            spec.replace("REQUIRED", required)
+        };
+        let (_, marks) = union(
+            parse_spec(&flip("x")).unwrap(),
+            parse_spec(&flip("y")).unwrap(),
+        );
+        let status = |path: &[usize]| marks.at(View::Schemas, path).map(|m| m.status);
+        assert_eq!(status(&[0, 0]), Some(Status::Changed));
+        assert_eq!(status(&[0, 1]), Some(Status::Changed));
+        assert_eq!(marks.counts(), (0, 0, 1));
+    }
+
+    #[test]
+    fn reports_in_every_format() {
+        let old = parse_spec(OLD).unwrap();
+        let new = parse_spec(NEW).unwrap();
+        let changes = diff(&old, &new);
+        let json = to_json(&changes);
+        assert!(json["breaking"].as_u64().unwrap() > 0);
+        assert_eq!(json["changes"][0]["class"], "breaking");
+
+        let markdown = to_markdown(&changes);
+        assert!(markdown.contains("## Breaking changes ("));
+        assert!(markdown.contains("- `GET /legacy`: operation removed\n"));
+
+        let mut text = Vec::new();
+        write_text(&changes, &mut text, false).unwrap();
+        let text = String::from_utf8(text).unwrap();
+        assert!(text.starts_with("Breaking changes ("));
+        assert!(text.trim_end().ends_with(&summary(&changes)));
+    }
+}
@@ -0,0 +1,630 @@
+use serde_json::{Map, Value};
+
+use crate::tree::{NodeKind, TreeNode};
+
+/// Optional properties are left out from this depth on, so a schema that
+/// reaches many others still gives an example of readable size.
+const OPTIONAL_DEPTH: usize = 4;
+
+/// Keyword nodes listed among an object's properties (3.1 lets a schema mix
+/// them); none of them is a member of the instance.
+pub const KEYWORD_CHILDREN: &[&str] = &[
+    "unevaluatedProperties",
+    "propertyNames",
+    "if",
+    "then",
+    "else",
+    "not",
+];
+
+/// A realistic JSON value for the schema at `node`: its own `example`,
+/// `default` or first `enum` value when it has one, otherwise one built from
+/// its type, format and constraints. `$ref`s are followed into `schemas` (the
+/// component schema roots) and cut where they lead back into themselves.
+/// `None` for nodes that are not schemas (tags, operations, keyword groups).
+pub fn generate(node: &TreeNode, schemas: &[TreeNode]) -> Option<Value> {
+    if !node.info.kind.is_schema() {
+        return None;
+    }
+    let mut generator = Generator {
+        schemas,
+        stack: Vec::new(),
+    };
+    // A component schema is already being generated when it refers to itself.
+    if let Some(root) = schemas.iter().find(|s| std::ptr::eq(*s, node)) {
+        generator.stack.push(&root.name);
+    }
+    Some(
+        generator
+            .value(node, 0)
+            .unwrap_or_else(|| Value::Object(Map::new())),
+    )
+}
+
+struct Generator<'a> {
+    schemas: &'a [TreeNode],
+    stack: Vec<&'a str>, // `$ref` targets being generated, outermost first
+}
+
+impl<'a> Generator<'a> {
+    /// `None` where a `$ref` leads back into a schema being generated.
+    fn value(&mut self, node: &'a TreeNode, depth: usize) -> Option<Value> {
+        match given(node) {
+            Some(value) => Some(value),
+            None => self.typed(&node.info.kind, node, depth),
+        }
+    }
+
+    fn typed(&mut self, kind: &NodeKind, node: &'a TreeNode, depth: usize) -> Option<Value> {
+        let value = match kind {
+            NodeKind::Ref(target) => return self.reference(target, node, depth),
+            NodeKind::AllOf => self.all_of(&node.children, depth),
+            NodeKind::OneOf | NodeKind::AnyOf => return self.one_of(&node.children, depth),
+            NodeKind::Conditional => {
+                let branch = child(node, "then").or_else(|| child(node, "if"));
+                return branch.map_or(Some(Value::Null), |b| self.value(b, depth));
+            }
+            // Several types allowed: the first will do.
+            NodeKind::Multi(types) => {
+                let first = types.first().map_or("null", String::as_str);
+                return self.typed(&type_kind(first), node, depth);
+            }
+            NodeKind::Object | NodeKind::Schema | NodeKind::Unknown => self.object(node, depth),
+            NodeKind::Array => self.array(node, depth),
+            NodeKind::Tuple => Value::Array(
+                node.children
+                    .iter()
+                    .filter(|c| c.name.starts_with('['))
+                    .map(|c| self.value(c, depth + 1).unwrap_or(Value::Null))
+                    .collect(),
+            ),
+            NodeKind::Str => Value::String(string(node)),
+            NodeKind::Integer => integer(node),
+            NodeKind::Number => number(node),
+            NodeKind::Boolean => Value::Bool(true),
+            _ => Value::Null,
+        };
+        Some(value)
+    }
+
+    fn reference(&mut self, target: &str, node: &'a TreeNode, depth: usize) -> Option<Value> {
+        if self.stack.contains(&target) {
+            return None;
+        }
+        match self.schemas.iter().find(|s| s.name == target) {
+            Some(schema) => {
+                self.stack.push(&schema.name);
+                let value = self.value(schema, depth);
+                self.stack.pop();
+                value
+            }
+            // Not a component (a 3.1 `$defs` entry): its children are already
+            // expanded under the reference.
+            None => Some(self.object(node, depth)),
+        }
+    }
+
+    /// Every property (optional ones only near the top), members of any
+    /// combiner alongside them, and one entry for a map's
+    /// `additionalProperties`.
+    fn object(&mut self, node: &'a TreeNode, depth: usize) -> Value {
+        let mut map = Map::new();
+        let mut combined = Vec::new();
+        let mut additional = None;
+        for child in &node.children {
+            match (child.name.as_str(), &child.info.kind) {
+                ("additionalProperties", _) => additional = Some(child),
+                ("allOf", NodeKind::AllOf)
+                | ("oneOf", NodeKind::OneOf)
+                | ("anyOf", NodeKind::AnyOf) => combined.push(child),
+                (name, _) if KEYWORD_CHILDREN.contains(&name) => {}
+                (_, NodeKind::Group(_)) => {}
+                (name, _) => {
+                    if !child.info.required && depth >= OPTIONAL_DEPTH {
+                        continue;
+                    }
+                    match self.value(child, depth + 1) {
+                        Some(value) => {
+                            map.insert(name.to_string(), value);
+                        }
+                        // A required property closing a cycle still has to be there.
+                        None if child.info.required => {
+                            map.insert(name.to_string(), Value::Object(Map::new()));
+                        }
+                        None => {}
+                    }
+                }
+            }
+        }
+        for combiner in combined {
+            if let Some(Value::Object(members)) = self.value(combiner, depth) {
+                for (name, value) in members {
+                    map.entry(name).or_insert(value);
+                }
+            }
+        }
+        if map.is_empty()
+            && let Some(additional) = additional
+            && let Some(value) = self.value(additional, depth + 1)
+        {
+            map.insert("key".to_string(), value);
+        }
+        Value::Object(map)
+    }
+
+    /// `minItems` items (at least one, at most `maxItems`), made distinct
+    /// when `uniqueItems` asks for it.
+    fn array(&mut self, node: &'a TreeNode, depth: usize) -> Value {
+        let min = node.info.bound("minItems").unwrap_or(0).max(1);
+        let count = node.info.bound("maxItems").map_or(min, |max| min.min(max));
+        let item = child(node, "items").and_then(|items| self.value(items, depth + 1));
+        let Some(item) = item.filter(|_| count > 0) else {
+            return Value::Array(Vec::new());
+        };
+        let unique = node
+            .info
+            .constraints
+            .iter()
+            .any(|c| c == "uniqueItems: true");
+        Value::Array(
+            (0..count)
+                .map(|i| if unique { vary(&item, i) } else { item.clone() })
+                .collect(),
+        )
+    }
+
+    /// The members of every branch merged, or the last branch that is not an
+    /// object.
+    fn all_of(&mut self, branches: &'a [TreeNode], depth: usize) -> Value {
+        let mut merged = Map::new();
+        let mut other = None;
+        for branch in branches {
+            match self.value(branch, depth) {
+                Some(Value::Object(members)) => merged.extend(members),
+                Some(value) => other = Some(value),
+                None => {}
+            }
+        }
+        match other {
+            Some(value) if merged.is_empty() => value,
+            _ => Value::Object(merged),
+        }
+    }
+
+    /// The first branch that is not `null` and does not close a cycle.
+    fn one_of(&mut self, branches: &'a [TreeNode], depth: usize) -> Option<Value> {
+        branches
+            .iter()
+            .filter(|b| !matches!(b.info.kind, NodeKind::Null))
+            .find_map(|b| self.value(b, depth))
+    }
+}
+
+/// The value the spec gives for the node, if any.
+fn given(node: &TreeNode) -> Option<Value> {
+    let info = &node.info;
+    let text = info
+        .example
+        .as_deref()
+        .or(info.examples.first().map(String::as_str))
+        .or(info.default_val.as_deref())
+        .or_else(|| info.constraint("const"))
+        .or(info.enum_values.first().map(String::as_str))?;
+    Some(literal(text))
+}
+
+/// A value as `NodeInfo` keeps it (JSON text). 3.0 enum strings are quoted
+/// but not escaped, so those that do not parse are taken as they read.
+pub fn literal(text: &str) -> Value {
+    serde_json::from_str(text).unwrap_or_else(|_| Value::String(text.trim_matches('"').to_string()))
+}
+
+fn child<'a>(node: &'a TreeNode, name: &str) -> Option<&'a TreeNode> {
+    node.children.iter().find(|c| c.name == name)
+}
+
+fn type_kind(name: &str) -> NodeKind {
+    match name {
+        "object" => NodeKind::Object,
+        "array" => NodeKind::Array,
+        "string" => NodeKind::Str,
+        "integer" => NodeKind::Integer,
+        "number" => NodeKind::Number,
+        "boolean" => NodeKind::Boolean,
+        "null" => NodeKind::Null,
+        _ => NodeKind::Unknown,
+    }
+}
+
+fn string(node: &TreeNode) -> String {
+    if let Some(sample) = node.info.format.as_deref().and_then(formatted) {
+        return sample.to_string();
+    }
+    if let Some(sample) = node.info.constraint("pattern").and_then(pattern) {
+        return sample;
+    }
+    let min = node.info.bound("minLength").unwrap_or(0);
+    let len = node
+        .info
+        .bound("maxLength")
+        .map_or(min.max(6), |max| min.max(6).min(max));
+    "string".chars().cycle().take(len).collect()
+}
+
+/// A sample for the string formats of JSON Schema and OpenAPI.
+fn formatted(format: &str) -> Option<&'static str> {
+    Some(match format {
+        "date-time" => "2024-01-15T09:30:00Z",
+        "date" => "2024-01-15",
+        "time" => "09:30:00Z",
+        "duration" => "P3D",
+        "email" | "idn-email" => "user@example.com",
+        "hostname" | "idn-hostname" => "example.com",
+        "ipv4" => "192.0.2.10",
+        "ipv6" => "2001:db8::10",
+        "uri" | "url" | "iri" => "https://example.com/resource",
+        "uri-reference" | "iri-reference" => "/resource",
+        "uri-template" => "https://example.com/resource/{id}",
+        "uuid" => "3fa85f64-5717-4562-b3fc-2c963f66afa6",
+        "json-pointer" => "/items/0",
+        "relative-json-pointer" => "0/items",
+        "regex" => "^[a-z]+$",
+        "byte" => "c3BlcQ==",
+        "password" => "s3cr3t-passw0rd",
+        _ => return None,
+    })
+}
+
+fn integer(node: &TreeNode) -> Value {
+    let lo = node
+        .info
+        .limit("min")
+        .map(|(v, exclusive)| if exclusive { v.floor() + 1.0 } else { v.ceil() });
+    let hi = node
+        .info
+        .limit("max")
+        .map(|(v, exclusive)| if exclusive { v.ceil() - 1.0 } else { v.floor() });
+    let mut n = lo.map_or(1.0, |lo| lo.max(1.0));
+    if let Some(hi) = hi {
+        n = n.min(hi);
+    }
+    n = multiple(node, n, hi);
+    Value::from(n as i64)
+}
+
+fn number(node: &TreeNode) -> Value {
+    let lo = node.info.limit("min");
+    let hi = node.info.limit("max");
+    let n = match (lo, hi) {
+        (Some((lo, _)), Some((hi, _))) => (lo + hi) / 2.0,
+        (Some((lo, exclusive)), None) => (lo + if exclusive { 1.0 } else { 0.0 }).max(1.5),
+        (None, Some((hi, exclusive))) => 1.5f64.min(if exclusive { hi - 1.0 } else { hi }),
+        (None, None) => 1.5,
+    };
+    let n = multiple(node, n, hi.map(|(hi, _)| hi));
+    Value::from(n)
+}
+
+/// `n` rounded up to a `multipleOf`, or down when that passes `hi`.
+fn multiple(node: &TreeNode, n: f64, hi: Option<f64>) -> f64 {
+    let Some(step) = node
+        .info
+        .constraint("multipleOf")
+        .and_then(|s| s.parse::<f64>().ok())
+    else {
+        return n;
+    };
+    if step <= 0.0 {
+        return n;
+    }
+    let up = (n / step).ceil() * step;
+    match hi {
+        Some(hi) if up > hi => (n / step).floor() * step,
+        _ => up,
+    }
+}
+
+/// The `i`th of several distinct items built from one.
+fn vary(value: &Value, i: usize) -> Value {
+    if i == 0 {
+        return value.clone();
+    }
+    match value {
+        Value::String(s) => Value::String(format!("{s}{i}")),
+        Value::Number(n) => match n.as_i64() {
+            Some(n) => Value::from(n + i as i64),
+            None => Value::from(n.as_f64().unwrap_or(0.0) + i as f64),
+        },
+        Value::Bool(b) => Value::Bool(*b ^ (i % 2 == 1)),
+        other => other.clone(),
+    }
+}
+
+// ── patterns ──────────────────────────────────────────────────────────────────
+
+/// A string matching `pattern`, for the regex syntax patterns mostly use:
+/// literals, classes, groups with alternation, anchors and quantifiers.
+/// `None` for anything else (lookaround, backreferences).
+fn pattern(pattern: &str) -> Option<String> {
+    let chars: Vec<char> = pattern.chars().collect();
+    let mut pos = 0;
+    let sample = alternation(&chars, &mut pos)?;
+    (pos == chars.len()).then_some(sample)
+}
+
+enum Atom {
+    Text(String),
+    Class(Vec<char>),
+}
+
+impl Atom {
+    /// The `i`th repetition: classes cycle through their members.
+    fn nth(&self, i: usize) -> String {
+        match self {
+            Atom::Text(text) => text.clone(),
+            Atom::Class(members) => members[i % members.len()].to_string(),
+        }
+    }
+}
+
+/// Its first branch; the others are parsed only to step over them.
+fn alternation(chars: &[char], pos: &mut usize) -> Option<String> {
+    let first = sequence(chars, pos)?;
+    while chars.get(*pos) == Some(&'|') {
+        *pos += 1;
+        sequence(chars, pos)?;
+    }
+    Some(first)
+}
+
+fn sequence(chars: &[char], pos: &mut usize) -> Option<String> {
+    let mut out = String::new();
+    while let Some(&c) = chars.get(*pos) {
+        if c == '|' || c == ')' {
+            break;
+        }
+        *pos += 1;
+        let atom = match c {
+            '^' | '$' => continue,
+            '(' => {
+                match chars.get(*pos..*pos + 2) {
+                    Some(['?', ':']) => *pos += 2,
+                    _ if chars.get(*pos) == Some(&'?') => return None,
+                    _ => {}
+                }
+                let inner = alternation(chars, pos)?;
+                if chars.get(*pos) != Some(&')') {
+                    return None;
+                }
+                *pos += 1;
+                Atom::Text(inner)
+            }
+            '[' => Atom::Class(class(chars, pos)?),
+            '\\' => escape(chars, pos)?,
+            '.' => Atom::Class(('a'..='z').collect()),
+            '*' | '+' | '?' | '{' => return None,
+            c => Atom::Text(c.to_string()),
+        };
+        let (min, max) = quantifier(chars, pos)?;
+        let count = if min > 0 {
+            min
+        } else {
+            max.map_or(1, |max| max.min(1))
+        };
+        for i in 0..count {
+            out.push_str(&atom.nth(i));
+        }
+    }
+    Some(out)
+}
+
+/// `?`, `*`, `+` or `{n}` / `{n,}` / `{n,m}` after an atom, as `(min, max)`.
+fn quantifier(chars: &[char], pos: &mut usize) -> Option<(usize, Option<usize>)> {
+    let range = match chars.get(*pos) {
+        Some('?') => (0, Some(1)),
+        Some('*') => (0, None),
+        Some('+') => (1, None),
+        Some('{') => {
+            let close = chars[*pos..].iter().position(|&c| c == '}')? + *pos;
+            let body: String = chars[*pos + 1..close].iter().collect();
+            let range = match body.split_once(',') {
+                Some((min, "")) => (min.trim().parse().ok()?, None),
+                Some((min, max)) => (min.trim().parse().ok()?, Some(max.trim().parse().ok()?)),
+                None => {
+                    let n = body.trim().parse().ok()?;
+                    (n, Some(n))
+                }
+            };
+            *pos = close;
+            range
+        }
+        _ => return Some((1, Some(1))),
+    };
+    *pos += 1;
+    // Lazy and possessive forms match the same strings.
+    if matches!(chars.get(*pos), Some('?' | '+')) {
+        *pos += 1;
+    }
+    Some(range)
+}
+
+/// Members of a `[...]` class (the `[` already read), a few per range.
+fn class(chars: &[char], pos: &mut usize) -> Option<Vec<char>> {
+    let negated = chars.get(*pos) == Some(&'^');
+    if negated {
+        *pos += 1;
+    }
+    let mut ranges: Vec<(char, char)> = Vec::new();
+    let mut first = true;
+    loop {
+        let c = *chars.get(*pos)?;
+        *pos += 1;
+        if c == ']' && !first {
+            break;
+        }
+        first = false;
+        let lo = if c == '\\' {
+            let escaped = *chars.get(*pos)?;
+            *pos += 1;
+            match escaped {
+                'd' => {
+                    ranges.push(('0', '9'));
+                    continue;
+                }
+                'w' => {
+                    ranges.extend([('a', 'z'), ('A', 'Z'), ('0', '9'), ('_', '_')]);
+                    continue;
+                }
+                's' => {
+                    ranges.push((' ', ' '));
+                    continue;
+                }
+                other => other,
+            }
+        } else {
+            c
+        };
+        match (chars.get(*pos), chars.get(*pos + 1)) {
+            (Some('-'), Some(&hi)) if hi != ']' => {
+                *pos += 2;
+                ranges.push((lo, hi));
+            }
+            _ => ranges.push((lo, lo)),
+        }
+    }
+    let members: Vec<char> = if negated {
+        ('a'..='z')
+            .chain('0'..='9')
+            .filter(|c| !ranges.iter().any(|(lo, hi)| (lo..=hi).contains(&c)))
+            .collect()
+    } else {
+        ranges
+            .iter()
+            .flat_map(|&(lo, hi)| (lo..=hi).take(26))
+            .collect()
+    };
+    (!members.is_empty()).then_some(members)
+}
+
+/// The atom for `\x` (the `\` already read).
+fn escape(chars: &[char], pos: &mut usize) -> Option<Atom> {
+    let c = *chars.get(*pos)?;
+    *pos += 1;
+    Some(match c {
+        'd' => Atom::Class(('0'..='9').collect()),
+        'w' => Atom::Class(('a'..='z').collect()),
+        's' => Atom::Text(" ".to_string()),
+        'D' | 'S' => Atom::Text("a".to_string()),
+        'W' => Atom::Text("-".to_string()),
+        'b' | 'B' | 'A' | 'z' | 'Z' => Atom::Text(String::new()),
+        'n' => Atom::Text("\n".to_string()),
+        't' => Atom::Text("\t".to_string()),
+        'u' => {
+            let hex: String = chars.get(*pos..*pos + 4)?.iter().collect();
+            *pos += 4;
+            Atom::Text(char::from_u32(u32::from_str_radix(&hex, 16).ok()?)?.to_string())
+        }
+        '1'..='9' => return None,
+        other => Atom::Text(other.to_string()),
+    })
+}
+
+// ── tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const SPEC: &str = r##"
+openapi: 3.1.0
+info: { title: Shop, version: "1" }
+paths: {}
+components:
+  schemas:
+    Order:
+      type: object
+      required: [id, placedAt, lines, status, customer]
+      properties:
+        id: { type: string, format: uuid }
+        placedAt: { type: string, format: date-time }
+        status: { type: string, enum: [pending, shipped] }
+        reference: { type: string, pattern: "^ORD-[0-9]{4}-[A-Z]{2}$" }
+        note: { type: string, minLength: 10, maxLength: 12 }
+        quantity: { type: integer, minimum: 5, maximum: 9, multipleOf: 3 }
+        discount: { type: number, exclusiveMinimum: 0, exclusiveMaximum: 1 }
+        lines:
+          type: array
+          minItems: 2
+          uniqueItems: true
+          items: { type: string, format: email }
+        customer: { $ref: "#/components/schemas/Customer" }
+        payment:
+          oneOf:
+            - { type: "null" }
+            - { $ref: "#/components/schemas/Card" }
+        parent: { $ref: "#/components/schemas/Order" }
+    Customer:
+      allOf:
+        - { $ref: "#/components/schemas/Named" }
+        - type: object
+          properties:
+            vip: { type: boolean, default: false }
+    Named:
+      type: object
+      required: [name]
+      properties:
+        name: { type: string, example: Ada }
+    Card:
+      type: object
+      properties:
+        last4: { type: string, pattern: "^\\d{4}$" }
+    Tree:
+      type: object
+      required: [children]
+      properties:
+        children: { type: array, items: { $ref: "#/components/schemas/Tree" } }
+"##;
+
+    fn example(name: &str) -> Value {
+        let spec = crate::parser::parse_spec(SPEC).unwrap();
+        let root = spec.schema_nodes.iter().find(|n| n.name == name).unwrap();
+        generate(root, &spec.schema_nodes).unwrap()
+    }
+
+    #[test]
+    fn builds_values_from_types_formats_and_constraints() {
+        let order = example("Order");
+        assert_eq!(order["id"], "3fa85f64-5717-4562-b3fc-2c963f66afa6");
+        assert_eq!(order["placedAt"], "2024-01-15T09:30:00Z");
+        assert_eq!(order["status"], "pending");
+        assert_eq!(order["reference"], "ORD-0123-AB");
+        assert_eq!(order["note"], "stringstri");
+        assert_eq!(order["quantity"], 6);
+        assert_eq!(order["discount"], 0.5);
+        assert_eq!(
+            order["lines"],
+            json!(["user@example.com", "user@example.com1"])
+        );
+        assert_eq!(order["payment"], json!({ "last4": "0123" }));
+    }
+
+    #[test]
+    fn merges_all_of_and_cuts_cycles() {
+        let order = example("Order");
+        assert_eq!(order["customer"], json!({ "name": "Ada", "vip": false }));
+        // Order › parent → Order is a cycle: the optional property is left out.
+        assert!(order.get("parent").is_none());
+        assert_eq!(example("Tree"), json!({ "children": [] }));
+    }
+
+    #[test]
+    fn samples_common_patterns() {
+        assert_eq!(pattern("^[a-z]{3}-\\d+$").as_deref(), Some("abc-0"));
+        assert_eq!(
+            pattern("^(?:foo|bar)_[^0-9]?x*$").as_deref(),
+            Some("foo_ax")
+        );
+        assert_eq!(pattern("^(?=a)"), None);
+    }
+}
@@ -0,0 +1,204 @@
+use anyhow::Result;
+use rusqlite::{Connection, params};
+
+/// Wraps a matched term in `snippet()` / `highlight()` output. Control
+/// characters, so they cannot clash with anything in a spec's text.
+pub const MATCH_START: char = '\u{2}';
+pub const MATCH_END: char = '\u{3}';
+
+/// One row of `search_index` that matched a query. `schema`, `property` and
+/// `snippet` carry `MATCH_START` / `MATCH_END` around the matched terms.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hit {
+    pub service: String,
+    pub schema_name: String,           // plain, for locating the schema
+    pub property_name: Option<String>, // plain; None for a schema-level row
+    pub schema: String,
+    pub property: Option<String>,
+    pub snippet: String, // description excerpt, empty if it has none
+}
+
+impl Hit {
+    /// `service › schema › property`, highlighted.
+    pub fn label(&self) -> String {
+        let mut label = format!("{} › {}", self.service, self.schema);
+        if let Some(property) = &self.property {
+            label.push_str(" › ");
+            label.push_str(property);
+        }
+        label
+    }
+}
+
+/// The cross-service search popup: its query and the current hits.
+#[derive(Default)]
+pub struct GlobalSearch {
+    pub query: String,
+    pub hits: Vec<Hit>,
+    pub selected: usize,
+}
+
+/// Search every indexed service, best hits first.
+///
+/// bm25 weights a hit on a schema or property name well above one in a
+/// description, so `iban` lists the `iban` fields before prose mentioning IBANs.
+/// Property rows that only matched through their schema's name go last: for
+/// `CustomerAddress` the schema itself comes first, not each of its fields.
+pub fn search(conn: &Connection, query: &str, limit: usize) -> Result<Vec<Hit>> {
+    let Some(expr) = match_expr(query) else {
+        return Ok(Vec::new());
+    };
+    let mut stmt = conn.prepare(
+        "SELECT service_name, schema_name, property_name,
+                highlight(search_index, 1, ?2, ?3),
+                highlight(search_index, 2, ?2, ?3) AS property,
+                snippet(search_index, 3, ?2, ?3, '…', 12) AS excerpt
+         FROM search_index
+         WHERE search_index MATCH ?1
+         ORDER BY property_name != '' AND instr(property, ?2) = 0 AND instr(excerpt, ?2) = 0,
+                  bm25(search_index, 0.0, 10.0, 10.0, 1.0)
+         LIMIT ?4",
+    )?;
+    let hits = stmt
+        .query_map(
+            params![
+                expr,
+                MATCH_START.to_string(),
+                MATCH_END.to_string(),
+                limit as i64
+            ],
+            |row| {
+                let property_name: String = row.get(2)?;
+                let property: String = row.get(4)?;
+                let has_property = !property_name.is_empty();
+                Ok(Hit {
+                    service: row.get(0)?,
+                    schema_name: row.get(1)?,
+                    property_name: has_property.then_some(property_name),
+                    schema: row.get(3)?,
+                    property: has_property.then_some(property),
+                    snippet: row.get(5)?,
+                })
+            },
+        )?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    Ok(hits)
+}
+
+/// Turn what the user typed into an FTS5 query: every word must appear, as a
+/// prefix so results show up while typing. Quoting each word keeps FTS5
+/// operators and punctuation in the input from being parsed as syntax.
+/// Searches the name and description columns, never the service name.
+fn match_expr(query: &str) -> Option<String> {
+    let terms: Vec<String> = query
+        .split_whitespace()
+        .map(|word| word.replace('"', ""))
+        .filter(|word| word.chars().any(char::is_alphanumeric))
+        .map(|word| format!("\"{word}\"*"))
+        .collect();
+    if terms.is_empty() {
+        return None;
+    }
+    Some(format!(
+        "{{schema_name property_name description}} : ({})",
+        terms.join(" ")
+    ))
+}
+
+/// Split highlighted text into `(text, is_match)` runs.
+pub fn segments(text: &str) -> Vec<(String, bool)> {
+    let mut out = Vec::new();
+    let mut run = String::new();
+    let mut in_match = false;
+    for c in text.chars() {
+        if c == MATCH_START || c == MATCH_END {
+            if !run.is_empty() {
+                out.push((std::mem::take(&mut run), in_match));
+            }
+            in_match = c == MATCH_START;
+        } else {
+            run.push(c);
+        }
+    }
+    if !run.is_empty() {
+        out.push((run, in_match));
+    }
+    out
+}
+
+// ── tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn index(rows: &[(&str, &str, &str, &str)]) -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::db::init(&conn).unwrap();
+        for row in rows {
+            conn.execute(
+                "INSERT INTO search_index (service_name, schema_name, property_name, description)
+                 VALUES (?1, ?2, ?3, ?4)",
+                params![row.0, row.1, row.2, row.3],
+            )
+            .unwrap();
+        }
+        conn
+    }
+
+    #[test]
+    fn names_outrank_descriptions_across_services() {
+        let conn = index(&[
+            (
+                "billing",
+                "Invoice",
+                "payee",
+                "Account holder, paid by IBAN transfer",
+            ),
+            (
+                "payments",
+                "BankAccount",
+                "iban",
+                "International bank account number",
+            ),
+            (
+                "customers",
+                "CustomerAddress",
+                "",
+                "Postal address of a customer",
+            ),
+            ("customers", "CustomerAddress", "street", ""),
+        ]);
+        let hits = search(&conn, "iban", 10).unwrap();
+        let services: Vec<&str> = hits.iter().map(|h| h.service.as_str()).collect();
+        assert_eq!(services, vec!["payments", "billing"]);
+        assert_eq!(hits[0].label(), "payments › BankAccount › \u{2}iban\u{3}");
+        assert!(hits[1].snippet.contains("\u{2}IBAN\u{3}"));
+
+        // Prefix match on a schema name; the service column is not searched.
+        let hits = search(&conn, "customeradd", 10).unwrap();
+        assert_eq!(hits[0].property_name, None);
+        assert_eq!(hits[0].schema_name, "CustomerAddress");
+        assert!(search(&conn, "payments", 10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn query_syntax_is_treated_as_text() {
+        let conn = index(&[("svc", "Pet", "tag", "a \"tag\" (optional)")]);
+        assert_eq!(search(&conn, "\"tag\" ( opt*", 10).unwrap().len(), 1);
+        assert!(search(&conn, "tag OR pet", 10).unwrap().is_empty());
+        assert!(search(&conn, "  \"\" ", 10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn segments_split_on_markers() {
+        assert_eq!(
+            segments("a \u{2}iban\u{3} field"),
+            vec![
+                ("a ".to_string(), false),
+                ("iban".to_string(), true),
+                (" field".to_string(), false),
+            ]
+        );
+    }
+}
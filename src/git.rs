@@ -0,0 +1,337 @@
+use std::path::{Component, Path, PathBuf};
+use std::process::Command;
+
+use anyhow::{Context, Result, bail};
+
+use crate::parser::{self, Files};
+use crate::spec::LoadedSpec;
+
+/// The files of a local git repository as they were at one commit, read with
+/// `git show` (nothing is checked out, nothing goes over the network).
+pub struct Revision {
+    repo: PathBuf, // the work tree's top-level directory
+    commit: String,
+    label: String, // as given, for messages
+}
+
+impl Revision {
+    /// `rev` in the repository at `repo`, resolved to a commit once so every
+    /// file is read from the same one.
+    pub fn new(repo: &Path, rev: &str) -> Result<Revision> {
+        let commit = git(
+            repo,
+            &[
+                "rev-parse",
+                "--verify",
+                "--quiet",
+                &format!("{rev}^{{commit}}"),
+            ],
+        )
+        .ok()
+        .with_context(|| format!("unknown revision {rev}"))?;
+        Ok(Revision {
+            repo: repo.to_path_buf(),
+            commit,
+            label: rev.to_string(),
+        })
+    }
+
+    /// Parse the spec at `path` (absolute, inside the repository) as it was
+    /// at this revision, following `$ref`s to other files at the same revision.
+    pub fn parse_spec(&self, path: &Path) -> Result<LoadedSpec> {
+        let content = self.read(path).with_context(|| {
+            let shown = self.relative(path).unwrap_or(path);
+            format!("{} does not exist at {}", shown.display(), self.label)
+        })?;
+        let bundle = parser::bundle_from(&content, Some(path), self)?;
+        parser::parse_bundle(&bundle)
+            .with_context(|| format!("failed to parse {} at {}", path.display(), self.label))
+    }
+
+    fn relative<'a>(&self, path: &'a Path) -> Option<&'a Path> {
+        path.strip_prefix(&self.repo).ok()
+    }
+}
+
+impl Files for Revision {
+    fn locate(&self, path: &Path) -> Option<PathBuf> {
+        let path = normalize(path);
+        self.relative(&path)?;
+        Some(path)
+    }
+
+    fn read(&self, path: &Path) -> Option<String> {
+        // Git paths use `/` whatever the platform.
+        let relative: Vec<String> = self
+            .relative(path)?
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy().into_owned())
+            .collect();
+        git(
+            &self.repo,
+            &["show", &format!("{}:{}", self.commit, relative.join("/"))],
+        )
+        .ok()
+    }
+}
+
+/// The top-level directory of the work tree containing `dir`.
+pub fn repo_root(dir: &Path) -> Result<PathBuf> {
+    let root = git(dir, &["rev-parse", "--show-toplevel"])
+        .with_context(|| format!("{} is not inside a git repository", dir.display()))?;
+    Ok(PathBuf::from(root))
+}
+
+/// The two sides of `--git`: `A` compares revision A with the work tree
+/// (`None`), `A..B` two revisions, and `A...B` B with where it forked from A.
+/// A side left empty is `HEAD`, as in git.
+pub fn range(repo: &Path, spec: &str) -> Result<(Revision, Option<Revision>)> {
+    let side = |rev: &str| Revision::new(repo, if rev.is_empty() { "HEAD" } else { rev });
+    if let Some((from, to)) = spec.split_once("...") {
+        let (from, to) = (side(from)?, side(to)?);
+        let base = git(repo, &["merge-base", &from.commit, &to.commit])
+            .with_context(|| format!("{} and {} have no common ancestor", from.label, to.label))?;
+        let base = Revision {
+            repo: repo.to_path_buf(),
+            commit: base,
+            label: format!("merge-base of {} and {}", from.label, to.label),
+        };
+        return Ok((base, Some(to)));
+    }
+    match spec.split_once("..") {
+        Some((from, to)) => Ok((side(from)?, Some(side(to)?))),
+        None => Ok((side(spec)?, None)),
+    }
+}
+
+/// Resolve `.` and `..` without touching the disk: at an old revision the
+/// file may not exist in the work tree.
+pub fn normalize(path: &Path) -> PathBuf {
+    let mut out = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                out.pop();
+            }
+            other => out.push(other),
+        }
+    }
+    out
+}
+
+/// Run git in `dir`; its trimmed stdout, or its stderr as the error.
+fn git(dir: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .output()
+        .context("failed to run git")?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        bail!("git {}: {}", args.join(" "), stderr.trim());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .trim_end_matches('\n')
+        .to_string())
+}
+
+// ── tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    const SPEC: &str = r##"
+openapi: 3.0.3
+info: { title: Shop, version: "1" }
+paths: {}
+components:
+  schemas:
+    Order:
+      type: object
+      properties:
+        total: { $ref: "./common/money.yaml#/components/schemas/Money" }
+"##;
+
+    fn commit(repo: &Path, message: &str) {
+        for args in [
+            vec!["add", "-A"],
+            vec![
+                "-c",
+                "user.name=t",
+                "-c",
+                "user.email=t@example.com",
+                "commit",
+                "-q",
+                "-m",
+                message,
+            ],
+        ] {
+            git(repo, &args).unwrap();
+        }
+    }
+
+    /// An empty repository in a fresh temporary directory.
+    fn init(name: &str) -> PathBuf {
+        let repo = std::env::temp_dir().join(format!("speq-git-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&repo);
+        fs::create_dir_all(repo.join("api/common")).unwrap();
+        let repo = repo.canonicalize().unwrap();
+        git(&repo, &["init", "-q"]).unwrap();
+        repo
+    }
+
+    fn money(props: &str) -> String {
+        let head = "components:\n  schemas:\n    Money:\n      type: object\n";
+        format!("{head}      properties: {{{props}}}\n")
+    }
+
+    /// The properties of `Money` in `spec`.
+    fn money_properties(spec: &LoadedSpec) -> Vec<String> {
+        let money = spec
+            .schema_nodes
+            .iter()
+            .find(|n| n.name == "Money")
+            .unwrap();
+        money.children.iter().map(|c| c.name.clone()).collect()
+    }
+
+    #[test]
+    fn reads_specs_and_their_refs_at_a_revision() {
+        let repo = init("refs");
+        fs::write(repo.join("api/openapi.yaml"), SPEC).unwrap();
+        fs::write(
+            repo.join("api/common/money.yaml"),
+            money("amount: {type: number}"),
+        )
+        .unwrap();
+        commit(&repo, "first");
+        fs::write(
+            repo.join("api/common/money.yaml"),
+            money("amount: {type: number}, currency: {type: string}"),
+        )
+        .unwrap();
+        commit(&repo, "second");
+        // Work tree: the file is gone, but older revisions still have it.
+        fs::remove_file(repo.join("api/common/money.yaml")).unwrap();
+
+        assert_eq!(repo_root(&repo.join("api")).unwrap(), repo);
+        let spec_path = normalize(&repo.join("api/./common/../openapi.yaml"));
+        assert_eq!(spec_path, repo.join("api/openapi.yaml"));
+
+        let (old, new) = range(&repo, "HEAD~1..").unwrap();
+        let properties =
+            |revision: &Revision| money_properties(&revision.parse_spec(&spec_path).unwrap());
+        assert_eq!(properties(&old), vec!["amount"]);
+        assert_eq!(properties(&new.unwrap()), vec!["amount", "currency"]);
+
+        assert!(range(&repo, "no-such-branch").is_err());
+        let (first, work_tree) = range(&repo, "HEAD~1").unwrap();
+        assert!(work_tree.is_none());
+        let Err(missing) = first.parse_spec(&repo.join("api/nope.yaml")) else {
+            panic!("parsed a file that is not there");
+        };
+        assert_eq!(
+            missing.to_string(),
+            "api/nope.yaml does not exist at HEAD~1"
+        );
+        fs::remove_dir_all(&repo).unwrap();
+    }
+
+    #[test]
+    fn three_dots_compare_with_the_merge_base() {
+        let repo = init("merge-base");
+        let spec_path = repo.join("api/openapi.yaml");
+        fs::write(&spec_path, SPEC).unwrap();
+        let step = |props: &str, message: &str| {
+            fs::write(repo.join("api/common/money.yaml"), money(props)).unwrap();
+            commit(&repo, message);
+        };
+        step("amount: {type: number}", "base");
+        git(&repo, &["checkout", "-q", "-b", "trunk"]).unwrap();
+        step("amount: {type: number}, currency: {type: string}", "trunk");
+        git(&repo, &["checkout", "-q", "-b", "feature", "HEAD~1"]).unwrap();
+        step("amount: {type: number}, rate: {type: number}", "feature");
+
+        let properties =
+            |revision: &Revision| money_properties(&revision.parse_spec(&spec_path).unwrap());
+        let (base, feature) = range(&repo, "trunk...feature").unwrap();
+        assert_eq!(base.label, "merge-base of trunk and feature");
+        assert_eq!(properties(&base), vec!["amount"]);
+        assert_eq!(properties(&feature.unwrap()), vec!["amount", "rate"]);
+
+        // Two dots compare the tips; an empty side is HEAD (here, feature).
+        let (trunk, head) = range(&repo, "trunk..").unwrap();
+        assert_eq!(properties(&trunk), vec!["amount", "currency"]);
+        assert_eq!(properties(&head.unwrap()), vec!["amount", "rate"]);
+        let (base, head) = range(&repo, "trunk...").unwrap();
+        assert_eq!(properties(&base), vec!["amount"]);
+        assert_eq!(head.unwrap().label, "HEAD");
+        fs::remove_dir_all(&repo).unwrap();
+    }
+
+    #[test]
+    fn one_revision_compares_with_the_work_tree() {
+        let repo = init("work-tree");
+        let spec_path = repo.join("api/openapi.yaml");
+        fs::write(&spec_path, SPEC).unwrap();
+        fs::write(
+            repo.join("api/common/money.yaml"),
+            money("amount: {type: number}"),
+        )
+        .unwrap();
+        commit(&repo, "first");
+        // Uncommitted: only the work tree has the new property.
+        fs::write(
+            repo.join("api/common/money.yaml"),
+            money("amount: {type: number}, currency: {type: string}"),
+        )
+        .unwrap();
+
+        let (head, work_tree) = range(&repo, "HEAD").unwrap();
+        assert!(work_tree.is_none());
+        assert_eq!(
+            money_properties(&head.parse_spec(&spec_path).unwrap()),
+            vec!["amount"]
+        );
+        // The other side is the spec on disk, as `speq diff --git HEAD` reads it.
+        let content = fs::read_to_string(&spec_path).unwrap();
+        let on_disk = parser::parse_spec_at(&content, Some(&spec_path)).unwrap();
+        assert_eq!(money_properties(&on_disk), vec!["amount", "currency"]);
+        fs::remove_dir_all(&repo).unwrap();
+    }
+
+    #[test]
+    fn unknown_revisions_are_errors() {
+        let repo = init("unknown");
+        let Err(empty) = range(&repo, "HEAD") else {
+            panic!("resolved HEAD in a repository with no commits");
+        };
+        assert_eq!(empty.to_string(), "unknown revision HEAD");
+
+        fs::write(repo.join("api/openapi.yaml"), SPEC).unwrap();
+        commit(&repo, "first");
+        for spec in [
+            "nope",
+            "nope..HEAD",
+            "HEAD..nope",
+            "nope...HEAD",
+            "HEAD...nope",
+            "HEAD^{tree}",
+        ] {
+            let Err(error) = range(&repo, spec) else {
+                panic!("resolved {spec}");
+            };
+            let rev = if spec == "HEAD^{tree}" { spec } else { "nope" };
+            assert_eq!(
+                error.to_string(),
+                format!("unknown revision {rev}"),
+                "{spec}"
+            );
+        }
+        fs::remove_dir_all(&repo).unwrap();
+    }
+}
@@ -0,0 +1,337 @@
+use std::collections::VecDeque;
+use std::fmt::Write;
+
+use clap::ValueEnum;
+
+use crate::spec::LoadedSpec;
+use crate::tree::{NodeKind, TreeNode};
+
+/// `speq graph --format`.
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum Format {
+    Dot,
+    Mermaid,
+}
+
+/// Which component schemas refer to which: one edge per schema pair with a
+/// `$ref` between them, by index into `LoadedSpec::schema_nodes`.
+#[derive(Debug, Default)]
+pub struct Graph {
+    pub references: Vec<Vec<usize>>, // the schemas each one refers to, in order
+    pub referrers: Vec<Vec<usize>>,  // the schemas referring to each one, in order
+    component: Vec<usize>,           // strongly connected component of each schema
+}
+
+impl Graph {
+    pub fn build(schemas: &[TreeNode]) -> Graph {
+        let references: Vec<Vec<usize>> = schemas
+            .iter()
+            .map(|root| {
+                let mut targets = Vec::new();
+                refs(root, schemas, &mut targets);
+                targets.sort_unstable();
+                targets.dedup();
+                targets
+            })
+            .collect();
+        let mut referrers = vec![Vec::new(); schemas.len()];
+        for (from, targets) in references.iter().enumerate() {
+            for &to in targets {
+                referrers[to].push(from);
+            }
+        }
+        let component = components(&references);
+        Graph {
+            references,
+            referrers,
+            component,
+        }
+    }
+
+    /// Whether the edge `from → to` lies on a cycle of references.
+    pub fn on_cycle(&self, from: usize, to: usize) -> bool {
+        self.component[from] == self.component[to] && self.component[from] < self.component.len()
+    }
+
+    /// The schemas within `depth` references of `focus`, either way; all
+    /// that are connected to it when `depth` is `None`.
+    pub fn around(&self, focus: usize, depth: Option<usize>) -> Vec<bool> {
+        let mut included = vec![false; self.references.len()];
+        included[focus] = true;
+        let mut queue = VecDeque::from([(focus, 0)]);
+        while let Some((at, distance)) = queue.pop_front() {
+            if depth.is_some_and(|depth| distance >= depth) {
+                continue;
+            }
+            for &next in self.references[at].iter().chain(&self.referrers[at]) {
+                if !included[next] {
+                    included[next] = true;
+                    queue.push_back((next, distance + 1));
+                }
+            }
+        }
+        included
+    }
+}
+
+/// Every component schema a `$ref` below `node` names. The children of a
+/// `$ref` are its target's, which is visited as a root of its own.
+fn refs(node: &TreeNode, schemas: &[TreeNode], out: &mut Vec<usize>) {
+    if let NodeKind::Ref(target) = &node.info.kind {
+        if let Some(index) = schemas.iter().position(|s| s.name == *target) {
+            out.push(index);
+        }
+        return;
+    }
+    for child in &node.children {
+        refs(child, schemas, out);
+    }
+}
+
+/// Tarjan's strongly connected components: the component of each schema.
+/// A schema on no cycle (alone, and not referring to itself) gets a number
+/// past the last schema's instead.
+fn components(references: &[Vec<usize>]) -> Vec<usize> {
+    struct Tarjan<'a> {
+        references: &'a [Vec<usize>],
+        order: Vec<Option<usize>>, // when each schema was first reached
+        low: Vec<usize>,
+        stack: Vec<usize>,
+        on_stack: Vec<bool>,
+        component: Vec<usize>,
+        next: usize,
+    }
+
+    impl Tarjan<'_> {
+        fn visit(&mut self, at: usize) {
+            self.order[at] = Some(self.next);
+            self.low[at] = self.next;
+            self.next += 1;
+            self.stack.push(at);
+            self.on_stack[at] = true;
+            for &to in &self.references[at] {
+                match self.order[to] {
+                    None => {
+                        self.visit(to);
+                        self.low[at] = self.low[at].min(self.low[to]);
+                    }
+                    Some(order) if self.on_stack[to] => self.low[at] = self.low[at].min(order),
+                    Some(_) => {}
+                }
+            }
+            if Some(self.low[at]) == self.order[at] {
+                while let Some(member) = self.stack.pop() {
+                    self.on_stack[member] = false;
+                    self.component[member] = at;
+                    if member == at {
+                        break;
+                    }
+                }
+            }
+        }
+    }
+
+    let count = references.len();
+    let mut tarjan = Tarjan {
+        references,
+        order: vec![None; count],
+        low: vec![0; count],
+        stack: Vec::new(),
+        on_stack: vec![false; count],
+        component: (0..count).collect(),
+        next: 0,
+    };
+    for at in 0..count {
+        if tarjan.order[at].is_none() {
+            tarjan.visit(at);
+        }
+    }
+    // A lone schema that does not refer to itself is on no cycle.
+    let mut sizes = vec![0; count];
+    for &component in &tarjan.component {
+        sizes[component] += 1;
+    }
+    for at in 0..count {
+        if sizes[tarjan.component[at]] == 1 && !references[at].contains(&at) {
+            tarjan.component[at] = count + at;
+        }
+    }
+    tarjan.component
+}
+
+// ── output ────────────────────────────────────────────────────────────────────
+
+/// The spec's reference graph as Graphviz DOT or a Mermaid flowchart. With
+/// `focus`, only the schemas within `depth` references of it (it is drawn
+/// bold); with `cycles`, edges on a cycle are drawn red.
+pub fn render(
+    spec: &LoadedSpec,
+    format: Format,
+    focus: Option<usize>,
+    depth: Option<usize>,
+    cycles: bool,
+) -> String {
+    let graph = &spec.graph;
+    let included = match focus {
+        Some(focus) => graph.around(focus, depth),
+        None => vec![true; spec.schema_nodes.len()],
+    };
+    let nodes: Vec<usize> = (0..included.len()).filter(|&i| included[i]).collect();
+    let edges: Vec<(usize, usize)> = nodes
+        .iter()
+        .flat_map(|&from| graph.references[from].iter().map(move |&to| (from, to)))
+        .filter(|&(_, to)| included[to])
+        .collect();
+    let red = |(from, to): (usize, usize)| cycles && graph.on_cycle(from, to);
+    let name = |i: usize| spec.schema_nodes[i].name.as_str();
+
+    let mut out = String::new();
+    match format {
+        Format::Dot => {
+            let quote =
+                |text: &str| format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""));
+            let _ = writeln!(out, "digraph {} {{", quote(&spec.title));
+            let _ = writeln!(out, "  rankdir=LR;");
+            let _ = writeln!(out, "  node [shape=box, fontname=\"Helvetica\"];");
+            for &i in &nodes {
+                let style = if focus == Some(i) {
+                    " [style=bold]"
+                } else {
+                    ""
+                };
+                let _ = writeln!(out, "  {}{style};", quote(name(i)));
+            }
+            for &(from, to) in &edges {
+                let style = if red((from, to)) { " [color=red]" } else { "" };
+                let _ = writeln!(
+                    out,
+                    "  {} -> {}{style};",
+                    quote(name(from)),
+                    quote(name(to))
+                );
+            }
+            out.push_str("}\n");
+        }
+        Format::Mermaid => {
+            out.push_str("graph LR\n");
+            for &i in &nodes {
+                let _ = writeln!(out, "  s{i}[\"{}\"]", name(i).replace('"', "#quot;"));
+            }
+            for &(from, to) in &edges {
+                let _ = writeln!(out, "  s{from} --> s{to}");
+            }
+            for (n, &edge) in edges.iter().enumerate() {
+                if red(edge) {
+                    let _ = writeln!(out, "  linkStyle {n} stroke:red");
+                }
+            }
+            if let Some(focus) = focus {
+                let _ = writeln!(out, "  style s{focus} stroke-width:3px");
+            }
+        }
+    }
+    out
+}
+
+// ── tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_spec;
+
+    const SPEC: &str = r##"
+openapi: 3.0.3
+info: { title: Shop, version: "1" }
+paths: {}
+components:
+  schemas:
+    Category:
+      type: object
+      properties:
+        parent: { $ref: "#/components/schemas/Category" }
+    Customer:
+      type: object
+      properties:
+        orders: { type: array, items: { $ref: "#/components/schemas/Order" } }
+    Money:
+      type: object
+      properties:
+        amount: { type: number }
+    Order:
+      type: object
+      properties:
+        customer: { $ref: "#/components/schemas/Customer" }
+        total: { $ref: "#/components/schemas/Money" }
+        lines: { type: array, items: { $ref: "#/components/schemas/OrderLine" } }
+    OrderLine:
+      type: object
+      properties:
+        price: { $ref: "#/components/schemas/Money" }
+"##;
+
+    fn names(spec: &LoadedSpec, indices: &[usize]) -> Vec<String> {
+        indices
+            .iter()
+            .map(|&i| spec.schema_nodes[i].name.clone())
+            .collect()
+    }
+
+    #[test]
+    fn finds_references_both_ways_and_cycles() {
+        let spec = parse_spec(SPEC).unwrap();
+        let index = |name: &str| spec.schema_names.iter().position(|n| n == name).unwrap();
+        let graph = &spec.graph;
+        assert_eq!(
+            names(&spec, &graph.references[index("Order")]),
+            ["Customer", "Money", "OrderLine"]
+        );
+        assert_eq!(
+            names(&spec, &graph.referrers[index("Money")]),
+            ["Order", "OrderLine"]
+        );
+
+        assert!(graph.on_cycle(index("Order"), index("Customer")));
+        assert!(graph.on_cycle(index("Category"), index("Category")));
+        assert!(!graph.on_cycle(index("Order"), index("Money")));
+        assert!(
+            !graph.on_cycle(index("Money"), index("Money")),
+            "no edge, no cycle"
+        );
+
+        let near: Vec<bool> = graph.around(index("Money"), Some(1));
+        let near: Vec<usize> = (0..near.len()).filter(|&i| near[i]).collect();
+        assert_eq!(names(&spec, &near), ["Money", "Order", "OrderLine"]);
+    }
+
+    #[test]
+    fn renders_dot_and_mermaid() {
+        let spec = parse_spec(SPEC).unwrap();
+        let order = spec.schema_names.iter().position(|n| n == "Order").unwrap();
+        let dot = render(&spec, Format::Dot, Some(order), Some(1), true);
+        assert_eq!(
+            dot,
+            r#"digraph "Shop" {
+  rankdir=LR;
+  node [shape=box, fontname="Helvetica"];
+  "Customer";
+  "Money";
+  "Order" [style=bold];
+  "OrderLine";
+  "Customer" -> "Order" [color=red];
+  "Order" -> "Customer" [color=red];
+  "Order" -> "Money";
+  "Order" -> "OrderLine";
+  "OrderLine" -> "Money";
+}
+"#
+        );
+        let mermaid = render(&spec, Format::Mermaid, None, None, true);
+        assert!(mermaid.starts_with("graph LR\n  s0[\"Category\"]\n"));
+        assert!(mermaid.contains("  s0 --> s0\n  s1 --> s3\n"));
+        assert!(mermaid.contains(
+            "  linkStyle 0 stroke:red\n  linkStyle 1 stroke:red\n  linkStyle 2 stroke:red\n"
+        ));
+        assert!(!mermaid.contains("linkStyle 3"));
+    }
+}
@@ -0,0 +1,782 @@
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{Context, Result, bail};
+use rusqlite::{Connection, OptionalExtension, params};
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::parser::{bundle, parse_bundle};
+use crate::spec::{LoadedSpec, SpecVersion};
+use crate::tree::{NodeKind, TreeNode};
+
+/// `meta.yaml`, written next to `openapi.yaml` by the cache script.
+#[derive(Debug, Deserialize)]
+pub struct Meta {
+    pub service: String,
+    pub repo: String,
+    pub team: Option<String>,
+    pub domain: Option<String>,
+    pub cached_at: String,
+    pub source_url: Option<String>,
+}
+
+pub struct IndexOptions {
+    pub force: bool,             // ignore the fingerprint check
+    pub service: Option<String>, // only this service (by `meta.yaml` name or directory)
+}
+
+/// What happened to one service directory.
+enum Outcome {
+    Indexed { schemas: usize, paths: usize },
+    Skipped,
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct Summary {
+    pub indexed: usize,
+    pub skipped: usize,
+    pub errors: usize,
+    pub removed: usize, // services whose directory is gone
+}
+
+/// Modification time of every file a service's rows were read from: its
+/// `openapi.yaml`, `meta.yaml` and the files the spec's `$ref`s pull in.
+type Fingerprint = BTreeMap<String, i64>;
+
+/// `speq index`: index every service under `specs_dir` into `conn`, printing
+/// one line per service and a summary to `out`.
+pub fn index_all(
+    conn: &mut Connection,
+    specs_dir: &Path,
+    options: &IndexOptions,
+    out: &mut impl Write,
+) -> Result<Summary> {
+    let mut dirs: Vec<PathBuf> = fs::read_dir(specs_dir)
+        .with_context(|| format!("cannot read {}", specs_dir.display()))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.is_dir())
+        .collect();
+    dirs.sort();
+
+    let mut selected = Vec::new();
+    for dir in dirs {
+        let meta = read_meta(&dir);
+        let dir_name = dir
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string();
+        let name = meta
+            .as_ref()
+            .map(|m| m.service.clone())
+            .unwrap_or_else(|_| dir_name.clone());
+        if let Some(only) = &options.service
+            && *only != name
+            && *only != dir_name
+        {
+            continue;
+        }
+        selected.push((dir, name, meta));
+    }
+    if let Some(only) = &options.service
+        && selected.is_empty()
+    {
+        bail!("no service named {only} in {}", specs_dir.display());
+    }
+    let seen: HashSet<String> = selected.iter().map(|(_, name, _)| name.clone()).collect();
+
+    let width = selected
+        .iter()
+        .map(|(_, name, _)| name.len())
+        .max()
+        .unwrap_or(0);
+    let mut summary = Summary::default();
+    for (dir, name, meta) in selected {
+        write!(out, "indexing {name:<width$} ... ")?;
+        let result = meta.and_then(|meta| index_service(conn, &dir, &meta, options.force));
+        match result {
+            Ok(Outcome::Indexed { schemas, paths }) => {
+                summary.indexed += 1;
+                writeln!(out, "ok ({schemas} schemas, {paths} paths)")?;
+            }
+            Ok(Outcome::Skipped) => {
+                summary.skipped += 1;
+                writeln!(out, "skipped (unchanged)")?;
+            }
+            Err(e) => {
+                summary.errors += 1;
+                writeln!(out, "error: {e:#}")?;
+            }
+        }
+    }
+
+    // A run over every service drops the ones whose directory has gone.
+    if options.service.is_none() {
+        let stale: Vec<(i64, String)> = conn
+            .prepare("SELECT id, name FROM services")?
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<rusqlite::Result<Vec<_>>>()?
+            .into_iter()
+            .filter(|(_, name)| !seen.contains(name))
+            .collect();
+        for (id, name) in stale {
+            let tx = conn.transaction()?;
+            delete_rows(&tx, id, &name)?;
+            tx.execute("DELETE FROM services WHERE id = ?1", [id])?;
+            tx.commit()?;
+            summary.removed += 1;
+            writeln!(
+                out,
+                "removed  {name} (no longer in {})",
+                specs_dir.display()
+            )?;
+        }
+    }
+
+    write!(
+        out,
+        "indexed {} {}, {} skipped, {} {}",
+        summary.indexed,
+        if summary.indexed == 1 {
+            "service"
+        } else {
+            "services"
+        },
+        summary.skipped,
+        summary.errors,
+        if summary.errors == 1 {
+            "error"
+        } else {
+            "errors"
+        },
+    )?;
+    if summary.removed > 0 {
+        write!(out, ", {} removed", summary.removed)?;
+    }
+    writeln!(out)?;
+    Ok(summary)
+}
+
+fn read_meta(dir: &Path) -> Result<Meta> {
+    let path = dir.join("meta.yaml");
+    let content = fs::read_to_string(&path).context("cannot read meta.yaml")?;
+    serde_yaml::from_str(&content).context("failed to parse meta.yaml")
+}
+
+fn index_service(conn: &mut Connection, dir: &Path, meta: &Meta, force: bool) -> Result<Outcome> {
+    let spec_path = dir.join("openapi.yaml");
+    let spec_path = spec_path.canonicalize().unwrap_or(spec_path);
+    let meta_path = dir.join("meta.yaml");
+    let meta_path = meta_path.canonicalize().unwrap_or(meta_path);
+    mtime_of(&spec_path).context("cannot read openapi.yaml")?;
+
+    let previous: Option<String> = conn
+        .query_row(
+            "SELECT fingerprint FROM services WHERE name = ?1",
+            [&meta.service],
+            |row| row.get(0),
+        )
+        .optional()?
+        .flatten();
+    let previous: Option<Fingerprint> = previous.and_then(|p| serde_json::from_str(&p).ok());
+    if !force && previous.is_some_and(|p| unchanged(&p, &spec_path)) {
+        return Ok(Outcome::Skipped);
+    }
+
+    // Taken before reading, so an edit made meanwhile is picked up next time.
+    let mut fingerprint = fingerprint([&spec_path, &meta_path]);
+    let content = fs::read_to_string(&spec_path).context("cannot read openapi.yaml")?;
+    // Schemas pulled in from other files are indexed as this service's own.
+    let bundle = bundle(&content, Some(&spec_path)).context("failed to parse openapi.yaml")?;
+    let spec = parse_bundle(&bundle).context("failed to parse openapi.yaml")?;
+    let raw = &bundle.document;
+    fingerprint.extend(self::fingerprint(&bundle.files));
+
+    let tx = conn.transaction()?;
+    let service_id = upsert_service(&tx, meta, &spec_path, &fingerprint, &spec, raw)?;
+    write_schemas(&tx, service_id, &meta.service, &spec, raw)?;
+    write_paths(&tx, service_id, &spec, raw)?;
+    tx.execute(
+        "UPDATE services SET indexed_at = ?1 WHERE id = ?2",
+        params![iso8601(SystemTime::now()), service_id],
+    )?;
+    tx.commit()?;
+
+    Ok(Outcome::Indexed {
+        schemas: spec.schema_nodes.len(),
+        paths: spec.operations.len(),
+    })
+}
+
+/// The current modification time of each of `paths` that can be read.
+fn fingerprint<P: AsRef<Path>>(paths: impl IntoIterator<Item = P>) -> Fingerprint {
+    paths
+        .into_iter()
+        .filter_map(|path| {
+            let path = path.as_ref();
+            Some((path.to_string_lossy().into_owned(), mtime_of(path).ok()?))
+        })
+        .collect()
+}
+
+/// Whether none of the files recorded for a service has changed (or gone),
+/// and it still lives at `spec_path`.
+fn unchanged(previous: &Fingerprint, spec_path: &Path) -> bool {
+    previous.contains_key(spec_path.to_string_lossy().as_ref())
+        && previous
+            .iter()
+            .all(|(path, &mtime)| mtime_of(Path::new(path)).ok() == Some(mtime))
+}
+
+fn upsert_service(
+    conn: &Connection,
+    meta: &Meta,
+    spec_path: &Path,
+    fingerprint: &Fingerprint,
+    spec: &LoadedSpec,
+    raw: &Value,
+) -> Result<i64> {
+    let openapi_version = match spec.version {
+        SpecVersion::V20 => "2.0",
+        SpecVersion::V30 => "3.0",
+        SpecVersion::V31 => "3.1",
+    };
+    let id = conn.query_row(
+        "INSERT INTO services
+             (name, repo, team, domain, source_url, cached_at, spec_path, fingerprint,
+              openapi_version, spec_title, spec_version)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)
+         ON CONFLICT(name) DO UPDATE SET
+             repo = excluded.repo, team = excluded.team, domain = excluded.domain,
+             source_url = excluded.source_url, cached_at = excluded.cached_at,
+             spec_path = excluded.spec_path, fingerprint = excluded.fingerprint,
+             openapi_version = excluded.openapi_version, spec_title = excluded.spec_title,
+             spec_version = excluded.spec_version
+         RETURNING id",
+        params![
+            meta.service,
+            meta.repo,
+            meta.team,
+            meta.domain,
+            meta.source_url,
+            meta.cached_at,
+            spec_path.to_string_lossy(),
+            serde_json::to_string(fingerprint)?,
+            openapi_version,
+            spec.title,
+            // `version: 1.0` unquoted is a YAML number.
+            raw.pointer("/info/version").map(|v| match v {
+                Value::String(s) => s.clone(),
+                other => other.to_string(),
+            }),
+        ],
+        |row| row.get(0),
+    )?;
+
+    // Re-indexing replaces everything the service had before.
+    delete_rows(conn, id, &meta.service)?;
+    Ok(id)
+}
+
+/// Everything indexed for a service, short of its `services` row.
+fn delete_rows(conn: &Connection, id: i64, name: &str) -> Result<()> {
+    conn.execute("DELETE FROM properties WHERE service_id = ?1", [id])?;
+    conn.execute("DELETE FROM schemas WHERE service_id = ?1", [id])?;
+    conn.execute("DELETE FROM paths WHERE service_id = ?1", [id])?;
+    conn.execute("DELETE FROM search_index WHERE service_name = ?1", [name])?;
+    Ok(())
+}
+
+fn write_schemas(
+    conn: &Connection,
+    service_id: i64,
+    service: &str,
+    spec: &LoadedSpec,
+    raw: &Value,
+) -> Result<()> {
+    let raw_schemas = match spec.version {
+        SpecVersion::V20 => raw.get("definitions"),
+        SpecVersion::V30 | SpecVersion::V31 => raw.pointer("/components/schemas"),
+    };
+
+    let mut insert_schema = conn.prepare(
+        "INSERT INTO schemas (service_id, name, description, type, raw_json)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+    )?;
+    let mut insert_property = conn.prepare(
+        "INSERT INTO properties
+             (schema_id, service_id, name, type, format, description, required, ref_target, combiner)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+    )?;
+    let mut insert_search = conn.prepare(
+        "INSERT INTO search_index (service_name, schema_name, property_name, description)
+         VALUES (?1, ?2, ?3, ?4)",
+    )?;
+
+    for schema in &spec.schema_nodes {
+        let raw_json = raw_schemas
+            .and_then(|schemas| schemas.get(&schema.name))
+            .map(Value::to_string);
+        let schema_id = insert_schema.insert(params![
+            service_id,
+            schema.name,
+            schema.info.description,
+            type_name(schema),
+            raw_json,
+        ])?;
+        insert_search.execute(params![
+            service,
+            schema.name,
+            "",
+            schema.info.description.as_deref().unwrap_or_default(),
+        ])?;
+
+        for (prop, combiner) in properties(schema) {
+            let ref_target = match &prop.info.kind {
+                NodeKind::Ref(target) => Some(target.as_str()),
+                _ => None,
+            };
+            insert_property.execute(params![
+                schema_id,
+                service_id,
+                prop.name,
+                type_name(prop),
+                prop.info.format,
+                prop.info.description,
+                prop.info.required,
+                ref_target,
+                combiner,
+            ])?;
+            insert_search.execute(params![
+                service,
+                schema.name,
+                prop.name,
+                prop.info.description.as_deref().unwrap_or_default(),
+            ])?;
+        }
+    }
+    Ok(())
+}
+
+fn write_paths(conn: &Connection, service_id: i64, spec: &LoadedSpec, raw: &Value) -> Result<()> {
+    let mut insert = conn.prepare(
+        "INSERT INTO paths (service_id, path, method, operation_id, summary, description, tags, raw_json)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+    )?;
+    for op in &spec.operations {
+        insert.execute(params![
+            service_id,
+            op.path,
+            op.method,
+            op.operation_id,
+            op.summary,
+            op.description,
+            serde_json::to_string(&op.tags)?,
+            standalone_operation(raw, &op.path, &op.method).map(|op| op.to_string()),
+        ])?;
+    }
+    Ok(())
+}
+
+/// An operation as the index keeps it: with the path item's parameters merged
+/// in, the document's `consumes` / `produces` (Swagger 2.0) copied down, and
+/// every `$ref` but those to schemas inlined, so it can be parsed again with
+/// nothing else from the document but its schemas.
+fn standalone_operation(raw: &Value, path: &str, method: &str) -> Option<Value> {
+    let item = inline_refs(raw, raw.get("paths")?.get(path)?, 0);
+    let mut op = item.get(method)?.as_object()?.clone();
+
+    let own: Vec<Value> = op
+        .get("parameters")
+        .and_then(Value::as_array)
+        .cloned()
+        .unwrap_or_default();
+    let shared = item
+        .get("parameters")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter(|p| {
+            !own.iter()
+                .any(|o| o.get("name") == p.get("name") && o.get("in") == p.get("in"))
+        });
+    let parameters: Vec<Value> = shared.chain(&own).cloned().collect();
+    if !parameters.is_empty() {
+        op.insert("parameters".to_string(), Value::Array(parameters));
+    }
+    for key in ["consumes", "produces"] {
+        if !op.contains_key(key)
+            && let Some(value) = raw.get(key)
+        {
+            op.insert(key.to_string(), value.clone());
+        }
+    }
+    Some(Value::Object(op))
+}
+
+/// `value` with its local `$ref`s to anything but a schema replaced by their
+/// targets (up to a few hops, in case they refer to each other).
+fn inline_refs(raw: &Value, value: &Value, hops: usize) -> Value {
+    match value {
+        Value::Object(map) => {
+            if hops < 8
+                && let Some(target) = map
+                    .get("$ref")
+                    .and_then(Value::as_str)
+                    .filter(|r| {
+                        !r.starts_with("#/components/schemas/") && !r.starts_with("#/definitions/")
+                    })
+                    .and_then(|r| r.strip_prefix('#'))
+                    .and_then(|pointer| raw.pointer(pointer))
+            {
+                return inline_refs(raw, target, hops + 1);
+            }
+            Value::Object(
+                map.iter()
+                    .map(|(key, value)| (key.clone(), inline_refs(raw, value, hops)))
+                    .collect(),
+            )
+        }
+        Value::Array(items) => {
+            Value::Array(items.iter().map(|v| inline_refs(raw, v, hops)).collect())
+        }
+        other => other.clone(),
+    }
+}
+
+/// The direct properties of a schema. `allOf` / `oneOf` / `anyOf` branches
+/// (inline or `$ref`) are flattened in, annotated with the outermost combiner.
+fn properties(schema: &TreeNode) -> Vec<(&TreeNode, Option<&'static str>)> {
+    let mut out = Vec::new();
+    collect_properties(schema, None, &mut out);
+    out
+}
+
+fn collect_properties<'a>(
+    node: &'a TreeNode,
+    combiner: Option<&'static str>,
+    out: &mut Vec<(&'a TreeNode, Option<&'static str>)>,
+) {
+    let keyword = match node.info.kind {
+        NodeKind::AllOf => Some("allOf"),
+        NodeKind::OneOf => Some("oneOf"),
+        NodeKind::AnyOf => Some("anyOf"),
+        _ => None,
+    };
+    match (&node.info.kind, keyword) {
+        (_, Some(keyword)) => {
+            for branch in &node.children {
+                collect_properties(branch, combiner.or(Some(keyword)), out);
+            }
+        }
+        // Only a combiner branch that is a `$ref` contributes the target's properties.
+        (NodeKind::Ref(_), None) if combiner.is_some() => {
+            out.extend(node.children.iter().map(|c| (c, combiner)));
+        }
+        (NodeKind::Object, None) => {
+            for child in &node.children {
+                match child.info.kind {
+                    // 3.1 keyword containers (`$defs`, `if` / `then` / `else`) are not properties.
+                    NodeKind::Group(_) | NodeKind::Conditional | NodeKind::Not => {}
+                    NodeKind::AllOf | NodeKind::OneOf | NodeKind::AnyOf => {
+                        collect_properties(child, combiner, out)
+                    }
+                    _ => out.push((child, combiner)),
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Type for the `type` columns; `$ref`s are named in `ref_target` instead.
+fn type_name(node: &TreeNode) -> String {
+    match &node.info.kind {
+        NodeKind::Ref(_) => "$ref".to_string(),
+        _ => node.type_label(),
+    }
+}
+
+fn mtime_of(path: &Path) -> Result<i64> {
+    let modified = fs::metadata(path)?.modified()?;
+    let nanos = modified.duration_since(UNIX_EPOCH)?.as_nanos();
+    Ok(i64::try_from(nanos).unwrap_or(i64::MAX))
+}
+
+/// `2026-02-18T17:00:00Z` (UTC, whole seconds).
+pub(crate) fn iso8601(time: SystemTime) -> String {
+    let secs = time
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let (days, rem) = (secs / 86_400, secs % 86_400);
+
+    // Civil-from-days (Howard Hinnant), days since 1970-01-01.
+    let z = days as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+        rem / 3_600,
+        rem / 60 % 60,
+        rem % 60
+    )
+}
+
+// ── tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_spec;
+    use std::time::Duration;
+
+    /// A throwaway `specs/` directory with the given services.
+    fn specs_dir(label: &str, services: &[(&str, &str)]) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("speq-index-{label}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        for (name, spec) in services {
+            let service_dir = dir.join(name);
+            fs::create_dir_all(&service_dir).unwrap();
+            fs::write(service_dir.join("openapi.yaml"), spec).unwrap();
+            fs::write(
+                service_dir.join("meta.yaml"),
+                format!(
+                    "service: {name}\nrepo: git@github.com:acme/{name}\nteam: pets\n\
+                     cached_at: 2026-02-18T17:00:00Z\n"
+                ),
+            )
+            .unwrap();
+        }
+        dir
+    }
+
+    fn index(conn: &mut Connection, dir: &Path, force: bool) -> (Summary, String) {
+        let options = IndexOptions {
+            force,
+            service: None,
+        };
+        let mut out = Vec::new();
+        let summary = index_all(conn, dir, &options, &mut out).unwrap();
+        (summary, String::from_utf8(out).unwrap())
+    }
+
+    fn memory_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::db::init(&conn).unwrap();
+        conn
+    }
+
+    #[test]
+    fn indexes_services_and_skips_unchanged() {
+        let dir = specs_dir(
+            "skip",
+            &[
+                ("petstore", include_str!("../fixtures/petstore.yaml")),
+                ("petstore-v2", include_str!("../fixtures/petstore-v2.yaml")),
+                ("broken", "openapi: 3.0.0\n"),
+            ],
+        );
+        let mut conn = memory_db();
+
+        let (summary, out) = index(&mut conn, &dir, false);
+        assert_eq!(
+            summary,
+            Summary {
+                indexed: 2,
+                skipped: 0,
+                errors: 1,
+                removed: 0
+            }
+        );
+        assert!(
+            out.contains("indexing petstore    ... ok (4 schemas, 3 paths)"),
+            "{out}"
+        );
+        assert!(out.contains("indexing broken      ... error: failed to parse openapi.yaml"));
+        assert!(out.ends_with("indexed 2 services, 0 skipped, 1 error\n"));
+
+        let (summary, _) = index(&mut conn, &dir, false);
+        assert_eq!(
+            summary,
+            Summary {
+                indexed: 0,
+                skipped: 2,
+                errors: 1,
+                removed: 0
+            }
+        );
+        let (summary, _) = index(&mut conn, &dir, true);
+        assert_eq!(summary.indexed, 2);
+
+        // Re-indexing replaced rows rather than duplicating them.
+        let schemas: i64 = conn
+            .query_row("SELECT count(*) FROM schemas", [], |r| r.get(0))
+            .unwrap();
+        assert_eq!(schemas, 8);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    /// Give `path` a modification time `secs` in the future, as an edit would.
+    fn touch(path: &Path, secs: u64) {
+        let file = fs::File::options().append(true).open(path).unwrap();
+        file.set_modified(SystemTime::now() + Duration::from_secs(secs))
+            .unwrap();
+    }
+
+    #[test]
+    fn reindexes_on_meta_and_ref_edits_and_drops_gone_services() {
+        let orders = r##"
+openapi: 3.0.3
+info: { title: Orders, version: "1" }
+paths: {}
+components:
+  schemas:
+    Order:
+      type: object
+      properties:
+        total: { $ref: "./common.yaml#/components/schemas/Money" }
+"##;
+        let dir = specs_dir(
+            "fingerprint",
+            &[
+                ("orders", orders),
+                ("petstore", include_str!("../fixtures/petstore.yaml")),
+            ],
+        );
+        fs::write(
+            dir.join("orders/common.yaml"),
+            "components:\n  schemas:\n    Money: { type: number }\n",
+        )
+        .unwrap();
+        let mut conn = memory_db();
+        assert_eq!(index(&mut conn, &dir, false).0.indexed, 2);
+        assert_eq!(index(&mut conn, &dir, false).0.skipped, 2);
+
+        touch(&dir.join("orders/common.yaml"), 10);
+        let (summary, out) = index(&mut conn, &dir, false);
+        assert_eq!((summary.indexed, summary.skipped), (1, 1), "{out}");
+
+        let meta = dir.join("petstore/meta.yaml");
+        let text = fs::read_to_string(&meta)
+            .unwrap()
+            .replace("team: pets", "team: animals");
+        fs::write(&meta, text).unwrap();
+        touch(&meta, 20);
+        let (summary, _) = index(&mut conn, &dir, false);
+        assert_eq!((summary.indexed, summary.skipped), (1, 1));
+        let team: String = conn
+            .query_row(
+                "SELECT team FROM services WHERE name = 'petstore'",
+                [],
+                |r| r.get(0),
+            )
+            .unwrap();
+        assert_eq!(team, "animals");
+
+        fs::remove_dir_all(dir.join("petstore")).unwrap();
+        let (summary, out) = index(&mut conn, &dir, false);
+        assert_eq!(summary.removed, 1);
+        assert!(
+            out.ends_with("indexed 0 services, 1 skipped, 0 errors, 1 removed\n"),
+            "{out}"
+        );
+        let rows: i64 = conn
+            .query_row(
+                "SELECT (SELECT count(*) FROM services) + (SELECT count(*) FROM search_index
+                 WHERE service_name = 'petstore')",
+                [],
+                |r| r.get(0),
+            )
+            .unwrap();
+        assert_eq!(rows, 1);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn writes_properties_and_search_rows() {
+        let dir = specs_dir(
+            "rows",
+            &[("petstore", include_str!("../fixtures/petstore.yaml"))],
+        );
+        let mut conn = memory_db();
+        index(&mut conn, &dir, false);
+
+        let (kind, required): (String, bool) = conn
+            .query_row(
+                "SELECT p.type, p.required FROM properties p JOIN schemas s ON s.id = p.schema_id
+                 WHERE s.name = 'Pet' AND p.name = 'id'",
+                [],
+                |r| Ok((r.get(0)?, r.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!((kind.as_str(), required), ("integer", true));
+
+        let raw: String = conn
+            .query_row(
+                "SELECT raw_json FROM schemas WHERE name = 'Pets'",
+                [],
+                |r| r.get(0),
+            )
+            .unwrap();
+        assert!(raw.contains("#/components/schemas/Pet"));
+
+        let hit: String = conn
+            .query_row(
+                "SELECT schema_name FROM search_index WHERE search_index MATCH 'property_name:tag'
+                 ORDER BY bm25(search_index) LIMIT 1",
+                [],
+                |r| r.get(0),
+            )
+            .unwrap();
+        assert!(hit == "Pet" || hit == "NewPet");
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn flattens_combiner_branches() {
+        let spec = parse_spec(
+            r##"
+openapi: 3.0.0
+info: { title: t, version: "1" }
+paths: {}
+components:
+  schemas:
+    Base:
+      type: object
+      properties:
+        id: { type: string }
+    Dog:
+      allOf:
+        - $ref: "#/components/schemas/Base"
+        - type: object
+          properties:
+            bark: { type: boolean }
+"##,
+        )
+        .unwrap();
+        let dog = spec.schema_nodes.iter().find(|s| s.name == "Dog").unwrap();
+        let props: Vec<(&str, Option<&str>)> = properties(dog)
+            .into_iter()
+            .map(|(p, c)| (p.name.as_str(), c))
+            .collect();
+        assert_eq!(props, vec![("id", Some("allOf")), ("bark", Some("allOf"))]);
+    }
+
+    #[test]
+    fn formats_utc_timestamps() {
+        let time = UNIX_EPOCH + Duration::from_secs(1_771_434_000);
+        assert_eq!(iso8601(time), "2026-02-18T17:00:00Z");
+    }
+}
@@ -0,0 +1,481 @@
+use std::collections::HashMap;
+
+use crossterm::event::{KeyCode, KeyModifiers};
+
+/// Everything a key sequence can be bound to. Names match the `action`
+/// strings accepted by `speq.keymap.set` in `config.lua`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    MoveDown,
+    MoveUp,
+    GotoTop,
+    GotoBottom,
+    ToggleNode,
+    ExpandNode,
+    CollapseNode,
+    ExpandAll,
+    CollapseAll,
+    FollowRef,
+    JumpBack,
+    JumpForward,
+    YankPointer,
+    YankExample,
+    Search,
+    SearchNext,
+    SearchPrev,
+    Filter,
+    ClearFilter,
+    Cancel,
+    ScrollDown,
+    ScrollUp,
+    FocusNextPane,
+    ToggleView,
+    SwitchService,
+    GlobalSearch,
+    Diagnostics,
+    Validate,
+    Usages,
+    Quit,
+    Noop, // binds a key to nothing, hiding a default
+}
+
+/// Action identifiers accepted in config files. `open_node` is kept as an alias
+/// of `expand_node` (the names used in the original design).
+const ACTION_NAMES: &[(&str, Action)] = &[
+    ("move_down", Action::MoveDown),
+    ("move_up", Action::MoveUp),
+    ("goto_top", Action::GotoTop),
+    ("goto_bottom", Action::GotoBottom),
+    ("toggle_node", Action::ToggleNode),
+    ("expand_node", Action::ExpandNode),
+    ("open_node", Action::ExpandNode),
+    ("collapse_node", Action::CollapseNode),
+    ("expand_all", Action::ExpandAll),
+    ("collapse_all", Action::CollapseAll),
+    ("follow_ref", Action::FollowRef),
+    ("jump_back", Action::JumpBack),
+    ("jump_forward", Action::JumpForward),
+    ("yank_pointer", Action::YankPointer),
+    ("yank_example", Action::YankExample),
+    ("search", Action::Search),
+    ("search_next", Action::SearchNext),
+    ("search_prev", Action::SearchPrev),
+    ("filter", Action::Filter),
+    ("clear_filter", Action::ClearFilter),
+    ("cancel", Action::Cancel),
+    ("scroll_down", Action::ScrollDown),
+    ("scroll_up", Action::ScrollUp),
+    ("focus_next_pane", Action::FocusNextPane),
+    ("toggle_view", Action::ToggleView),
+    ("switch_service", Action::SwitchService),
+    ("global_search", Action::GlobalSearch),
+    ("diagnostics", Action::Diagnostics),
+    ("validate", Action::Validate),
+    ("usages", Action::Usages),
+    ("quit", Action::Quit),
+    ("noop", Action::Noop),
+];
+
+/// Built-in normal-mode bindings, in nvim notation. `config.lua` is applied on top.
+const DEFAULT_BINDINGS: &[(&str, Action)] = &[
+    ("j", Action::MoveDown),
+    ("<Down>", Action::MoveDown),
+    ("k", Action::MoveUp),
+    ("<Up>", Action::MoveUp),
+    ("gg", Action::GotoTop),
+    ("G", Action::GotoBottom),
+    ("l", Action::ToggleNode),
+    ("<CR>", Action::ToggleNode),
+    ("h", Action::CollapseNode),
+    ("zo", Action::ExpandNode),
+    ("zc", Action::CollapseNode),
+    ("zR", Action::ExpandAll),
+    ("zM", Action::CollapseAll),
+    ("gd", Action::FollowRef),
+    // Most terminals send Ctrl-i as Tab; it is only distinct when the terminal
+    // supports keyboard enhancement (enabled in `main`).
+    ("<C-o>", Action::JumpBack),
+    ("<C-i>", Action::JumpForward),
+    ("yp", Action::YankPointer),
+    ("ye", Action::YankExample),
+    ("/", Action::Search),
+    ("n", Action::SearchNext),
+    ("N", Action::SearchPrev),
+    ("f", Action::Filter),
+    ("F", Action::ClearFilter),
+    ("<Esc>", Action::Cancel),
+    ("<C-d>", Action::ScrollDown),
+    ("<C-u>", Action::ScrollUp),
+    ("<Tab>", Action::FocusNextPane),
+    ("p", Action::ToggleView),
+    ("S", Action::SwitchService),
+    ("gs", Action::GlobalSearch),
+    ("D", Action::Diagnostics),
+    ("V", Action::Validate),
+    ("gr", Action::Usages),
+    ("q", Action::Quit),
+    ("ZZ", Action::Quit),
+    ("<C-c>", Action::Quit),
+];
+
+/// Built-in bindings inside the list popups (`gs`, `D`, `gr`, `V`). `toggle_node`
+/// opens the selected entry there. In the `gs` popup plain characters are typed
+/// into the query instead, so only the non-character keys apply.
+const DEFAULT_POPUP_BINDINGS: &[(&str, Action)] = &[
+    ("j", Action::MoveDown),
+    ("<Down>", Action::MoveDown),
+    ("<Tab>", Action::MoveDown),
+    ("<C-n>", Action::MoveDown),
+    ("k", Action::MoveUp),
+    ("<Up>", Action::MoveUp),
+    ("<S-Tab>", Action::MoveUp),
+    ("<C-p>", Action::MoveUp),
+    ("<CR>", Action::ToggleNode),
+    ("<Esc>", Action::Cancel),
+    ("q", Action::Cancel),
+];
+
+pub const DEFAULT_LEADER: &str = " ";
+
+/// Which set of bindings a key goes through: `"n"` or `"p"` in `config.lua`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    Normal,
+    Popup,
+}
+
+impl Mode {
+    pub fn from_name(name: &str) -> Option<Mode> {
+        match name {
+            "n" => Some(Mode::Normal),
+            "p" => Some(Mode::Popup),
+            _ => None,
+        }
+    }
+}
+
+impl Action {
+    pub fn from_name(name: &str) -> Option<Action> {
+        ACTION_NAMES
+            .iter()
+            .find(|(n, _)| *n == name)
+            .map(|(_, action)| *action)
+    }
+}
+
+/// One key press, normalised so it compares equal to what crossterm reports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Key {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl Key {
+    /// Shift is already part of a character's case (`G`), so it is dropped
+    /// for `Char` keys; it is kept for everything else (`<S-Tab>`).
+    pub fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        let modifiers = match code {
+            KeyCode::Char(_) => modifiers - KeyModifiers::SHIFT,
+            _ => modifiers,
+        };
+        Key { code, modifiers }
+    }
+}
+
+/// Parse an nvim-style key string (`gd`, `<C-d>`, `<leader>q`) into keys.
+pub fn parse_keys(lhs: &str, leader: &[Key]) -> Result<Vec<Key>, String> {
+    let mut keys = Vec::new();
+    let mut rest = lhs;
+    while let Some(c) = rest.chars().next() {
+        if c == '<'
+            && let Some(end) = rest.find('>')
+            && end > 1
+        {
+            let name = &rest[1..end];
+            if name.eq_ignore_ascii_case("leader") {
+                keys.extend_from_slice(leader);
+            } else {
+                keys.push(parse_special(name).ok_or_else(|| format!("unknown key <{name}>"))?);
+            }
+            rest = &rest[end + 1..];
+        } else {
+            keys.push(Key::new(KeyCode::Char(c), KeyModifiers::NONE));
+            rest = &rest[c.len_utf8()..];
+        }
+    }
+    if keys.is_empty() {
+        return Err("empty key sequence".to_string());
+    }
+    Ok(keys)
+}
+
+/// The inside of `<...>`: optional `C-` / `S-` / `M-` / `A-` prefixes, then a key name.
+fn parse_special(name: &str) -> Option<Key> {
+    let mut modifiers = KeyModifiers::NONE;
+    let mut base = name;
+    while base.len() > 2 && base.as_bytes()[1] == b'-' {
+        modifiers |= match base.as_bytes()[0].to_ascii_uppercase() {
+            b'C' => KeyModifiers::CONTROL,
+            b'S' => KeyModifiers::SHIFT,
+            b'M' | b'A' => KeyModifiers::ALT,
+            _ => return None,
+        };
+        base = &base[2..];
+    }
+
+    let code = match base.to_ascii_lowercase().as_str() {
+        "cr" | "enter" | "return" => KeyCode::Enter,
+        "esc" => KeyCode::Esc,
+        "tab" if modifiers.contains(KeyModifiers::SHIFT) => {
+            modifiers -= KeyModifiers::SHIFT;
+            KeyCode::BackTab
+        }
+        "tab" => KeyCode::Tab,
+        "bs" | "backspace" => KeyCode::Backspace,
+        "del" => KeyCode::Delete,
+        "space" => KeyCode::Char(' '),
+        "lt" => KeyCode::Char('<'),
+        "bslash" => KeyCode::Char('\\'),
+        "bar" => KeyCode::Char('|'),
+        "up" => KeyCode::Up,
+        "down" => KeyCode::Down,
+        "left" => KeyCode::Left,
+        "right" => KeyCode::Right,
+        "home" => KeyCode::Home,
+        "end" => KeyCode::End,
+        "pageup" => KeyCode::PageUp,
+        "pagedown" => KeyCode::PageDown,
+        lower => {
+            if let Some(n) = lower.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+                KeyCode::F(n)
+            } else {
+                let mut chars = base.chars();
+                let c = chars.next()?;
+                if chars.next().is_some() {
+                    return None;
+                }
+                // `<C-D>` means the same as `<C-d>` in nvim, and `<S-a>` means `A`.
+                let c = if modifiers.contains(KeyModifiers::CONTROL) {
+                    c.to_ascii_lowercase()
+                } else if modifiers.contains(KeyModifiers::SHIFT) {
+                    c.to_ascii_uppercase()
+                } else {
+                    c
+                };
+                KeyCode::Char(c)
+            }
+        }
+    };
+    Some(Key::new(code, modifiers))
+}
+
+#[derive(Default)]
+struct KeymapNode {
+    action: Option<Action>,
+    children: HashMap<Key, KeymapNode>,
+}
+
+/// Key bindings per mode, each stored as a trie so any multi-key sequence works.
+pub struct Keymap {
+    normal: KeymapNode,
+    popup: KeymapNode,
+    leader: Vec<Key>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        let mut keymap = Keymap {
+            normal: KeymapNode::default(),
+            popup: KeymapNode::default(),
+            leader: parse_keys(DEFAULT_LEADER, &[]).expect("default leader parses"),
+        };
+        for (lhs, action) in DEFAULT_BINDINGS {
+            keymap
+                .set(Mode::Normal, lhs, *action)
+                .expect("default bindings parse");
+        }
+        for (lhs, action) in DEFAULT_POPUP_BINDINGS {
+            keymap
+                .set(Mode::Popup, lhs, *action)
+                .expect("default bindings parse");
+        }
+        keymap
+    }
+}
+
+impl Keymap {
+    /// Set the key(s) `<leader>` expands to in later `set` calls.
+    pub fn set_leader(&mut self, leader: &str) -> Result<(), String> {
+        self.leader = parse_keys(leader, &[])?;
+        Ok(())
+    }
+
+    /// Bind `lhs` (nvim notation) to `action` in `mode`, replacing any existing binding.
+    pub fn set(&mut self, mode: Mode, lhs: &str, action: Action) -> Result<(), String> {
+        let keys = parse_keys(lhs, &self.leader)?;
+        let mut node = match mode {
+            Mode::Normal => &mut self.normal,
+            Mode::Popup => &mut self.popup,
+        };
+        for key in keys {
+            node = node.children.entry(key).or_default();
+        }
+        node.action = Some(action);
+        Ok(())
+    }
+
+    /// Feed one key press. `pending` holds the keys typed so far in an
+    /// unfinished sequence. Returns the actions to run, in order.
+    ///
+    /// There is no timeout: when a key does not continue the pending sequence,
+    /// the sequence's own binding (if any) runs and the key starts afresh.
+    pub fn feed(&self, mode: Mode, pending: &mut Vec<Key>, key: Key) -> Vec<Action> {
+        let prefix = self.lookup(mode, pending);
+        match prefix.and_then(|node| node.children.get(&key)) {
+            Some(node) if !node.children.is_empty() => {
+                pending.push(key);
+                Vec::new()
+            }
+            Some(node) => {
+                pending.clear();
+                node.action.into_iter().collect()
+            }
+            None if pending.is_empty() => Vec::new(),
+            None => {
+                let mut actions: Vec<Action> =
+                    prefix.and_then(|node| node.action).into_iter().collect();
+                pending.clear();
+                actions.extend(self.feed(mode, pending, key));
+                actions
+            }
+        }
+    }
+
+    fn lookup(&self, mode: Mode, keys: &[Key]) -> Option<&KeymapNode> {
+        let mut node = match mode {
+            Mode::Normal => &self.normal,
+            Mode::Popup => &self.popup,
+        };
+        for key in keys {
+            node = node.children.get(key)?;
+        }
+        Some(node)
+    }
+}
+
+// ── tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(c: char) -> Key {
+        Key::new(KeyCode::Char(c), KeyModifiers::NONE)
+    }
+
+    fn feed_all(keymap: &Keymap, keys: &[Key]) -> Vec<Action> {
+        let mut pending = Vec::new();
+        keys.iter()
+            .flat_map(|k| keymap.feed(Mode::Normal, &mut pending, *k))
+            .collect()
+    }
+
+    #[test]
+    fn parses_nvim_notation() {
+        let ctrl_d = Key::new(KeyCode::Char('d'), KeyModifiers::CONTROL);
+        assert_eq!(parse_keys("<C-d>", &[]), Ok(vec![ctrl_d]));
+        assert_eq!(parse_keys("<c-D>", &[]), Ok(vec![ctrl_d]));
+        assert_eq!(
+            parse_keys("<CR><Esc>", &[]),
+            Ok(vec![
+                Key::new(KeyCode::Enter, KeyModifiers::NONE),
+                Key::new(KeyCode::Esc, KeyModifiers::NONE)
+            ])
+        );
+        assert_eq!(
+            parse_keys("<leader>q", &[key(',')]),
+            Ok(vec![key(','), key('q')])
+        );
+        assert_eq!(parse_keys("<lt>", &[]), Ok(vec![key('<')]));
+        assert!(parse_keys("<Nope>", &[]).is_err());
+        // Shift is folded into the character, matching what crossterm reports.
+        assert_eq!(
+            Key::new(KeyCode::Char('G'), KeyModifiers::SHIFT),
+            parse_keys("G", &[]).unwrap()[0]
+        );
+    }
+
+    #[test]
+    fn multi_key_sequences_wait_for_completion() {
+        let keymap = Keymap::default();
+        let mut pending = Vec::new();
+        assert!(keymap.feed(Mode::Normal, &mut pending, key('g')).is_empty());
+        assert_eq!(
+            keymap.feed(Mode::Normal, &mut pending, key('d')),
+            vec![Action::FollowRef]
+        );
+        assert!(pending.is_empty());
+    }
+
+    #[test]
+    fn broken_sequence_replays_the_new_key() {
+        let keymap = Keymap::default();
+        // `g` then `j`: `g` alone is unbound, `j` still moves down.
+        assert_eq!(
+            feed_all(&keymap, &[key('g'), key('j')]),
+            vec![Action::MoveDown]
+        );
+    }
+
+    #[test]
+    fn prefix_binding_fires_when_sequence_breaks() {
+        let mut keymap = Keymap::default();
+        keymap.set(Mode::Normal, "g", Action::GotoBottom).unwrap();
+        assert_eq!(
+            feed_all(&keymap, &[key('g'), key('g')]),
+            vec![Action::GotoTop]
+        );
+        assert_eq!(
+            feed_all(&keymap, &[key('g'), key('k')]),
+            vec![Action::GotoBottom, Action::MoveUp]
+        );
+    }
+
+    #[test]
+    fn leader_and_noop_overrides() {
+        let mut keymap = Keymap::default();
+        keymap.set_leader(",").unwrap();
+        keymap.set(Mode::Normal, "<leader>q", Action::Quit).unwrap();
+        keymap.set(Mode::Normal, "q", Action::Noop).unwrap();
+        assert_eq!(feed_all(&keymap, &[key(','), key('q')]), vec![Action::Quit]);
+        assert_eq!(feed_all(&keymap, &[key('q')]), vec![Action::Noop]);
+    }
+
+    #[test]
+    fn every_action_has_a_name() {
+        assert_eq!(Action::from_name("open_node"), Some(Action::ExpandNode));
+        assert_eq!(Action::from_name("nope"), None);
+        for (_, action) in DEFAULT_BINDINGS.iter().chain(DEFAULT_POPUP_BINDINGS) {
+            assert!(ACTION_NAMES.iter().any(|(_, a)| a == action));
+        }
+    }
+
+    #[test]
+    fn popup_bindings_are_separate_from_normal_mode() {
+        let mut keymap = Keymap::default();
+        let mut pending = Vec::new();
+        let mut feed = |keymap: &Keymap, mode, k| keymap.feed(mode, &mut pending, k);
+        assert_eq!(feed(&keymap, Mode::Popup, key('q')), vec![Action::Cancel]);
+        assert_eq!(feed(&keymap, Mode::Normal, key('q')), vec![Action::Quit]);
+
+        keymap.set(Mode::Popup, "<C-j>", Action::MoveDown).unwrap();
+        keymap.set(Mode::Popup, "j", Action::Noop).unwrap();
+        let ctrl_j = Key::new(KeyCode::Char('j'), KeyModifiers::CONTROL);
+        assert_eq!(feed(&keymap, Mode::Popup, ctrl_j), vec![Action::MoveDown]);
+        assert_eq!(feed(&keymap, Mode::Popup, key('j')), vec![Action::Noop]);
+        assert_eq!(
+            feed(&keymap, Mode::Normal, key('j')),
+            vec![Action::MoveDown]
+        );
+    }
+}
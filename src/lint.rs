@@ -0,0 +1,946 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+
+use clap::ValueEnum;
+use serde::Serialize;
+use serde_json::{Value, json};
+
+use crate::breadcrumb::escape;
+use crate::locate;
+use crate::parser::Bundle;
+use crate::spec::{LoadedSpec, SpecVersion};
+
+/// How much a finding matters; `--fail-on` picks the lowest that fails CI.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, ValueEnum, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+impl Severity {
+    pub fn label(self) -> &'static str {
+        match self {
+            Severity::Info => "info",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        }
+    }
+
+    fn sarif_level(self) -> &'static str {
+        match self {
+            Severity::Info => "note",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        }
+    }
+}
+
+/// `speq lint --format`.
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum Format {
+    Text,
+    Json,
+    Sarif,
+}
+
+pub struct Rule {
+    pub id: &'static str,
+    pub severity: Severity,
+    pub summary: &'static str,
+}
+
+/// The built-in rule set, with each rule's severity.
+pub const RULES: &[Rule] = &[
+    Rule {
+        id: "missing-operation-id",
+        severity: Severity::Error,
+        summary: "Operations need an operationId (client generators name methods after it)",
+    },
+    Rule {
+        id: "array-without-items",
+        severity: Severity::Error,
+        summary: "Array schemas must say what their items are",
+    },
+    Rule {
+        id: "missing-description",
+        severity: Severity::Warning,
+        summary: "Operations and top-level schemas should be described",
+    },
+    Rule {
+        id: "property-casing",
+        severity: Severity::Warning,
+        summary: "Property names should follow the casing most of the spec uses",
+    },
+    Rule {
+        id: "unused-component",
+        severity: Severity::Warning,
+        summary: "Components nothing refers to are dead weight",
+    },
+    Rule {
+        id: "ref-siblings",
+        severity: Severity::Warning,
+        summary: "Keys next to a $ref are ignored",
+    },
+    Rule {
+        id: "enum-without-type",
+        severity: Severity::Warning,
+        summary: "Enums should declare the type of their values",
+    },
+    Rule {
+        id: "integer-without-format",
+        severity: Severity::Info,
+        summary: "Integers should say whether they are int32 or int64",
+    },
+];
+
+/// One rule violation, located in the file it comes from.
+#[derive(Debug, Clone, Serialize)]
+pub struct Finding {
+    pub rule: &'static str,
+    pub severity: Severity,
+    pub message: String,
+    pub file: String,
+    pub line: Option<usize>,
+    pub pointer: String, // JSON Pointer fragment within `file`
+}
+
+/// The spec as given on the command line, for `file:line` locations.
+pub struct Input<'a> {
+    pub label: &'a str,         // file path, URL or `<stdin>`
+    pub content: &'a str,       // its text, as read
+    pub path: Option<&'a Path>, // set for a file: other files resolve against it
+}
+
+/// Run every rule over a spec: operations from the parsed model, schemas
+/// from the bundled document (so each is checked once, not at every `$ref`).
+pub fn lint(spec: &LoadedSpec, bundle: &Bundle, input: &Input) -> Vec<Finding> {
+    let mut linter = Linter {
+        spec,
+        doc: &bundle.document,
+        found: Vec::new(),
+    };
+    linter.operations();
+    linter.schemas();
+    linter.references();
+    locate(linter.found, spec, bundle, input)
+}
+
+/// Turn `(rule, pointer into the bundled document, message)` triples into
+/// findings located in their files, sorted by file and line.
+pub fn locate(
+    found: Vec<(&'static Rule, String, String)>,
+    spec: &LoadedSpec,
+    bundle: &Bundle,
+    input: &Input,
+) -> Vec<Finding> {
+    let mut locator = Locator {
+        input,
+        sources: &bundle.sources,
+        swagger: spec.version == SpecVersion::V20,
+        files: HashMap::new(),
+    };
+    let mut findings: Vec<Finding> = found
+        .into_iter()
+        .map(|(rule, pointer, message)| {
+            let (file, pointer, line) = locator.locate(&pointer);
+            Finding {
+                rule: rule.id,
+                severity: rule.severity,
+                message,
+                file,
+                line,
+                pointer,
+            }
+        })
+        .collect();
+    findings.sort_by(|a, b| (&a.file, a.line, a.rule).cmp(&(&b.file, b.line, b.rule)));
+    findings
+}
+
+struct Linter<'a> {
+    spec: &'a LoadedSpec,
+    doc: &'a Value,
+    found: Vec<(&'static Rule, String, String)>, // rule, pointer into `doc`, message
+}
+
+const METHODS: [&str; 8] = [
+    "get", "put", "post", "delete", "options", "head", "patch", "trace",
+];
+
+impl<'a> Linter<'a> {
+    fn add(&mut self, id: &str, pointer: String, message: String) {
+        let rule = RULES
+            .iter()
+            .find(|r| r.id == id)
+            .expect("unknown lint rule");
+        self.found.push((rule, pointer, message));
+    }
+
+    fn swagger(&self) -> bool {
+        self.spec.version == SpecVersion::V20
+    }
+
+    fn operations(&mut self) {
+        for op in &self.spec.operations {
+            let pointer = format!("/paths/{}/{}", escape(&op.path), op.method);
+            let name = format!("{} {}", op.method.to_uppercase(), op.path);
+            if op.operation_id.is_none() {
+                self.add(
+                    "missing-operation-id",
+                    pointer.clone(),
+                    format!("{name} has no operationId"),
+                );
+            }
+            if op.summary.is_none() && op.description.is_none() {
+                self.add(
+                    "missing-description",
+                    pointer,
+                    format!("{name} has no summary or description"),
+                );
+            }
+        }
+    }
+
+    fn schemas(&mut self) {
+        let container = if self.swagger() {
+            "/definitions"
+        } else {
+            "/components/schemas"
+        };
+        if let Some(schemas) = self.doc.pointer(container).and_then(Value::as_object) {
+            for (name, schema) in schemas {
+                if schema.get("description").is_none() && schema.get("$ref").is_none() {
+                    let pointer = format!("{container}/{}", escape(name));
+                    self.add(
+                        "missing-description",
+                        pointer,
+                        format!("schema {name} has no description"),
+                    );
+                }
+            }
+        }
+
+        let mut schemas = Vec::new();
+        for (pointer, schema) in schema_roots(self.doc, self.swagger()) {
+            walk_schema(schema, pointer, &mut schemas);
+        }
+
+        let mut properties = Vec::new();
+        for (pointer, schema) in &schemas {
+            let Some(obj) = schema.as_object() else {
+                continue;
+            };
+            if obj.contains_key("$ref") {
+                continue;
+            }
+            let types = types(schema);
+            if types.contains(&"array")
+                && !obj.contains_key("items")
+                && !obj.contains_key("prefixItems")
+            {
+                self.add(
+                    "array-without-items",
+                    pointer.clone(),
+                    "array schema without items".to_string(),
+                );
+            }
+            if obj.contains_key("enum") && types.is_empty() && !obj.contains_key("const") {
+                self.add(
+                    "enum-without-type",
+                    pointer.clone(),
+                    "enum without a type".to_string(),
+                );
+            }
+            if types.contains(&"integer") && !obj.contains_key("format") {
+                self.add(
+                    "integer-without-format",
+                    pointer.clone(),
+                    "integer without a format (int32 / int64)".to_string(),
+                );
+            }
+            if let Some(props) = obj.get("properties").and_then(Value::as_object) {
+                for name in props.keys() {
+                    properties.push((
+                        name.clone(),
+                        format!("{pointer}/properties/{}", escape(name)),
+                    ));
+                }
+            }
+        }
+        self.casing(&properties);
+    }
+
+    /// Flag property names that break from the casing most of the spec uses.
+    fn casing(&mut self, properties: &[(String, String)]) {
+        let mut counts: HashMap<Case, usize> = HashMap::new();
+        for (name, _) in properties {
+            *counts.entry(Case::of(name)).or_default() += 1;
+        }
+        let Some(dominant) = Case::STYLES
+            .iter()
+            .copied()
+            .filter(|case| counts.get(case).is_some_and(|&n| n > 0))
+            .max_by_key(|case| {
+                (
+                    counts[case],
+                    std::cmp::Reverse(Case::STYLES.iter().position(|c| c == case)),
+                )
+            })
+        else {
+            return;
+        };
+        for (name, pointer) in properties {
+            let case = Case::of(name);
+            if case != dominant && Case::STYLES.contains(&case) && !case.fits(dominant) {
+                self.add(
+                    "property-casing",
+                    pointer.clone(),
+                    format!(
+                        "property {name} is {}, but most properties are {}",
+                        case.label(),
+                        dominant.label()
+                    ),
+                );
+            }
+        }
+    }
+
+    /// `$ref` siblings, and components no `$ref` points at.
+    fn references(&mut self) {
+        let mut schema_pointers = Vec::new();
+        for (pointer, schema) in schema_roots(self.doc, self.swagger()) {
+            walk_schema(schema, pointer, &mut schema_pointers);
+        }
+        let schema_pointers: std::collections::HashSet<String> =
+            schema_pointers.into_iter().map(|(p, _)| p).collect();
+
+        let mut refs = Vec::new();
+        let mut siblings = Vec::new();
+        collect_refs(self.doc, String::new(), &mut refs, &mut siblings);
+        for (pointer, keys) in siblings {
+            // 3.1 schemas are JSON Schema, where `$ref` is one keyword among
+            // others; 3.1 reference objects may carry a summary / description.
+            let keys: Vec<&String> = match self.spec.version {
+                SpecVersion::V31 if schema_pointers.contains(&pointer) => continue,
+                SpecVersion::V31 => keys
+                    .iter()
+                    .filter(|k| *k != "summary" && *k != "description")
+                    .collect(),
+                _ => keys.iter().collect(),
+            };
+            if keys.is_empty() {
+                continue;
+            }
+            let keys: Vec<&str> = keys.iter().map(|k| k.as_str()).collect();
+            self.add(
+                "ref-siblings",
+                pointer,
+                format!("ignored next to $ref: {}", keys.join(", ")),
+            );
+        }
+
+        let sections: &[&str] = if self.swagger() {
+            &["/definitions", "/parameters", "/responses"]
+        } else {
+            &[
+                "/components/schemas",
+                "/components/responses",
+                "/components/parameters",
+                "/components/examples",
+                "/components/requestBodies",
+                "/components/headers",
+                "/components/links",
+                "/components/callbacks",
+                "/components/pathItems",
+            ]
+        };
+        for section in sections {
+            let Some(components) = self.doc.pointer(section).and_then(Value::as_object) else {
+                continue;
+            };
+            for name in components.keys() {
+                let pointer = format!("{section}/{}", escape(name));
+                let inside = |at: &str| at == pointer || at.starts_with(&format!("{pointer}/"));
+                let used = refs
+                    .iter()
+                    .any(|(target, at)| inside(target) && !inside(at));
+                if !used {
+                    let kind = match section.rsplit('/').next().unwrap_or_default() {
+                        "schemas" | "definitions" => "schema",
+                        "requestBodies" => "requestBody",
+                        plural => plural.trim_end_matches('s'),
+                    };
+                    self.add(
+                        "unused-component",
+                        pointer.clone(),
+                        format!("{kind} {name} is never referenced"),
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// Property-name casing styles.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Case {
+    Lower,     // `name`: fits camel, snake and kebab case alike
+    Camel,     // `customerId`
+    Pascal,    // `CustomerId`
+    Snake,     // `customer_id`
+    Kebab,     // `customer-id`
+    Screaming, // `CUSTOMER_ID`
+    Other,
+}
+
+impl Case {
+    /// The styles a spec can settle on, in tie-break order.
+    const STYLES: [Case; 5] = [
+        Case::Camel,
+        Case::Snake,
+        Case::Pascal,
+        Case::Kebab,
+        Case::Screaming,
+    ];
+
+    /// Leading `_`, `@` and `$` (`_links`, `@type`, `$schema`) are conventions
+    /// of their own and are ignored.
+    fn of(name: &str) -> Case {
+        let name = name.trim_start_matches(['_', '@', '$']);
+        let Some(first) = name.chars().next() else {
+            return Case::Other;
+        };
+        if !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+        {
+            return Case::Other;
+        }
+        let lower = !name.chars().any(|c| c.is_ascii_uppercase());
+        let upper = !name.chars().any(|c| c.is_ascii_lowercase());
+        match (name.contains('_'), name.contains('-')) {
+            (true, true) => Case::Other,
+            (true, false) if lower => Case::Snake,
+            (true, false) if upper => Case::Screaming,
+            (false, true) if lower => Case::Kebab,
+            (true, false) | (false, true) => Case::Other,
+            (false, false) if lower => Case::Lower,
+            (false, false) if upper && name.len() > 1 => Case::Screaming,
+            (false, false) if first.is_ascii_uppercase() => Case::Pascal,
+            (false, false) => Case::Camel,
+        }
+    }
+
+    fn fits(self, style: Case) -> bool {
+        self == Case::Lower && matches!(style, Case::Camel | Case::Snake | Case::Kebab)
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Case::Lower => "lowercase",
+            Case::Camel => "camelCase",
+            Case::Pascal => "PascalCase",
+            Case::Snake => "snake_case",
+            Case::Kebab => "kebab-case",
+            Case::Screaming => "SCREAMING_CASE",
+            Case::Other => "mixed",
+        }
+    }
+}
+
+/// `type`, as a list (3.1 allows several).
+fn types(schema: &Value) -> Vec<&str> {
+    match schema.get("type") {
+        Some(Value::String(t)) => vec![t.as_str()],
+        Some(Value::Array(ts)) => ts.iter().filter_map(Value::as_str).collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Children of an object (escaped keys) or array (indices), with their pointers.
+fn children<'a>(value: Option<&'a Value>, pointer: &str) -> Vec<(String, &'a Value)> {
+    match value {
+        Some(Value::Object(map)) => map
+            .iter()
+            .map(|(k, v)| (format!("{pointer}/{}", escape(k)), v))
+            .collect(),
+        Some(Value::Array(items)) => items
+            .iter()
+            .enumerate()
+            .map(|(i, v)| (format!("{pointer}/{i}"), v))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Every place a schema starts outside another schema: component schemas and
+/// the schemas of parameters, headers, request bodies and responses.
+pub(crate) fn schema_roots(doc: &Value, swagger: bool) -> Vec<(String, &Value)> {
+    let mut roots = Vec::new();
+    if swagger {
+        roots.extend(children(doc.get("definitions"), "/definitions"));
+        for (pointer, param) in children(doc.get("parameters"), "/parameters") {
+            swagger_parameter(pointer, param, &mut roots);
+        }
+        for (pointer, resp) in children(doc.get("responses"), "/responses") {
+            swagger_response(pointer, resp, &mut roots);
+        }
+    } else {
+        roots.extend(children(
+            doc.pointer("/components/schemas"),
+            "/components/schemas",
+        ));
+        for section in ["parameters", "headers"] {
+            let at = format!("/components/{section}");
+            for (pointer, param) in children(doc.pointer(&at), &at) {
+                parameter(pointer, param, &mut roots);
+            }
+        }
+        for (pointer, resp) in children(
+            doc.pointer("/components/responses"),
+            "/components/responses",
+        ) {
+            response(pointer, resp, &mut roots);
+        }
+        for (pointer, body) in children(
+            doc.pointer("/components/requestBodies"),
+            "/components/requestBodies",
+        ) {
+            content(&pointer, body, &mut roots);
+        }
+    }
+
+    for (path_pointer, item) in children(doc.get("paths"), "/paths") {
+        for (key, value) in item.as_object().into_iter().flatten() {
+            let pointer = format!("{path_pointer}/{}", escape(key));
+            if key == "parameters" {
+                operation_parameters(pointer, value, swagger, &mut roots);
+            } else if METHODS.contains(&key.as_str()) {
+                operation_parameters(
+                    format!("{pointer}/parameters"),
+                    value.get("parameters").unwrap_or(&Value::Null),
+                    swagger,
+                    &mut roots,
+                );
+                if let Some(body) = value.get("requestBody") {
+                    content(&format!("{pointer}/requestBody"), body, &mut roots);
+                }
+                for (resp_pointer, resp) in
+                    children(value.get("responses"), &format!("{pointer}/responses"))
+                {
+                    if swagger {
+                        swagger_response(resp_pointer, resp, &mut roots);
+                    } else {
+                        response(resp_pointer, resp, &mut roots);
+                    }
+                }
+            }
+        }
+    }
+    roots
+}
+
+fn operation_parameters<'a>(
+    pointer: String,
+    params: &'a Value,
+    swagger: bool,
+    roots: &mut Vec<(String, &'a Value)>,
+) {
+    for (pointer, param) in children(Some(params), &pointer) {
+        if swagger {
+            swagger_parameter(pointer, param, roots);
+        } else {
+            parameter(pointer, param, roots);
+        }
+    }
+}
+
+/// A 3.x parameter or header: a `schema`, or a `content` map.
+fn parameter<'a>(pointer: String, param: &'a Value, roots: &mut Vec<(String, &'a Value)>) {
+    if let Some(schema) = param.get("schema") {
+        roots.push((format!("{pointer}/schema"), schema));
+    }
+    content(&pointer, param, roots);
+}
+
+fn response<'a>(pointer: String, resp: &'a Value, roots: &mut Vec<(String, &'a Value)>) {
+    content(&pointer, resp, roots);
+    for (pointer, header) in children(resp.get("headers"), &format!("{pointer}/headers")) {
+        parameter(pointer, header, roots);
+    }
+}
+
+fn content<'a>(pointer: &str, value: &'a Value, roots: &mut Vec<(String, &'a Value)>) {
+    for (pointer, media) in children(value.get("content"), &format!("{pointer}/content")) {
+        if let Some(schema) = media.get("schema") {
+            roots.push((format!("{pointer}/schema"), schema));
+        }
+    }
+}
+
+/// A 2.0 body parameter has a `schema`; any other parameter is schema-like itself.
+fn swagger_parameter<'a>(pointer: String, param: &'a Value, roots: &mut Vec<(String, &'a Value)>) {
+    match param.get("schema") {
+        Some(schema) => roots.push((format!("{pointer}/schema"), schema)),
+        None if param.get("$ref").is_none() => roots.push((pointer, param)),
+        None => {}
+    }
+}
+
+fn swagger_response<'a>(pointer: String, resp: &'a Value, roots: &mut Vec<(String, &'a Value)>) {
+    if let Some(schema) = resp.get("schema") {
+        roots.push((format!("{pointer}/schema"), schema));
+    }
+    roots.extend(children(resp.get("headers"), &format!("{pointer}/headers")));
+}
+
+/// Keywords whose value is one subschema / a list of them / a map of them.
+const SUBSCHEMA: [&str; 11] = [
+    "items",
+    "additionalProperties",
+    "unevaluatedProperties",
+    "unevaluatedItems",
+    "additionalItems",
+    "contains",
+    "propertyNames",
+    "not",
+    "if",
+    "then",
+    "else",
+];
+const SUBSCHEMA_LISTS: [&str; 4] = ["allOf", "oneOf", "anyOf", "prefixItems"];
+const SUBSCHEMA_MAPS: [&str; 4] = [
+    "properties",
+    "patternProperties",
+    "dependentSchemas",
+    "$defs",
+];
+
+/// Collect `schema` and every schema nested in it (not following `$ref`s).
+pub(crate) fn walk_schema<'a>(
+    schema: &'a Value,
+    pointer: String,
+    out: &mut Vec<(String, &'a Value)>,
+) {
+    let Some(obj) = schema.as_object() else {
+        return;
+    };
+    for key in SUBSCHEMA {
+        match obj.get(key) {
+            // 2.0 / draft-4 tuple form: `items: [..]`.
+            Some(Value::Array(_)) => {
+                for (child, value) in children(obj.get(key), &format!("{pointer}/{key}")) {
+                    walk_schema(value, child, out);
+                }
+            }
+            Some(value) => walk_schema(value, format!("{pointer}/{}", escape(key)), out),
+            None => {}
+        }
+    }
+    for key in SUBSCHEMA_LISTS.iter().chain(&SUBSCHEMA_MAPS) {
+        for (child, value) in children(obj.get(*key), &format!("{pointer}/{}", escape(key))) {
+            walk_schema(value, child, out);
+        }
+    }
+    out.push((pointer, schema));
+}
+
+/// Every `$ref` in the document with where it sits, and every object with keys
+/// next to its `$ref`. Discriminator mappings count as references too.
+fn collect_refs(
+    value: &Value,
+    pointer: String,
+    refs: &mut Vec<(String, String)>,
+    siblings: &mut Vec<(String, Vec<String>)>,
+) {
+    match value {
+        Value::Object(map) => {
+            if let Some(target) = map.get("$ref").and_then(Value::as_str) {
+                if let Some(local) = target.strip_prefix('#') {
+                    refs.push((local.to_string(), pointer.clone()));
+                }
+                let others: Vec<String> = map
+                    .keys()
+                    .filter(|k| *k != "$ref" && !k.starts_with("x-"))
+                    .cloned()
+                    .collect();
+                if !others.is_empty() {
+                    siblings.push((pointer.clone(), others));
+                }
+            }
+            if let Some(mapping) = map
+                .get("discriminator")
+                .and_then(|d| d.get("mapping"))
+                .and_then(Value::as_object)
+            {
+                for target in mapping.values().filter_map(Value::as_str) {
+                    let local = match target.strip_prefix('#') {
+                        Some(local) => local.to_string(),
+                        None => format!("/components/schemas/{}", escape(target)),
+                    };
+                    refs.push((local, pointer.clone()));
+                }
+            }
+            for (key, child) in map {
+                collect_refs(child, format!("{pointer}/{}", escape(key)), refs, siblings);
+            }
+        }
+        Value::Array(items) => {
+            for (i, child) in items.iter().enumerate() {
+                collect_refs(child, format!("{pointer}/{i}"), refs, siblings);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Maps pointers into the bundled document back to the file and line they
+/// were read from: schemas imported from other files live in those files.
+struct Locator<'a> {
+    input: &'a Input<'a>,
+    sources: &'a HashMap<String, String>,
+    swagger: bool,
+    files: HashMap<String, Option<String>>, // other files' text, read once
+}
+
+impl Locator<'_> {
+    fn locate(&mut self, pointer: &str) -> (String, String, Option<usize>) {
+        let container = if self.swagger {
+            "/definitions/"
+        } else {
+            "/components/schemas/"
+        };
+        let imported = pointer.strip_prefix(container).and_then(|rest| {
+            let (name, tail) = rest.split_once('/').map_or((rest, ""), |(n, t)| (n, t));
+            let name = name.replace("~1", "/").replace("~0", "~");
+            let source = self.sources.get(&name)?;
+            let (file, fragment) = source.split_once('#')?;
+            let tail = if tail.is_empty() {
+                String::new()
+            } else {
+                format!("/{tail}")
+            };
+            Some((file.to_string(), format!("{fragment}{tail}")))
+        });
+
+        let Some((file, pointer)) = imported else {
+            let line = locate::line_of(self.input.content, pointer);
+            return (self.input.label.to_string(), format!("#{pointer}"), line);
+        };
+        let path = match self.input.path.and_then(Path::parent) {
+            Some(dir) => dir.join(&file),
+            None => Path::new(&file).to_path_buf(),
+        };
+        let text = self
+            .files
+            .entry(file)
+            .or_insert_with(|| fs::read_to_string(&path).ok());
+        let line = text
+            .as_deref()
+            .and_then(|text| locate::line_of(text, &pointer));
+        (path.display().to_string(), format!("#{pointer}"), line)
+    }
+}
+
+// ── output ───────────────────────────────────────────────────────────────────
+
+/// `file:line: severity [rule] message`, then a count of each severity.
+pub fn write_text(findings: &[Finding], out: &mut impl Write, color: bool) -> io::Result<()> {
+    for f in findings {
+        let location = match f.line {
+            Some(line) => format!("{}:{line}", f.file),
+            None => f.file.clone(),
+        };
+        let severity = if color {
+            let code = match f.severity {
+                Severity::Error => "1;31",
+                Severity::Warning => "1;33",
+                Severity::Info => "1;36",
+            };
+            format!("\x1b[{code}m{}\x1b[0m", f.severity.label())
+        } else {
+            f.severity.label().to_string()
+        };
+        writeln!(out, "{location}: {severity} [{}] {}", f.rule, f.message)?;
+        writeln!(out, "    {}", f.pointer)?;
+    }
+    if findings.is_empty() {
+        return writeln!(out, "no problems");
+    }
+    let count = |severity: Severity, word: &str| {
+        let n = findings.iter().filter(|f| f.severity == severity).count();
+        if n == 1 || word == "info" {
+            format!("{n} {word}")
+        } else {
+            format!("{n} {word}s")
+        }
+    };
+    writeln!(
+        out,
+        "\n{} problem{} ({}, {}, {})",
+        findings.len(),
+        if findings.len() == 1 { "" } else { "s" },
+        count(Severity::Error, "error"),
+        count(Severity::Warning, "warning"),
+        count(Severity::Info, "info")
+    )
+}
+
+pub fn to_json(findings: &[Finding]) -> Value {
+    json!(findings)
+}
+
+/// SARIF 2.1.0, as read by GitHub code scanning and most CI dashboards.
+pub fn to_sarif(findings: &[Finding], rules: &[Rule]) -> Value {
+    let rules: Vec<Value> = rules
+        .iter()
+        .map(|rule| {
+            json!({
+                "id": rule.id,
+                "shortDescription": { "text": rule.summary },
+                "defaultConfiguration": { "level": rule.severity.sarif_level() },
+            })
+        })
+        .collect();
+    let results: Vec<Value> = findings
+        .iter()
+        .map(|f| {
+            let mut location = json!({
+                "physicalLocation": { "artifactLocation": { "uri": f.file } },
+                "logicalLocations": [{ "fullyQualifiedName": f.pointer }],
+            });
+            if let Some(line) = f.line {
+                location["physicalLocation"]["region"] = json!({ "startLine": line });
+            }
+            json!({
+                "ruleId": f.rule,
+                "level": f.severity.sarif_level(),
+                "message": { "text": f.message },
+                "locations": [location],
+            })
+        })
+        .collect();
+    json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": { "driver": {
+                "name": "speq",
+                "version": env!("CARGO_PKG_VERSION"),
+                "rules": rules,
+            }},
+            "results": results,
+        }],
+    })
+}
+
+// ── tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::{bundle, parse_bundle};
+
+    const PETSTORE: &str = include_str!("../fixtures/petstore.yaml");
+
+    const SLOPPY: &str = r##"openapi: 3.0.3
+info: { title: Sloppy, version: "1" }
+paths:
+  /orders:
+    get:
+      responses:
+        "200":
+          description: ok
+          content:
+            application/json:
+              schema:
+                $ref: "#/components/schemas/Order"
+                description: ignored
+components:
+  schemas:
+    Order:
+      description: An order
+      type: object
+      properties:
+        orderId: { type: integer }
+        createdAt: { type: string }
+        customer_name: { type: string }
+        status: { enum: [open, closed] }
+        lines: { type: array }
+    Orphan:
+      description: Nothing uses me
+      type: string
+"##;
+
+    fn run(content: &str) -> Vec<Finding> {
+        let bundle = bundle(content, None).unwrap();
+        let spec = parse_bundle(&bundle).unwrap();
+        let input = Input {
+            label: "openapi.yaml",
+            content,
+            path: None,
+        };
+        lint(&spec, &bundle, &input)
+    }
+
+    #[test]
+    fn reports_each_rule_at_its_line() {
+        let findings = run(SLOPPY);
+        let summary: Vec<(&str, Option<usize>)> =
+            findings.iter().map(|f| (f.rule, f.line)).collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("missing-description", Some(5)),
+                ("missing-operation-id", Some(5)),
+                ("ref-siblings", Some(11)),
+                ("integer-without-format", Some(20)),
+                ("property-casing", Some(22)),
+                ("enum-without-type", Some(23)),
+                ("array-without-items", Some(24)),
+                ("unused-component", Some(25)),
+            ]
+        );
+        assert_eq!(
+            findings[4].message,
+            "property customer_name is snake_case, but most properties are camelCase"
+        );
+        assert_eq!(
+            findings[6].pointer,
+            "#/components/schemas/Order/properties/lines"
+        );
+        assert_eq!(findings[6].severity, Severity::Error);
+    }
+
+    #[test]
+    fn sarif_carries_rules_levels_and_regions() {
+        let sarif = to_sarif(&run(SLOPPY), RULES);
+        let run = &sarif["runs"][0];
+        assert_eq!(
+            run["tool"]["driver"]["rules"].as_array().unwrap().len(),
+            RULES.len()
+        );
+        let result = &run["results"][0];
+        assert_eq!(result["level"], "warning");
+        assert_eq!(
+            result["locations"][0]["physicalLocation"]["region"]["startLine"],
+            5
+        );
+    }
+
+    #[test]
+    fn casing_styles() {
+        assert_eq!(Case::of("customerId"), Case::Camel);
+        assert_eq!(Case::of("customer_id"), Case::Snake);
+        assert_eq!(Case::of("_links"), Case::Lower);
+        assert_eq!(Case::of("CustomerId"), Case::Pascal);
+        assert_eq!(Case::of("ID"), Case::Screaming);
+        assert_eq!(Case::of("x.y"), Case::Other);
+        // Petstore only has one-word names: nothing to be inconsistent about.
+        assert!(run(PETSTORE).iter().all(|f| f.rule != "property-casing"));
+    }
+}
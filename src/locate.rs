@@ -0,0 +1,189 @@
+/// Find the line a JSON Pointer points at in a spec's source text, for
+/// `file:line` reports. serde_yaml keeps no positions, so this reads the text
+/// directly: block YAML by indentation, anything else (JSON, flow YAML) by
+/// looking for each key in turn.
+///
+/// Returns the 1-based line of the deepest part of the pointer that could be
+/// found, or `None` if not even its first token was.
+pub fn line_of(text: &str, pointer: &str) -> Option<usize> {
+    let tokens = crate::parser::pointer_tokens(pointer.trim_start_matches('#'));
+    if text.trim_start().starts_with(['{', '[']) {
+        return json_line(text, &tokens);
+    }
+    let lines: Vec<Line> = text.lines().map(Line::new).collect();
+    yaml_line(&lines, &tokens)
+}
+
+/// One line of block YAML: where its content starts, past any `- ` markers.
+struct Line<'a> {
+    indent: usize,  // column of the first non-space character
+    key_col: usize, // column of a mapping key on this line (after `- `)
+    dash: bool,     // starts a sequence item
+    key: Option<&'a str>,
+    content: bool, // not blank, not a comment, not a document marker
+}
+
+impl<'a> Line<'a> {
+    fn new(text: &'a str) -> Line<'a> {
+        let trimmed = text.trim_start_matches(' ');
+        let indent = text.len() - trimmed.len();
+        let content =
+            !(trimmed.is_empty() || trimmed.starts_with('#') || trimmed.starts_with("---"));
+        let mut rest = trimmed;
+        let mut key_col = indent;
+        let dash = rest == "-" || rest.starts_with("- ");
+        while rest.starts_with("- ") {
+            let after = rest[1..].trim_start_matches(' ');
+            key_col += rest.len() - after.len();
+            rest = after;
+        }
+        Line {
+            indent,
+            key_col,
+            dash,
+            key: mapping_key(rest),
+            content,
+        }
+    }
+}
+
+/// The key of a `key: value` line, unquoted.
+fn mapping_key(text: &str) -> Option<&str> {
+    for quote in ['"', '\''] {
+        if let Some(rest) = text.strip_prefix(quote) {
+            let end = rest.find(quote)?;
+            return rest[end + 1..]
+                .trim_start()
+                .starts_with(':')
+                .then(|| &rest[..end]);
+        }
+    }
+    let colon = text
+        .find(": ")
+        .or_else(|| text.strip_suffix(':').map(str::len))?;
+    Some(text[..colon].trim_end())
+}
+
+fn yaml_line(lines: &[Line], tokens: &[String]) -> Option<usize> {
+    let (mut start, mut end) = (0, lines.len());
+    let mut found = None;
+    for token in tokens {
+        // Children sit at the indent of the first content line in the range.
+        let Some(first) = (start..end).find(|&i| lines[i].content) else {
+            break;
+        };
+        let child = lines[first].key_col;
+
+        let key = (start..end).find(|&i| {
+            let line = &lines[i];
+            line.content && line.key_col == child && line.key == Some(token.as_str())
+        });
+        if let Some(i) = key {
+            // A key's value runs until the next line back at its indent (or
+            // out of it); a sequence may sit at the key's own indent.
+            found = Some(i);
+            start = i + 1;
+            end = (start..end)
+                .find(|&j| {
+                    let line = &lines[j];
+                    line.content && (line.indent < child || (line.indent == child && !line.dash))
+                })
+                .unwrap_or(end);
+            continue;
+        }
+
+        let dash_indent = lines[first].indent;
+        let Some(item) = token
+            .parse::<usize>()
+            .ok()
+            .filter(|_| lines[first].dash)
+            .and_then(|index| {
+                (start..end)
+                    .filter(|&i| {
+                        lines[i].content && lines[i].dash && lines[i].indent == dash_indent
+                    })
+                    .nth(index)
+            })
+        else {
+            break;
+        };
+        found = Some(item);
+        // The item's first key shares its `- ` line.
+        start = item;
+        end = (item + 1..end)
+            .find(|&j| lines[j].content && lines[j].indent <= dash_indent)
+            .unwrap_or(end);
+    }
+    found.map(|i| i + 1)
+}
+
+/// JSON (or any other layout): find each key after the previous one.
+fn json_line(text: &str, tokens: &[String]) -> Option<usize> {
+    let mut offset = 0;
+    let mut found = None;
+    for token in tokens {
+        let needle = format!("\"{}\"", token.replace('\\', "\\\\").replace('"', "\\\""));
+        let Some(at) = text[offset..].match_indices(&needle).find_map(|(at, _)| {
+            let after = &text[offset + at + needle.len()..];
+            after.trim_start().starts_with(':').then_some(offset + at)
+        }) else {
+            // Array indices and keys that are not there: keep the last hit.
+            continue;
+        };
+        offset = at + needle.len();
+        found = Some(at);
+    }
+    found.map(|at| text[..at].matches('\n').count() + 1)
+}
+
+// ── tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const YAML: &str = "\
+openapi: 3.0.3
+paths:
+  /pets:
+    get:
+      parameters:
+        - name: limit
+          in: query
+        - name: offset   # second
+          schema:
+            type: integer
+      responses:
+        '200':
+          description: ok
+components:
+  schemas:
+    Pet:
+      type: object
+";
+
+    #[test]
+    fn finds_keys_and_sequence_items_in_yaml() {
+        assert_eq!(line_of(YAML, "#/paths/~1pets/get"), Some(4));
+        assert_eq!(line_of(YAML, "#/paths/~1pets/get/parameters/1"), Some(8));
+        assert_eq!(
+            line_of(YAML, "#/paths/~1pets/get/parameters/1/schema/type"),
+            Some(10)
+        );
+        assert_eq!(line_of(YAML, "#/paths/~1pets/get/responses/200"), Some(12));
+        assert_eq!(line_of(YAML, "#/components/schemas/Pet"), Some(16));
+        // Not there: the deepest part that is.
+        assert_eq!(
+            line_of(YAML, "#/components/schemas/Pet/properties/id"),
+            Some(16)
+        );
+        assert_eq!(line_of(YAML, "#/nothing"), None);
+    }
+
+    #[test]
+    fn finds_keys_in_json() {
+        let json = "{\n  \"paths\": {\n    \"/pets\": {\n      \"get\": {}\n    }\n  },\n  \"components\": {\"schemas\": {\"Pet\": {}}}\n}";
+        assert_eq!(line_of(json, "#/paths/~1pets/get"), Some(4));
+        assert_eq!(line_of(json, "#/components/schemas/Pet"), Some(7));
+    }
+}
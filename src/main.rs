@@ -1,45 +1,640 @@
 mod app;
+mod breadcrumb;
+mod catalog;
+mod clipboard;
+mod config;
+mod db;
+mod diagnostics;
+mod diff;
+mod example;
+mod fts;
+mod git;
+mod graph;
+mod indexer;
+mod keymap;
+mod lint;
+mod locate;
+mod mcp;
+mod mock;
+mod naming;
 mod parser;
+mod search;
+mod source;
 mod spec;
 mod tree;
 mod ui;
+mod usage;
+mod validate;
 
-use std::{env, fs, io};
+use std::io::{self, IsTerminal, Write};
+use std::path::PathBuf;
 
-use anyhow::{Context, Result};
+use anyhow::{Context, Result, bail};
+use clap::{Parser, Subcommand};
 use crossterm::{
-    event::{self, Event, KeyCode, KeyModifiers},
+    event::{
+        self, Event, KeyCode, KeyEventKind, KeyModifiers, KeyboardEnhancementFlags,
+        PopKeyboardEnhancementFlags, PushKeyboardEnhancementFlags,
+    },
     execute,
-    terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
+    terminal::{
+        EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode,
+        supports_keyboard_enhancement,
+    },
 };
 use ratatui::{Terminal, backend::CrosstermBackend};
 
 use app::App;
+use keymap::{Key, Mode};
+use source::Source;
+
+/// speq — OpenAPI specification browser.
+#[derive(Parser)]
+#[command(name = "speq", version, about, args_conflicts_with_subcommands = true)]
+struct Cli {
+    /// Spec to browse (YAML or JSON): a file, an http(s):// URL, or `-` for stdin.
+    /// Without one, read a piped stdin or browse every service in the index
+    file: Option<String>,
+
+    /// Browse two versions of a spec as one tree, with changes marked
+    #[arg(long, num_args = 2, value_names = ["OLD", "NEW"], conflicts_with = "file")]
+    diff: Option<Vec<String>>,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Index the specs cached under ~/.cache/speq/specs into ~/.cache/speq/index.db
+    Index {
+        /// Re-index every service, even if its openapi.yaml is unchanged
+        #[arg(long)]
+        force: bool,
+        /// Only index this service
+        service: Option<String>,
+    },
+    /// Full-text search over every indexed service (schema and property names, descriptions)
+    Search {
+        /// Words to look for; each must match, as a prefix
+        #[arg(required = true)]
+        query: Vec<String>,
+        /// Maximum number of hits
+        #[arg(long, default_value_t = 20)]
+        limit: usize,
+    },
+    /// Check a spec against the built-in lint rules.
+    /// Exits 1 if a finding reaches --fail-on, 2 if the spec cannot be read
+    Lint {
+        /// Spec to check: a file, an http(s):// URL, or `-` for stdin
+        spec: String,
+        /// Output format
+        #[arg(long, value_enum, default_value_t = lint::Format::Text)]
+        format: lint::Format,
+        /// Lowest severity that makes the exit status non-zero
+        #[arg(long, value_enum, default_value_t = lint::Severity::Error)]
+        fail_on: lint::Severity,
+    },
+    /// Check schema and property names against the conventions in .speq.toml.
+    /// Exits 1 on any violation, 2 if the spec or the config cannot be read
+    CheckNames {
+        /// Spec to check: a file, an http(s):// URL, or `-` for stdin
+        spec: String,
+        /// Config to use instead of the nearest .speq.toml
+        #[arg(long)]
+        config: Option<PathBuf>,
+        /// Output format
+        #[arg(long, value_enum, default_value_t = lint::Format::Text)]
+        format: lint::Format,
+    },
+    /// Compare two versions of a spec and classify each change for clients.
+    /// Exits 1 if any change is breaking, 2 if a spec cannot be read
+    Diff {
+        /// The old and the new spec (files, http(s):// URLs, or `-` for stdin);
+        /// with --git, the spec's path in the repository
+        #[arg(required = true, num_args = 1..=2, value_name = "SPEC")]
+        specs: Vec<String>,
+        /// Compare with the spec at a revision: REV (against the work tree),
+        /// REV..REV, or REV...REV (from where the second forked off the first)
+        #[arg(long, value_name = "REV[..REV]")]
+        git: Option<String>,
+        /// Output format
+        #[arg(long, value_enum, default_value_t = diff::Format::Text)]
+        format: diff::Format,
+    },
+    /// Print an example JSON payload for a component schema, built from its
+    /// examples, types, formats and constraints
+    Example {
+        /// Spec to read: a file, an http(s):// URL, or `-` for stdin
+        spec: String,
+        /// Name of the component schema
+        schema: String,
+    },
+    /// Check a JSON payload against a component schema.
+    /// Exits 1 if it does not conform, 2 if the spec or the payload cannot be read
+    Validate {
+        /// Spec to read: a file, an http(s):// URL, or `-` for stdin
+        spec: String,
+        /// Name of the component schema
+        schema: String,
+        /// JSON payload to check: a file, or `-` for stdin
+        payload: String,
+        /// Output format
+        #[arg(long, value_enum, default_value_t = validate::Format::Text)]
+        format: validate::Format,
+    },
+    /// Print which component schemas reference which, as Graphviz DOT or Mermaid
+    Graph {
+        /// Spec to read: a file, an http(s):// URL, or `-` for stdin
+        spec: String,
+        /// Output format
+        #[arg(long, value_enum, default_value_t = graph::Format::Dot)]
+        format: graph::Format,
+        /// Only this schema and the schemas around it
+        #[arg(long)]
+        focus: Option<String>,
+        /// With --focus, how many references away to go, either way (default: no limit)
+        #[arg(long, requires = "focus")]
+        depth: Option<usize>,
+        /// Draw the references that form cycles in red
+        #[arg(long)]
+        cycles: bool,
+    },
+    /// Answer Model Context Protocol requests on stdin / stdout, with tools for
+    /// coding agents to look up schemas, properties and operations
+    Mcp {
+        /// Spec to serve: a file or an http(s):// URL.
+        /// Without one, every service in the index
+        spec: Option<String>,
+    },
+    /// Serve every operation in the spec's paths on localhost, answering with
+    /// documented or generated examples and logging requests that stray from it.
+    /// Paths are served both under the servers' base path (`/v1/pets`) and without it.
+    /// Send `Prefer: code=404` to get a response other than the first success
+    Mock {
+        /// Spec to serve: a file, an http(s):// URL, or `-` for stdin
+        spec: String,
+        /// Port to listen on
+        #[arg(long, default_value_t = 4010)]
+        port: u16,
+    },
+}
 
 fn main() -> Result<()> {
-    // Determine the spec path: first CLI arg, or the built-in fixture for dev.
-    let spec_path = env::args()
-        .nth(1)
-        .unwrap_or_else(|| "fixtures/petstore.yaml".to_string());
+    let cli = Cli::parse();
+    match cli.command {
+        Some(Command::Index { force, service }) => index(force, service),
+        Some(Command::Search { query, limit }) => search(&query.join(" "), limit),
+        Some(Command::Lint {
+            spec,
+            format,
+            fail_on,
+        }) => lint(&spec, format, fail_on),
+        Some(Command::CheckNames {
+            spec,
+            config,
+            format,
+        }) => check_names(&spec, config, format),
+        Some(Command::Diff { specs, git, format }) => diff(&specs, git.as_deref(), format),
+        Some(Command::Example { spec, schema }) => example(&spec, &schema),
+        Some(Command::Validate {
+            spec,
+            schema,
+            payload,
+            format,
+        }) => validate(&spec, &schema, &payload, format),
+        Some(Command::Graph {
+            spec,
+            format,
+            focus,
+            depth,
+            cycles,
+        }) => graph(&spec, format, focus.as_deref(), depth, cycles),
+        Some(Command::Mcp { spec }) => mcp(spec.as_deref()),
+        Some(Command::Mock { spec, port }) => mock(&spec, port),
+        None => match cli.diff {
+            Some(specs) => browse_diff(&specs[0], &specs[1]),
+            None => browse(cli.file),
+        },
+    }
+}
+
+fn index(force: bool, service: Option<String>) -> Result<()> {
+    let cache = config::cache_dir().context("cannot locate ~/.cache/speq (HOME is not set)")?;
+    let db_path = db::index_path().context("cannot locate ~/.cache/speq/index.db")?;
+    let mut conn = db::open(&db_path)?;
+    let options = indexer::IndexOptions { force, service };
+    let summary = indexer::index_all(&mut conn, &cache.join("specs"), &options, &mut io::stdout())?;
+    if summary.errors > 0 {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+fn search(query: &str, limit: usize) -> Result<()> {
+    let db_path = db::index_path().context("cannot locate ~/.cache/speq/index.db")?;
+    if !db_path.exists() {
+        bail!("no index at {} — run `speq index` first", db_path.display());
+    }
+    let conn = db::open(&db_path)?;
+    let hits = fts::search(&conn, query, limit)?;
+
+    // Matches in bold yellow on a terminal, plain text when piped.
+    let color = io::stdout().is_terminal();
+    let render = |text: &str| -> String {
+        fts::segments(text)
+            .into_iter()
+            .map(|(run, is_match)| {
+                if is_match && color {
+                    format!("\x1b[1;33m{run}\x1b[0m")
+                } else {
+                    run
+                }
+            })
+            .collect()
+    };
+    let mut out = io::stdout().lock();
+    for hit in &hits {
+        writeln!(out, "{}", render(&hit.label()))?;
+        if !hit.snippet.is_empty() {
+            writeln!(out, "    {}", render(&hit.snippet))?;
+        }
+    }
+    if hits.is_empty() {
+        eprintln!("no matches for {query:?}");
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+fn lint(arg: &str, format: lint::Format, fail_on: lint::Severity) -> Result<()> {
+    let source = Source::parse(arg);
+    let checked = source.read().and_then(|fetched| {
+        let bundle = parser::bundle(&fetched.content, source.file())?;
+        let spec = parser::parse_bundle(&bundle)?;
+        let input = lint::Input {
+            label: &source.label(),
+            content: &fetched.content,
+            path: source.file(),
+        };
+        Ok(lint::lint(&spec, &bundle, &input))
+    });
+    // A spec that cannot be checked is not the same as one that fails.
+    let findings = match checked {
+        Ok(findings) => findings,
+        Err(e) => {
+            eprintln!("Error: failed to lint {}: {e:#}", source.label());
+            std::process::exit(2);
+        }
+    };
+
+    let mut out = io::stdout().lock();
+    match format {
+        lint::Format::Text => lint::write_text(&findings, &mut out, io::stdout().is_terminal())?,
+        lint::Format::Json => writeln!(out, "{:#}", lint::to_json(&findings))?,
+        lint::Format::Sarif => writeln!(out, "{:#}", lint::to_sarif(&findings, lint::RULES))?,
+    }
+    out.flush()?;
+    if findings.iter().any(|f| f.severity >= fail_on) {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+fn check_names(arg: &str, config: Option<PathBuf>, format: lint::Format) -> Result<()> {
+    let source = Source::parse(arg);
+    let config = config.map(Ok).unwrap_or_else(|| {
+        let dir = spec_dir(&source)?;
+        naming::find_config(&dir).with_context(|| {
+            format!(
+                "no {} found at or above {}",
+                naming::CONFIG_FILE,
+                dir.display()
+            )
+        })
+    });
+    let checked = config
+        .and_then(|path| naming::load(&path))
+        .and_then(|config| {
+            let fetched = source.read()?;
+            let bundle = parser::bundle(&fetched.content, source.file())?;
+            let spec = parser::parse_bundle(&bundle)?;
+            let found = naming::check(
+                &bundle.document,
+                spec.version == spec::SpecVersion::V20,
+                &config.naming,
+            );
+            let input = lint::Input {
+                label: &source.label(),
+                content: &fetched.content,
+                path: source.file(),
+            };
+            Ok(lint::locate(found, &spec, &bundle, &input))
+        });
+    let findings = match checked {
+        Ok(findings) => findings,
+        Err(e) => {
+            eprintln!("Error: failed to check names in {}: {e:#}", source.label());
+            std::process::exit(2);
+        }
+    };
+
+    let mut out = io::stdout().lock();
+    match format {
+        lint::Format::Text => lint::write_text(&findings, &mut out, io::stdout().is_terminal())?,
+        lint::Format::Json => writeln!(out, "{:#}", lint::to_json(&findings))?,
+        lint::Format::Sarif => writeln!(out, "{:#}", lint::to_sarif(&findings, naming::RULES))?,
+    }
+    out.flush()?;
+    if !findings.is_empty() {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+fn diff(specs: &[String], git: Option<&str>, format: diff::Format) -> Result<()> {
+    let load = |arg: &str| {
+        let source = Source::parse(arg);
+        source
+            .read()
+            .and_then(|fetched| parser::parse_spec_at(&fetched.content, source.file()))
+            .with_context(|| format!("failed to read {}", source.label()))
+    };
+    let loaded = match (specs, git) {
+        ([path], Some(range)) => diff_git(path, range, load),
+        ([old, new], None) => load(old).and_then(|old| Ok((old, load(new)?))),
+        (_, Some(_)) => Err(anyhow::anyhow!("--git takes one spec path")),
+        (_, None) => Err(anyhow::anyhow!("diff needs two specs, or one with --git")),
+    };
+    let changes = match loaded {
+        Ok((old, new)) => diff::diff(&old, &new),
+        Err(e) => {
+            eprintln!("Error: {e:#}");
+            std::process::exit(2);
+        }
+    };
 
-    let content = fs::read_to_string(&spec_path)
-        .with_context(|| format!("cannot read spec file: {spec_path}"))?;
+    let mut out = io::stdout().lock();
+    match format {
+        diff::Format::Text => diff::write_text(&changes, &mut out, io::stdout().is_terminal())?,
+        diff::Format::Json => writeln!(out, "{:#}", diff::to_json(&changes))?,
+        diff::Format::Markdown => write!(out, "{}", diff::to_markdown(&changes))?,
+    }
+    out.flush()?;
+    if changes.iter().any(|c| c.class == diff::Class::Breaking) {
+        std::process::exit(1);
+    }
+    Ok(())
+}
 
-    let spec = parser::parse_spec(&content)
-        .with_context(|| format!("failed to parse spec: {spec_path}"))?;
+fn example(arg: &str, name: &str) -> Result<()> {
+    let source = Source::parse(arg);
+    let fetched = source.read()?;
+    let spec = parser::parse_spec_at(&fetched.content, source.file())
+        .with_context(|| format!("failed to parse spec: {}", source.label()))?;
+    let schema = spec
+        .schema_nodes
+        .iter()
+        .find(|n| n.name == name)
+        .with_context(|| format!("no schema named {name} in {}", source.label()))?;
+    let value = example::generate(schema, &spec.schema_nodes).unwrap_or_default();
+    println!("{value:#}");
+    Ok(())
+}
+
+fn validate(arg: &str, name: &str, payload: &str, format: validate::Format) -> Result<()> {
+    let violations = match check_payload(arg, name, payload) {
+        Ok(violations) => violations,
+        Err(e) => {
+            eprintln!("Error: {e:#}");
+            std::process::exit(2);
+        }
+    };
+
+    let mut out = io::stdout().lock();
+    match format {
+        validate::Format::Text => {
+            validate::write_text(&violations, &mut out, io::stdout().is_terminal())?
+        }
+        validate::Format::Json => writeln!(out, "{:#}", validate::to_json(&violations))?,
+    }
+    out.flush()?;
+    if !violations.is_empty() {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+/// The payload at `payload` checked against schema `name` of the spec at `arg`.
+fn check_payload(arg: &str, name: &str, payload: &str) -> Result<Vec<validate::Violation>> {
+    if arg == "-" && payload == "-" {
+        bail!("the spec and the payload cannot both come from stdin");
+    }
+    let source = Source::parse(arg);
+    let fetched = source.read()?;
+    let spec = parser::parse_spec_at(&fetched.content, source.file())
+        .with_context(|| format!("failed to parse spec: {}", source.label()))?;
+    let index = spec
+        .schema_nodes
+        .iter()
+        .position(|n| n.name == name)
+        .with_context(|| format!("no schema named {name} in {}", source.label()))?;
+    let (label, text) = match payload {
+        "-" => ("stdin", io::read_to_string(io::stdin())?),
+        path => (
+            path,
+            std::fs::read_to_string(path).with_context(|| format!("cannot read {path}"))?,
+        ),
+    };
+    let value: serde_json::Value =
+        serde_json::from_str(&text).with_context(|| format!("{label} is not valid JSON"))?;
+    let root = &spec.schema_nodes[index];
+    let pointer =
+        breadcrumb::json_pointer(&spec, app::View::Schemas, &[root], &[index]).unwrap_or_default();
+    Ok(validate::validate(&value, root, &spec, &pointer))
+}
+
+fn graph(
+    arg: &str,
+    format: graph::Format,
+    focus: Option<&str>,
+    depth: Option<usize>,
+    cycles: bool,
+) -> Result<()> {
+    let source = Source::parse(arg);
+    let fetched = source.read()?;
+    let spec = parser::parse_spec_at(&fetched.content, source.file())
+        .with_context(|| format!("failed to parse spec: {}", source.label()))?;
+    let focus = match focus {
+        Some(name) => Some(
+            spec.schema_nodes
+                .iter()
+                .position(|n| n.name == name)
+                .with_context(|| format!("no schema named {name} in {}", source.label()))?,
+        ),
+        None => None,
+    };
+    print!("{}", graph::render(&spec, format, focus, depth, cycles));
+    Ok(())
+}
+
+fn mcp(arg: Option<&str>) -> Result<()> {
+    let backend = match arg {
+        Some("-") => bail!("the spec cannot come from stdin: MCP requests arrive there"),
+        Some(arg) => {
+            let source = Source::parse(arg);
+            let fetched = source.read()?;
+            let spec = parser::parse_spec_at(&fetched.content, source.file())
+                .with_context(|| format!("failed to parse spec: {}", source.label()))?;
+            mcp::Backend::Spec(spec)
+        }
+        None => {
+            let db_path = db::index_path().context("cannot locate ~/.cache/speq/index.db")?;
+            if !db_path.exists() {
+                bail!(
+                    "no index at {} — run `speq index` or pass a spec file",
+                    db_path.display()
+                );
+            }
+            mcp::Backend::index(catalog::Catalog::open(&db_path)?)
+        }
+    };
+    mcp::serve(backend, io::stdin().lock(), io::stdout().lock())
+}
+
+fn mock(arg: &str, port: u16) -> Result<()> {
+    let source = Source::parse(arg);
+    let fetched = source.read()?;
+    let bundle = parser::bundle(&fetched.content, source.file())
+        .and_then(|bundle| Ok((parser::parse_bundle(&bundle)?, bundle)));
+    let (spec, bundle) =
+        bundle.with_context(|| format!("failed to parse spec: {}", source.label()))?;
+    mock::serve(spec, &bundle.document, port)
+}
+
+/// `speq diff --git RANGE path`: the spec at the range's revisions, the new
+/// side from the work tree when the range has just one.
+fn diff_git(
+    path: &str,
+    range: &str,
+    load: impl Fn(&str) -> Result<spec::LoadedSpec>,
+) -> Result<(spec::LoadedSpec, spec::LoadedSpec)> {
+    let cwd = std::env::current_dir()?;
+    let cwd = cwd.canonicalize().unwrap_or(cwd);
+    let repo = git::repo_root(&cwd)?;
+    let spec_path = git::normalize(&cwd.join(path));
+    let (old, new) = git::range(&repo, range)?;
+    let old_spec = old.parse_spec(&spec_path)?;
+    let new_spec = match new {
+        Some(new) => new.parse_spec(&spec_path)?,
+        None => load(path)?,
+    };
+    Ok((old_spec, new_spec))
+}
+
+/// Where to start looking for `.speq.toml`: the spec file's directory, or
+/// the current one for stdin and URLs.
+fn spec_dir(source: &Source) -> Result<PathBuf> {
+    let dir = match source.file().and_then(|file| file.parent()) {
+        Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(),
+        _ => std::env::current_dir()?,
+    };
+    Ok(dir.canonicalize().unwrap_or(dir))
+}
+
+fn browse(file: Option<String>) -> Result<()> {
+    let source = match file {
+        Some(arg) => Some(Source::parse(&arg)),
+        // `curl ... | speq` works like `speq -`.
+        None if !io::stdin().is_terminal() => Some(Source::Stdin),
+        None => None,
+    };
+    let app = match source {
+        Some(source) => {
+            let fetched = source.read()?;
+            let bundle = parser::bundle(&fetched.content, source.file())
+                .and_then(|bundle| Ok((parser::parse_bundle(&bundle)?, bundle)));
+            let (mut spec, bundle) =
+                bundle.with_context(|| format!("failed to parse spec: {}", source.label()))?;
+            // Naming conventions are marked like diagnostics when the spec's
+            // repository has a `.speq.toml`; a broken one is reported, not fatal.
+            let mut note = fetched.note;
+            let config = spec_dir(&source)
+                .ok()
+                .and_then(|dir| naming::find_config(&dir));
+            match config.map(|path| naming::load(&path)) {
+                Some(Ok(config)) => {
+                    let swagger = spec.version == spec::SpecVersion::V20;
+                    let found: Vec<(String, String)> =
+                        naming::check(&bundle.document, swagger, &config.naming)
+                            .into_iter()
+                            .map(|(_, pointer, message)| (pointer, message))
+                            .collect();
+                    diagnostics::mark(&mut spec, diagnostics::DiagnosticKind::Naming, &found);
+                }
+                Some(Err(e)) => note = Some(format!("{e:#}")),
+                None => {}
+            }
+            let mut app = App::new(spec);
+            app.status_message = note;
+            app
+        }
+        // Nothing given: every service in the index.
+        None => {
+            let db_path = db::index_path().context("cannot locate ~/.cache/speq/index.db")?;
+            if !db_path.exists() {
+                bail!(
+                    "no index at {} — run `speq index` or pass a spec file",
+                    db_path.display()
+                );
+            }
+            App::with_catalog(catalog::Catalog::open(&db_path)?)
+        }
+    };
+
+    run_app(app)
+}
+
+/// `speq --diff OLD NEW`: the union of both specs, changes marked.
+fn browse_diff(old: &str, new: &str) -> Result<()> {
+    let load = |arg: &str| {
+        let source = Source::parse(arg);
+        let fetched = source.read()?;
+        parser::parse_spec_at(&fetched.content, source.file())
+            .with_context(|| format!("failed to parse spec: {}", source.label()))
+    };
+    run_app(App::with_diff(load(old)?, load(new)?))
+}
+
+/// Take over the terminal and run `app` until it quits.
+fn run_app(mut app: App) -> Result<()> {
+    // Key bindings: defaults plus ~/.config/speq/config.lua, read before the
+    // alternate screen so any warnings stay visible.
+    let keymap = config::load();
 
     // Set up the terminal
     enable_raw_mode()?;
     let mut stdout = io::stdout();
     execute!(stdout, EnterAlternateScreen)?;
+    // Ask for disambiguated key codes so Ctrl-i can be told apart from Tab.
+    let enhanced_keys = supports_keyboard_enhancement().unwrap_or(false);
+    if enhanced_keys {
+        execute!(
+            stdout,
+            PushKeyboardEnhancementFlags(KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES)
+        )?;
+    }
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
-    let mut app = App::new(spec);
+    app.keymap = keymap;
 
     // Run the event loop; restore terminal afterwards even on error
     let result = run(&mut terminal, &mut app);
 
+    if enhanced_keys {
+        execute!(terminal.backend_mut(), PopKeyboardEnhancementFlags)?;
+    }
     disable_raw_mode()?;
     execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
     terminal.show_cursor()?;
@@ -51,10 +646,11 @@ fn run(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>, app: &mut App) ->
     loop {
         terminal.draw(|frame| ui::draw(frame, app))?;
 
-        if event::poll(std::time::Duration::from_millis(16))? {
-            if let Event::Key(key) = event::read()? {
-                handle_key(app, key.code, key.modifiers);
-            }
+        if event::poll(std::time::Duration::from_millis(16))?
+            && let Event::Key(key) = event::read()?
+            && key.kind != KeyEventKind::Release
+        {
+            handle_key(app, key.code, key.modifiers);
         }
 
         if app.should_quit {
@@ -64,69 +660,65 @@ fn run(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>, app: &mut App) ->
 }
 
 fn handle_key(app: &mut App, code: KeyCode, modifiers: KeyModifiers) {
-    // Handle pending two-key sequences first.
-    if let Some(pending) = app.pending_key.take() {
-        match (pending, code, modifiers) {
-            // gg → goto top
-            ('g', KeyCode::Char('g'), KeyModifiers::NONE) => {
-                app.goto_top();
-                return;
-            }
-            // zo → expand node
-            ('z', KeyCode::Char('o'), KeyModifiers::NONE) => {
-                app.expand_node();
-                return;
-            }
-            // zc → collapse node
-            ('z', KeyCode::Char('c'), KeyModifiers::NONE) => {
-                app.collapse_node();
-                return;
-            }
-            // zR → expand all
-            ('z', KeyCode::Char('R'), KeyModifiers::SHIFT) => {
-                app.expand_all();
-                return;
-            }
-            // zM → collapse all
-            ('z', KeyCode::Char('M'), KeyModifiers::SHIFT) => {
-                app.collapse_all();
-                return;
+    app.status_message = None;
+
+    // The list popups take every key while one is open, through the popup
+    // keymap. In the cross-service search plain characters edit the query.
+    if app.global.is_some()
+        || app.diagnostics_panel.is_some()
+        || app.usages.is_some()
+        || app.validation.is_some()
+    {
+        let typing = !modifiers.contains(KeyModifiers::CONTROL);
+        if app.global.is_some() {
+            match code {
+                KeyCode::Backspace => return app.global_pop(),
+                KeyCode::Char(c) if typing => return app.global_push(c),
+                _ => {}
             }
-            // Unrecognised second key — fall through to normal handling below.
-            _ => {}
         }
+        let key = Key::new(code, modifiers);
+        for action in app.keymap.feed(Mode::Popup, &mut app.pending_keys, key) {
+            app.perform_popup(action);
+        }
+        return;
     }
 
-    match (code, modifiers) {
-        // Quit
-        (KeyCode::Char('q'), KeyModifiers::NONE) => app.should_quit = true,
-        (KeyCode::Char('c'), KeyModifiers::CONTROL) => app.should_quit = true,
-
-        // Navigation
-        (KeyCode::Char('j'), KeyModifiers::NONE) | (KeyCode::Down, KeyModifiers::NONE) => {
-            app.move_down()
+    // The `/` prompt takes every key until it is confirmed or cancelled.
+    if app.search.as_ref().is_some_and(|s| s.typing) {
+        match code {
+            KeyCode::Esc => app.cancel_search(),
+            KeyCode::Enter => app.confirm_search(),
+            KeyCode::Backspace => app.search_pop(),
+            KeyCode::Char(c) if !modifiers.contains(KeyModifiers::CONTROL) => app.search_push(c),
+            _ => {}
         }
-        (KeyCode::Char('k'), KeyModifiers::NONE) | (KeyCode::Up, KeyModifiers::NONE) => {
-            app.move_up()
+        return;
+    }
+    if app.validate_prompt.is_some() {
+        match code {
+            KeyCode::Esc => app.validate_prompt = None,
+            KeyCode::Enter => app.confirm_validate(),
+            KeyCode::Backspace => app.validate_pop(),
+            KeyCode::Char(c) if !modifiers.contains(KeyModifiers::CONTROL) => app.validate_push(c),
+            _ => {}
         }
-        // First key of 'gg' sequence
-        (KeyCode::Char('g'), KeyModifiers::NONE) => app.pending_key = Some('g'),
-        (KeyCode::Char('G'), KeyModifiers::SHIFT) => app.goto_bottom(),
-
-        // Expand / collapse
-        (KeyCode::Char('l'), KeyModifiers::NONE) => app.toggle_expand(),
-        (KeyCode::Char('h'), KeyModifiers::NONE) => app.collapse_node(),
-
-        // Two-key 'z' sequences
-        (KeyCode::Char('z'), KeyModifiers::NONE) => app.pending_key = Some('z'),
-
-        // Pane switching
-        (KeyCode::Tab, KeyModifiers::NONE) => app.toggle_pane(),
-
-        // Detail pane scrolling
-        (KeyCode::Char('d'), KeyModifiers::CONTROL) => app.scroll_detail_down(),
-        (KeyCode::Char('u'), KeyModifiers::CONTROL) => app.scroll_detail_up(),
+        return;
+    }
+    if app.filter_typing {
+        match code {
+            KeyCode::Esc => app.clear_filter(),
+            KeyCode::Enter => app.confirm_filter(),
+            KeyCode::Backspace => app.filter_pop(),
+            KeyCode::Char(c) if !modifiers.contains(KeyModifiers::CONTROL) => app.filter_push(c),
+            _ => {}
+        }
+        return;
+    }
 
-        _ => {}
+    // Normal mode: resolve the key through the (possibly remapped) keymap.
+    let key = Key::new(code, modifiers);
+    for action in app.keymap.feed(Mode::Normal, &mut app.pending_keys, key) {
+        app.perform(action);
     }
 }
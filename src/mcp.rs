@@ -0,0 +1,980 @@
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+use std::io::{BufRead, Write};
+
+use anyhow::{Context, Result, bail};
+use serde_json::{Map, Value, json};
+
+use crate::app::View;
+use crate::breadcrumb;
+use crate::catalog::Catalog;
+use crate::example::{KEYWORD_CHILDREN, literal};
+use crate::fts;
+use crate::spec::{LoadedSpec, Operation};
+use crate::tree::{NodeKind, TreeNode};
+
+/// Protocol revision answered when the client does not ask for one.
+const PROTOCOL_VERSION: &str = "2025-06-18";
+
+/// Hits returned by `find_property` and `search`.
+const MAX_HITS: usize = 50;
+
+// JSON-RPC error codes.
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+/// What the tools read: one parsed spec, or every service in the index,
+/// each parsed the first time a tool asks for it.
+pub enum Backend {
+    Spec(LoadedSpec),
+    Index {
+        catalog: Catalog,
+        loaded: HashMap<usize, LoadedSpec>, // by index into `Catalog::services`
+    },
+}
+
+impl Backend {
+    pub fn index(catalog: Catalog) -> Backend {
+        Backend::Index {
+            catalog,
+            loaded: HashMap::new(),
+        }
+    }
+}
+
+struct Tool {
+    name: &'static str,
+    description: &'static str,
+    args: &'static [(&'static str, &'static str)], // all strings, all required
+    optional: &'static [(&'static str, &'static str)],
+}
+
+const TOOLS: &[Tool] = &[
+    Tool {
+        name: "list_services",
+        description: "List the services in the index, with their team, domain and spec title.",
+        args: &[],
+        optional: &[],
+    },
+    Tool {
+        name: "list_schemas",
+        description: "List the component schemas: name, type and the first line of the description.",
+        args: &[],
+        optional: &[],
+    },
+    Tool {
+        name: "get_schema",
+        description: "A component schema as JSON Schema, with every $ref replaced by the schema it names \
+                      (a $ref back into a schema already being shown is kept as is).",
+        args: &[("name", "Name of the component schema")],
+        optional: &[],
+    },
+    Tool {
+        name: "find_property",
+        description: "Find schema properties whose name contains the given text (any case): \
+                      where each is, its type, whether it is required, and its description.",
+        args: &[("name", "Property name, or part of one")],
+        optional: &[],
+    },
+    Tool {
+        name: "list_operations",
+        description: "List the operations in paths: method, path, operationId, summary and tags.",
+        args: &[],
+        optional: &[("tag", "Only operations with this tag")],
+    },
+    Tool {
+        name: "get_operation",
+        description: "One operation with its parameters, request body and responses, schemas resolved.",
+        args: &[(
+            "operation",
+            "operationId, or method and path (`GET /pets/{petId}`)",
+        )],
+        optional: &[],
+    },
+    Tool {
+        name: "search",
+        description: "Search schema and property names and descriptions (and, within one spec, \
+                      operations) for every word of the query.",
+        args: &[("query", "Words to look for")],
+        optional: &[],
+    },
+];
+
+/// Answer MCP requests, one JSON-RPC message per line on `input`, until it ends.
+pub fn serve(backend: Backend, input: impl BufRead, mut output: impl Write) -> Result<()> {
+    let mut server = Server { backend };
+    for line in input.lines() {
+        let line = line.context("cannot read from stdin")?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let reply = match serde_json::from_str::<Value>(&line) {
+            Ok(message) => server.handle(&message),
+            Err(e) => Some(failure(
+                Value::Null,
+                PARSE_ERROR,
+                format!("invalid JSON: {e}"),
+            )),
+        };
+        if let Some(reply) = reply {
+            writeln!(output, "{reply}")?;
+            output.flush()?;
+        }
+    }
+    Ok(())
+}
+
+struct Server {
+    backend: Backend,
+}
+
+impl Server {
+    /// The response to one message; `None` for notifications.
+    fn handle(&mut self, message: &Value) -> Option<Value> {
+        let id = message.get("id").cloned()?;
+        let Some(method) = message.get("method").and_then(Value::as_str) else {
+            // A response: this server never sends requests.
+            if message.get("result").is_some() || message.get("error").is_some() {
+                return None;
+            }
+            return Some(failure(id, INVALID_REQUEST, "missing method".to_string()));
+        };
+        let params = message.get("params").cloned().unwrap_or_else(|| json!({}));
+        let result = match method {
+            "initialize" => Ok(json!({
+                "protocolVersion": params.get("protocolVersion").and_then(Value::as_str).unwrap_or(PROTOCOL_VERSION),
+                "capabilities": { "tools": {} },
+                "serverInfo": { "name": "speq", "version": env!("CARGO_PKG_VERSION") },
+            })),
+            "ping" => Ok(json!({})),
+            "tools/list" => Ok(json!({ "tools": self.tools() })),
+            "tools/call" => self.call(&params),
+            _ => Err((METHOD_NOT_FOUND, format!("unknown method {method}"))),
+        };
+        Some(match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err((code, message)) => failure(id, code, message),
+        })
+    }
+
+    fn indexed(&self) -> bool {
+        matches!(self.backend, Backend::Index { .. })
+    }
+
+    /// `tools/list`: in multi-service mode every tool but `list_services`
+    /// takes the service to look in, optional for the ones that can look in all.
+    fn tools(&self) -> Vec<Value> {
+        TOOLS
+            .iter()
+            .filter(|tool| self.indexed() || tool.name != "list_services")
+            .map(|tool| {
+                let mut properties = Map::new();
+                let mut required: Vec<&str> = tool.args.iter().map(|(name, _)| *name).collect();
+                if self.indexed() && tool.name != "list_services" {
+                    let across = matches!(tool.name, "find_property" | "search");
+                    let description = if across {
+                        "Service to look in, as named by list_services; all of them when left out"
+                    } else {
+                        "Service to look in, as named by list_services"
+                    };
+                    properties.insert("service".to_string(), json!({ "type": "string", "description": description }));
+                    if !across {
+                        required.push("service");
+                    }
+                }
+                for (name, description) in tool.args.iter().chain(tool.optional) {
+                    properties.insert(name.to_string(), json!({ "type": "string", "description": description }));
+                }
+                json!({
+                    "name": tool.name,
+                    "description": tool.description,
+                    "inputSchema": { "type": "object", "properties": properties, "required": required },
+                })
+            })
+            .collect()
+    }
+
+    /// `tools/call`: a failing tool is reported in its result, for the agent
+    /// to read; an unknown one is a protocol error.
+    fn call(&mut self, params: &Value) -> Result<Value, (i64, String)> {
+        let name = params
+            .get("name")
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+        if !TOOLS.iter().any(|t| t.name == name) || (name == "list_services" && !self.indexed()) {
+            return Err((INVALID_PARAMS, format!("unknown tool {name}")));
+        }
+        let args = params
+            .get("arguments")
+            .cloned()
+            .unwrap_or_else(|| json!({}));
+        let (text, is_error) = match self.run(name, &args) {
+            Ok(value) => (format!("{value:#}"), false),
+            Err(e) => (format!("{e:#}"), true),
+        };
+        Ok(json!({ "content": [{ "type": "text", "text": text }], "isError": is_error }))
+    }
+
+    fn run(&mut self, tool: &str, args: &Value) -> Result<Value> {
+        let across = self.indexed() && args.get("service").is_none();
+        match tool {
+            "list_services" => Ok(self.list_services()),
+            "list_schemas" => Ok(list_schemas(self.spec(args)?)),
+            "get_schema" => {
+                let name = arg(args, "name")?;
+                let spec = self.spec(args)?;
+                let root = spec
+                    .schema_nodes
+                    .iter()
+                    .find(|n| n.name == name)
+                    .with_context(|| {
+                        format!("no schema named {name}; list_schemas gives their names")
+                    })?;
+                Ok(schema_json(root, spec))
+            }
+            "find_property" if across => {
+                let name = arg(args, "name")?.to_lowercase();
+                let mut hits = Vec::new();
+                for (service, spec) in self.every_spec()? {
+                    hits.extend(find_property(spec, &name).into_iter().map(|mut hit| {
+                        hit["service"] = json!(service);
+                        hit
+                    }));
+                }
+                hits.truncate(MAX_HITS);
+                Ok(Value::Array(hits))
+            }
+            "find_property" => {
+                let name = arg(args, "name")?.to_lowercase();
+                let mut hits = find_property(self.spec(args)?, &name);
+                hits.truncate(MAX_HITS);
+                Ok(Value::Array(hits))
+            }
+            "list_operations" => {
+                let tag = args.get("tag").and_then(Value::as_str);
+                let spec = self.spec(args)?;
+                let operations = spec
+                    .operations
+                    .iter()
+                    .filter(|op| tag.is_none_or(|tag| op.tags.iter().any(|t| t == tag)))
+                    .map(operation_summary)
+                    .collect();
+                Ok(Value::Array(operations))
+            }
+            "get_operation" => {
+                let wanted = arg(args, "operation")?;
+                let spec = self.spec(args)?;
+                let op = find_operation(spec, wanted).with_context(|| {
+                    format!("no operation {wanted}; list_operations gives their ids and paths")
+                })?;
+                Ok(operation_json(op, spec))
+            }
+            "search" if across => {
+                let Backend::Index { catalog, .. } = &self.backend else {
+                    unreachable!("searching across services needs the index");
+                };
+                let hits = catalog.search(arg(args, "query")?, MAX_HITS)?;
+                Ok(Value::Array(hits.iter().map(index_hit).collect()))
+            }
+            "search" => {
+                let words: Vec<String> = arg(args, "query")?
+                    .split_whitespace()
+                    .map(str::to_lowercase)
+                    .collect();
+                Ok(Value::Array(search(self.spec(args)?, &words)))
+            }
+            _ => bail!("unknown tool {tool}"),
+        }
+    }
+
+    /// The spec the tool asks about: the only one, or the named service.
+    fn spec(&mut self, args: &Value) -> Result<&LoadedSpec> {
+        match &mut self.backend {
+            Backend::Spec(spec) => Ok(spec),
+            Backend::Index { catalog, loaded } => {
+                let name = arg(args, "service")?;
+                let index = catalog.position(name).with_context(|| {
+                    format!("no service named {name}; list_services gives their names")
+                })?;
+                load(catalog, loaded, index)
+            }
+        }
+    }
+
+    /// Every service in the index, by name.
+    fn every_spec(&mut self) -> Result<Vec<(&str, &LoadedSpec)>> {
+        let Backend::Index { catalog, loaded } = &mut self.backend else {
+            bail!("not serving the index");
+        };
+        for index in 0..catalog.services.len() {
+            load(catalog, loaded, index)?;
+        }
+        Ok(catalog
+            .services
+            .iter()
+            .enumerate()
+            .map(|(index, service)| (service.name.as_str(), &loaded[&index]))
+            .collect())
+    }
+
+    fn list_services(&self) -> Value {
+        let Backend::Index { catalog, .. } = &self.backend else {
+            return json!([]);
+        };
+        catalog
+            .services
+            .iter()
+            .map(|s| {
+                json!({
+                    "name": s.name,
+                    "title": s.spec_title,
+                    "version": s.spec_version,
+                    "team": s.team,
+                    "domain": s.domain,
+                    "schemas": s.schema_count,
+                    "paths": s.path_count,
+                })
+            })
+            .collect()
+    }
+}
+
+/// A service's spec, parsed from the index the first time it is asked for.
+fn load<'a>(
+    catalog: &Catalog,
+    loaded: &'a mut HashMap<usize, LoadedSpec>,
+    index: usize,
+) -> Result<&'a LoadedSpec> {
+    Ok(match loaded.entry(index) {
+        Entry::Occupied(entry) => entry.into_mut(),
+        Entry::Vacant(entry) => entry.insert(catalog.load(index)?),
+    })
+}
+
+fn failure(id: Value, code: i64, message: String) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } })
+}
+
+fn arg<'a>(args: &'a Value, key: &str) -> Result<&'a str> {
+    args.get(key)
+        .and_then(Value::as_str)
+        .with_context(|| format!("missing argument {key}"))
+}
+
+// ── tools ─────────────────────────────────────────────────────────────────────
+
+fn list_schemas(spec: &LoadedSpec) -> Value {
+    spec.schema_nodes
+        .iter()
+        .map(|node| {
+            let description = node
+                .info
+                .description
+                .as_deref()
+                .and_then(|d| d.lines().next());
+            json!({ "name": node.name, "type": node.type_label(), "description": description })
+        })
+        .collect()
+}
+
+fn find_property(spec: &LoadedSpec, name: &str) -> Vec<Value> {
+    let mut hits = Vec::new();
+    walk(spec, &mut |chain, path| {
+        let node = chain[chain.len() - 1];
+        if chain.len() > 1 && node.name.to_lowercase().contains(name) {
+            hits.push(property_hit(spec, chain, path));
+        }
+    });
+    hits
+}
+
+/// Schemas and properties whose name or description has every word, then
+/// operations whose path, id, summary or description does.
+fn search(spec: &LoadedSpec, words: &[String]) -> Vec<Value> {
+    let has_all = |texts: &[Option<&str>]| {
+        let texts: Vec<String> = texts.iter().flatten().map(|t| t.to_lowercase()).collect();
+        words
+            .iter()
+            .all(|word| texts.iter().any(|t| t.contains(word.as_str())))
+    };
+    let mut hits = Vec::new();
+    walk(spec, &mut |chain, path| {
+        let node = chain[chain.len() - 1];
+        if has_all(&[Some(&node.name), node.info.description.as_deref()]) {
+            hits.push(property_hit(spec, chain, path));
+        }
+    });
+    for op in &spec.operations {
+        let texts = [
+            Some(op.path.as_str()),
+            op.operation_id.as_deref(),
+            op.summary.as_deref(),
+            op.description.as_deref(),
+        ];
+        if has_all(&texts) {
+            hits.push(operation_summary(op));
+        }
+    }
+    hits.truncate(MAX_HITS);
+    hits
+}
+
+/// Call `visit` with the chain and path of every schema node, not going
+/// through `$ref`s: what they lead to is visited under its own name.
+fn walk<'a>(spec: &'a LoadedSpec, visit: &mut dyn FnMut(&[&'a TreeNode], &[usize])) {
+    fn go<'a>(
+        node: &'a TreeNode,
+        chain: &mut Vec<&'a TreeNode>,
+        path: &mut Vec<usize>,
+        visit: &mut dyn FnMut(&[&'a TreeNode], &[usize]),
+    ) {
+        chain.push(node);
+        visit(chain, path);
+        if !matches!(node.info.kind, NodeKind::Ref(_)) {
+            for (i, child) in node.children.iter().enumerate() {
+                path.push(i);
+                go(child, chain, path, visit);
+                path.pop();
+            }
+        }
+        chain.pop();
+    }
+    for (i, root) in spec.schema_nodes.iter().enumerate() {
+        go(root, &mut Vec::new(), &mut vec![i], visit);
+    }
+}
+
+fn property_hit(spec: &LoadedSpec, chain: &[&TreeNode], path: &[usize]) -> Value {
+    let node = chain[chain.len() - 1];
+    let mut hit = json!({
+        "schema": chain[0].name,
+        "path": breadcrumb::label(chain),
+        "type": node.type_label(),
+        "description": node.info.description,
+        "pointer": breadcrumb::json_pointer(spec, View::Schemas, chain, path),
+    });
+    if chain.len() > 1 {
+        hit["required"] = json!(node.info.required);
+    }
+    hit
+}
+
+/// An index search hit with its match markers taken out.
+fn index_hit(hit: &fts::Hit) -> Value {
+    let plain = |text: &str| -> String {
+        fts::segments(text)
+            .into_iter()
+            .map(|(run, _)| run)
+            .collect()
+    };
+    json!({
+        "service": hit.service,
+        "schema": hit.schema_name,
+        "property": hit.property_name,
+        "description": plain(&hit.snippet),
+    })
+}
+
+fn operation_summary(op: &Operation) -> Value {
+    json!({
+        "method": op.method.to_ascii_uppercase(),
+        "path": op.path,
+        "operationId": op.operation_id,
+        "summary": op.summary,
+        "tags": op.tags,
+        "deprecated": op.deprecated,
+    })
+}
+
+/// By `operationId`, or by `METHOD /path`.
+fn find_operation<'a>(spec: &'a LoadedSpec, wanted: &str) -> Option<&'a Operation> {
+    let by_route = wanted
+        .split_once(' ')
+        .map(|(method, path)| (method.to_ascii_lowercase(), path.trim()));
+    spec.operations.iter().find(|op| {
+        op.operation_id.as_deref() == Some(wanted)
+            || by_route
+                .as_ref()
+                .is_some_and(|(method, path)| op.method == *method && op.path == *path)
+    })
+}
+
+fn operation_json(op: &Operation, spec: &LoadedSpec) -> Value {
+    let mut out = operation_summary(op);
+    out["description"] = json!(op.description);
+    out["parameters"] = op
+        .parameters
+        .iter()
+        .map(|p| {
+            json!({
+                "name": p.schema.name,
+                "in": p.location,
+                "required": p.schema.info.required,
+                "description": p.schema.info.description,
+                "schema": schema_json(&p.schema, spec),
+            })
+        })
+        .collect();
+    if let Some(body) = &op.request_body {
+        out["requestBody"] = json!({
+            "contentType": body.content_type,
+            "required": body.required,
+            "description": body.description,
+            "schema": body.schema.as_ref().map(|s| schema_json(s, spec)),
+        });
+    }
+    let mut responses = Map::new();
+    for response in &op.responses {
+        let mut entry =
+            json!({ "description": response.description, "contentType": response.content_type });
+        if let Some(schema) = &response.schema {
+            entry["schema"] = schema_json(schema, spec);
+        }
+        if let Some(example) = &response.example {
+            entry["example"] = example.clone();
+        }
+        responses.insert(response.status.clone(), entry);
+    }
+    out["responses"] = Value::Object(responses);
+    out
+}
+
+// ── resolved JSON ─────────────────────────────────────────────────────────────
+
+/// The schema at `node` as JSON Schema, with each `$ref` replaced by the
+/// component it names. A `$ref` back into a component already being written
+/// stays a `$ref`, so cyclic schemas come out finite.
+pub fn schema_json(node: &TreeNode, spec: &LoadedSpec) -> Value {
+    let mut writer = Writer {
+        spec,
+        stack: Vec::new(),
+    };
+    if let Some(index) = spec.schema_nodes.iter().position(|s| std::ptr::eq(s, node)) {
+        writer.stack.push(index);
+    }
+    writer.value(node)
+}
+
+struct Writer<'a> {
+    spec: &'a LoadedSpec,
+    stack: Vec<usize>, // components being written, by index into `schema_nodes`
+}
+
+impl Writer<'_> {
+    fn value(&mut self, node: &TreeNode) -> Value {
+        if let NodeKind::Ref(target) = &node.info.kind
+            && let Some(index) = self
+                .spec
+                .schema_nodes
+                .iter()
+                .position(|s| s.name == *target)
+        {
+            let root = &self.spec.schema_nodes[index];
+            if self.stack.contains(&index) {
+                let pointer = breadcrumb::json_pointer(self.spec, View::Schemas, &[root], &[index]);
+                return json!({ "$ref": pointer });
+            }
+            self.stack.push(index);
+            let mut value = self.value(root);
+            self.stack.pop();
+            if let Value::Object(map) = &mut value {
+                map.insert("title".to_string(), json!(target));
+                // What the referring property says about it wins.
+                if let Some(description) = &node.info.description {
+                    map.insert("description".to_string(), json!(description));
+                }
+            }
+            return value;
+        }
+
+        let mut out = Map::new();
+        let info = &node.info;
+        let kind = match &info.kind {
+            NodeKind::Object => Some(json!("object")),
+            NodeKind::Array | NodeKind::Tuple => Some(json!("array")),
+            NodeKind::Str => Some(json!("string")),
+            NodeKind::Integer => Some(json!("integer")),
+            NodeKind::Number => Some(json!("number")),
+            NodeKind::Boolean => Some(json!("boolean")),
+            NodeKind::Null => Some(json!("null")),
+            NodeKind::Multi(types) => Some(json!(types)),
+            _ => None,
+        };
+        match kind {
+            Some(Value::String(single)) if info.nullable => {
+                out.insert("type".to_string(), json!([single, "null"]))
+            }
+            Some(Value::Array(mut many)) if info.nullable => {
+                many.push(json!("null"));
+                out.insert("type".to_string(), Value::Array(many))
+            }
+            Some(kind) => out.insert("type".to_string(), kind),
+            None => None,
+        };
+        if let Some(format) = &info.format {
+            out.insert("format".to_string(), json!(format));
+        }
+        if let Some(description) = &info.description {
+            out.insert("description".to_string(), json!(description));
+        }
+        if !info.enum_values.is_empty() {
+            out.insert(
+                "enum".to_string(),
+                info.enum_values.iter().map(|v| literal(v)).collect(),
+            );
+        }
+        if let Some(default) = &info.default_val {
+            out.insert("default".to_string(), literal(default));
+        }
+        if let Some(example) = &info.example {
+            out.insert("example".to_string(), literal(example));
+        }
+        if !info.examples.is_empty() {
+            out.insert(
+                "examples".to_string(),
+                info.examples.iter().map(|v| literal(v)).collect(),
+            );
+        }
+        for constraint in &info.constraints {
+            keyword(&mut out, constraint);
+        }
+        self.children(&mut out, node);
+        Value::Object(out)
+    }
+
+    /// Properties, items, branches and keyword schemas, by where the tree
+    /// puts them.
+    fn children(&mut self, out: &mut Map<String, Value>, node: &TreeNode) {
+        let combiner = match node.info.kind {
+            NodeKind::AllOf => Some("allOf"),
+            NodeKind::OneOf => Some("oneOf"),
+            NodeKind::AnyOf => Some("anyOf"),
+            _ => None,
+        };
+        let listy = matches!(node.info.kind, NodeKind::Array | NodeKind::Tuple);
+        let mut properties = Map::new();
+        let mut required = Vec::new();
+        let mut positional = Vec::new();
+        for child in &node.children {
+            let name = child.name.as_str();
+            match &child.info.kind {
+                NodeKind::AllOf | NodeKind::OneOf | NodeKind::AnyOf
+                    if matches!(name, "allOf" | "oneOf" | "anyOf") =>
+                {
+                    let branches: Vec<Value> =
+                        child.children.iter().map(|b| self.value(b)).collect();
+                    out.insert(name.to_string(), Value::Array(branches));
+                }
+                NodeKind::Group(keyword) => {
+                    let entries: Map<String, Value> = child
+                        .children
+                        .iter()
+                        .map(|c| (c.name.clone(), self.value(c)))
+                        .collect();
+                    out.insert(keyword.clone(), Value::Object(entries));
+                }
+                _ if name == "additionalProperties" || KEYWORD_CHILDREN.contains(&name) => {
+                    out.insert(name.to_string(), self.value(child));
+                }
+                _ if listy && matches!(name, "items" | "contains" | "unevaluatedItems") => {
+                    out.insert(name.to_string(), self.value(child));
+                }
+                _ if name.starts_with('[') && (combiner.is_some() || listy) => {
+                    positional.push(self.value(child))
+                }
+                _ => {
+                    if child.info.required {
+                        required.push(json!(name));
+                    }
+                    properties.insert(name.to_string(), self.value(child));
+                }
+            }
+        }
+        match combiner {
+            Some(keyword) => {
+                out.insert(keyword.to_string(), Value::Array(positional));
+            }
+            None if !positional.is_empty() => {
+                out.insert("prefixItems".to_string(), Value::Array(positional));
+            }
+            None => {}
+        }
+        if !properties.is_empty() {
+            out.insert("properties".to_string(), Value::Object(properties));
+        }
+        if !required.is_empty() {
+            out.insert("required".to_string(), Value::Array(required));
+        }
+    }
+}
+
+/// A constraint as `NodeInfo` keeps it ("min: >0", "maxLength: 255") back
+/// as the keyword it came from.
+fn keyword(out: &mut Map<String, Value>, constraint: &str) {
+    let Some((key, raw)) = constraint.split_once(": ") else {
+        return;
+    };
+    match key {
+        "min" | "max" => {
+            let (exclusive, inclusive) = match key {
+                "min" => ("exclusiveMinimum", "minimum"),
+                _ => ("exclusiveMaximum", "maximum"),
+            };
+            match raw.strip_prefix(['>', '<']) {
+                Some(bound) => out.insert(exclusive.to_string(), literal(bound)),
+                None => out.insert(inclusive.to_string(), literal(raw)),
+            };
+        }
+        "pattern" => {
+            out.insert(key.to_string(), json!(raw));
+        }
+        "dependentRequired" => {
+            let Some((property, needs)) = raw.split_once(" → ") else {
+                return;
+            };
+            let needs: Vec<&str> = needs.split(", ").collect();
+            let entry = out.entry(key).or_insert_with(|| json!({}));
+            entry[property] = json!(needs);
+        }
+        _ => {
+            out.insert(key.to_string(), literal(raw));
+        }
+    }
+}
+
+// ── tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::catalog::tests::fixture_catalog;
+    use crate::parser::parse_spec;
+
+    const SPEC: &str = r##"
+openapi: 3.0.3
+info: { title: Shop, version: "1" }
+paths:
+  /orders/{id}:
+    get:
+      operationId: getOrder
+      summary: Fetch one order
+      parameters:
+        - { name: id, in: path, required: true, schema: { type: string, format: uuid } }
+      responses:
+        "200":
+          description: The order
+          content:
+            application/json:
+              schema: { $ref: "#/components/schemas/Order" }
+components:
+  schemas:
+    Order:
+      type: object
+      description: A customer's order
+      required: [id]
+      properties:
+        id: { type: string, format: uuid }
+        total: { type: number, minimum: 0, exclusiveMaximum: true, maximum: 1000 }
+        customer: { $ref: "#/components/schemas/Customer" }
+    Customer:
+      type: object
+      properties:
+        customerId: { type: string, pattern: "^C[0-9]+$" }
+        referrer: { $ref: "#/components/schemas/Customer" }
+"##;
+
+    /// Feed `requests` through the server, one per line; the parsed replies.
+    fn exchange(backend: Backend, requests: &[Value]) -> Vec<Value> {
+        let input: String = requests.iter().map(|r| format!("{r}\n")).collect();
+        let mut output = Vec::new();
+        serve(backend, input.as_bytes(), &mut output).unwrap();
+        String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+
+    fn call(id: u64, tool: &str, arguments: Value) -> Value {
+        json!({ "jsonrpc": "2.0", "id": id, "method": "tools/call", "params": { "name": tool, "arguments": arguments } })
+    }
+
+    /// The JSON a tool answered with.
+    fn text(reply: &Value) -> Value {
+        assert_eq!(reply["result"]["isError"], json!(false), "{reply}");
+        serde_json::from_str(reply["result"]["content"][0]["text"].as_str().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn speaks_the_protocol() {
+        let spec = Backend::Spec(parse_spec(SPEC).unwrap());
+        let replies = exchange(
+            spec,
+            &[
+                json!({ "jsonrpc": "2.0", "id": 1, "method": "initialize", "params": { "protocolVersion": "2025-03-26" } }),
+                json!({ "jsonrpc": "2.0", "method": "notifications/initialized" }),
+                json!({ "jsonrpc": "2.0", "id": 2, "method": "tools/list" }),
+                json!({ "jsonrpc": "2.0", "id": 3, "method": "resources/list" }),
+                call(4, "get_schema", json!({ "name": "Nope" })),
+                call(5, "list_services", json!({})),
+            ],
+        );
+        assert_eq!(replies.len(), 5, "notifications get no reply");
+        assert_eq!(replies[0]["result"]["protocolVersion"], "2025-03-26");
+        assert_eq!(replies[0]["result"]["serverInfo"]["name"], "speq");
+        let tools: Vec<&str> = replies[1]["result"]["tools"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|t| t["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            tools,
+            [
+                "list_schemas",
+                "get_schema",
+                "find_property",
+                "list_operations",
+                "get_operation",
+                "search"
+            ]
+        );
+        assert_eq!(replies[2]["error"]["code"], METHOD_NOT_FOUND);
+        assert_eq!(replies[3]["result"]["isError"], true);
+        assert_eq!(
+            replies[3]["result"]["content"][0]["text"],
+            "no schema named Nope; list_schemas gives their names"
+        );
+        assert_eq!(
+            replies[4]["error"]["code"], INVALID_PARAMS,
+            "only in multi-service mode"
+        );
+    }
+
+    #[test]
+    fn answers_from_one_spec() {
+        let spec = Backend::Spec(parse_spec(SPEC).unwrap());
+        let replies = exchange(
+            spec,
+            &[
+                call(1, "get_schema", json!({ "name": "Order" })),
+                call(2, "find_property", json!({ "name": "CUSTOMER" })),
+                call(
+                    3,
+                    "get_operation",
+                    json!({ "operation": "get /orders/{id}" }),
+                ),
+                call(4, "search", json!({ "query": "fetch order" })),
+            ],
+        );
+        let order = text(&replies[0]);
+        assert_eq!(order["required"], json!(["id"]));
+        assert_eq!(
+            order["properties"]["total"],
+            json!({ "type": "number", "minimum": 0, "exclusiveMaximum": 1000 })
+        );
+        let customer = &order["properties"]["customer"];
+        assert_eq!(customer["title"], "Customer");
+        assert_eq!(customer["properties"]["customerId"]["pattern"], "^C[0-9]+$");
+        assert_eq!(
+            customer["properties"]["referrer"],
+            json!({ "$ref": "#/components/schemas/Customer" }),
+            "the cycle is cut"
+        );
+
+        let found = text(&replies[1]);
+        let paths: Vec<&str> = found
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|h| h["path"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            paths,
+            ["Customer › customerId", "Order › customer → Customer"]
+        );
+        assert_eq!(
+            found[0]["pointer"],
+            "#/components/schemas/Customer/properties/customerId"
+        );
+
+        let operation = text(&replies[2]);
+        assert_eq!(operation["operationId"], "getOrder");
+        assert_eq!(
+            operation["parameters"][0]["schema"],
+            json!({ "type": "string", "format": "uuid" })
+        );
+        assert_eq!(operation["responses"]["200"]["schema"]["title"], "Order");
+
+        assert_eq!(
+            text(&replies[3]),
+            json!([operation_summary(&parse_spec(SPEC).unwrap().operations[0])])
+        );
+    }
+
+    #[test]
+    fn answers_from_the_index() {
+        let catalog = fixture_catalog("mcp");
+        let replies = exchange(
+            Backend::index(catalog),
+            &[
+                call(1, "list_services", json!({})),
+                call(2, "list_schemas", json!({})),
+                call(3, "list_schemas", json!({ "service": "pets-v2" })),
+                call(4, "search", json!({ "query": "pet name" })),
+                call(5, "find_property", json!({ "name": "tag" })),
+                call(
+                    6,
+                    "get_operation",
+                    json!({ "service": "pets-v2", "operation": "showPetById" }),
+                ),
+            ],
+        );
+        let services = text(&replies[0]);
+        let names: Vec<&str> = services
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|s| s["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(names, ["pets-v2", "pets-v3", "pets-v31"]);
+        assert_eq!(
+            replies[1]["result"]["content"][0]["text"],
+            "missing argument service"
+        );
+        assert!(
+            text(&replies[2])
+                .as_array()
+                .unwrap()
+                .iter()
+                .any(|s| s["name"] == "Pet")
+        );
+        let hits = text(&replies[3]);
+        assert!(
+            hits.as_array()
+                .unwrap()
+                .iter()
+                .all(|h| h["description"].as_str().is_some())
+        );
+        assert_eq!(hits[0]["property"], "name");
+        let tags = text(&replies[4]);
+        assert!(
+            tags.as_array()
+                .unwrap()
+                .iter()
+                .any(|h| h["service"] == "pets-v31")
+        );
+
+        // Operations read back from the index keep their parameters and responses.
+        let op = text(&replies[5]);
+        assert_eq!(op["parameters"][0]["name"], "petId");
+        assert_eq!(op["parameters"][0]["in"], "path");
+        assert_eq!(op["responses"]["200"]["schema"]["title"], "Pet");
+        assert_eq!(
+            op["responses"]["404"]["description"],
+            "The requested pet does not exist"
+        );
+    }
+}
@@ -0,0 +1,776 @@
+use std::io::IsTerminal;
+
+use anyhow::{Result, anyhow};
+use regex::Regex;
+use serde_json::{Value, json};
+use tiny_http::{Header, Server};
+
+use crate::example;
+use crate::spec::{LoadedSpec, Operation, Response};
+use crate::tree::{NodeKind, TreeNode};
+use crate::validate;
+
+/// Answers requests for every operation in a spec's `paths` with the
+/// documented example, or one generated from the response schema.
+pub struct Mock {
+    spec: LoadedSpec,
+    routes: Vec<Route>, // most specific template first
+    bases: Vec<String>, // path prefixes of the spec's servers, e.g. `/v1`
+}
+
+/// One operation's path template, as a pattern over request paths.
+struct Route {
+    operation: usize,   // index into `LoadedSpec::operations`
+    pattern: Regex,     // anchored, one group per `{name}`
+    names: Vec<String>, // the path parameters, in group order
+    literal: usize,     // characters outside `{...}`: `/pets/mine` beats `/pets/{id}`
+}
+
+/// A request as it came in.
+pub struct Request<'a> {
+    pub method: &'a str,
+    pub url: &'a str, // path and query string
+    pub headers: &'a [(String, String)],
+    pub body: &'a [u8],
+}
+
+/// What to answer, and how the request strayed from the spec.
+pub struct Reply {
+    pub status: u16,
+    pub content_type: Option<String>,
+    pub body: Vec<u8>,
+    pub operation: Option<String>, // operationId, else `METHOD /template`
+    pub mismatches: Vec<String>,
+}
+
+impl Mock {
+    /// `document` is the spec as parsed (see `parser::bundle`), for its servers.
+    pub fn new(spec: LoadedSpec, document: &Value) -> Mock {
+        let mut routes: Vec<Route> = spec
+            .operations
+            .iter()
+            .enumerate()
+            .map(|(i, op)| route(i, &op.path))
+            .collect();
+        routes.sort_by_key(|r| std::cmp::Reverse(r.literal));
+        Mock {
+            spec,
+            routes,
+            bases: base_paths(document),
+        }
+    }
+
+    pub fn respond(&self, request: &Request) -> Reply {
+        let (path, query) = request.url.split_once('?').unwrap_or((request.url, ""));
+        // `/v1/pets` for a server at `https://host/v1`, or plain `/pets`.
+        let template_path = self
+            .bases
+            .iter()
+            .filter_map(|base| path.strip_prefix(base.as_str()))
+            .find(|rest| rest.starts_with('/'))
+            .unwrap_or(path);
+        let method = request.method.to_ascii_lowercase();
+        let mut allowed = Vec::new();
+        for route in &self.routes {
+            let Some(captures) = route.pattern.captures(template_path) else {
+                continue;
+            };
+            let op = &self.spec.operations[route.operation];
+            if op.method != method {
+                allowed.push(op.method.to_ascii_uppercase());
+                continue;
+            }
+            let values: Vec<(String, String)> = route
+                .names
+                .iter()
+                .zip(captures.iter().skip(1))
+                .filter_map(|(name, m)| Some((name.clone(), decode(m?.as_str()))))
+                .collect();
+            return self.answer(op, &values, query, request);
+        }
+        if allowed.is_empty() {
+            return error(404, format!("no operation at {path}"));
+        }
+        error(
+            405,
+            format!(
+                "{} is not defined for {path} (only {})",
+                request.method,
+                allowed.join(", ")
+            ),
+        )
+    }
+
+    fn answer(
+        &self,
+        op: &Operation,
+        path_values: &[(String, String)],
+        query: &str,
+        request: &Request,
+    ) -> Reply {
+        let mut mismatches = self.check_parameters(op, path_values, query, request);
+        mismatches.extend(self.check_body(op, request));
+
+        let prefer = header(request.headers, "Prefer").and_then(|p| {
+            p.split([',', ';'])
+                .find_map(|t| t.trim().strip_prefix("code=")?.parse().ok())
+        });
+        let chosen = match prefer {
+            Some(code) => response_for(op, code).or_else(|| {
+                mismatches.push(format!("no {code} response is documented"));
+                success(op)
+            }),
+            None => success(op),
+        };
+        let label = op
+            .operation_id
+            .clone()
+            .unwrap_or_else(|| format!("{} {}", op.method.to_ascii_uppercase(), op.path));
+        let Some((response, status)) = chosen else {
+            return Reply {
+                status: 200,
+                content_type: None,
+                body: Vec::new(),
+                operation: Some(label),
+                mismatches,
+            };
+        };
+
+        let value = response.example.clone().or_else(|| {
+            let schema = response.schema.as_ref()?;
+            example::generate(schema, &self.spec.schema_nodes)
+        });
+        let (content_type, body) = match value {
+            Some(value) => {
+                let content_type = response
+                    .content_type
+                    .clone()
+                    .unwrap_or_else(|| "application/json".to_string());
+                let body = match value {
+                    // Plain text, XML and the like are served as written.
+                    Value::String(text) if !content_type.contains("json") => text.into_bytes(),
+                    value => serde_json::to_vec_pretty(&value).unwrap_or_default(),
+                };
+                (Some(content_type), body)
+            }
+            None => (None, Vec::new()),
+        };
+        Reply {
+            status,
+            content_type,
+            body,
+            operation: Some(label),
+            mismatches,
+        }
+    }
+
+    /// Each parameter checked against its schema; missing required ones noted.
+    fn check_parameters(
+        &self,
+        op: &Operation,
+        path_values: &[(String, String)],
+        query: &str,
+        request: &Request,
+    ) -> Vec<String> {
+        let query = pairs(query, '&');
+        let cookies = header(request.headers, "Cookie")
+            .map(|c| pairs(c, ';'))
+            .unwrap_or_default();
+        let mut mismatches = Vec::new();
+        for param in &op.parameters {
+            let name = &param.schema.name;
+            let found: Vec<String> = match param.location.as_str() {
+                "path" => lookup(path_values, name, false),
+                "query" => lookup(&query, name, false),
+                "header" => lookup(request.headers, name, true),
+                "cookie" => lookup(&cookies, name, false),
+                _ => continue,
+            };
+            if found.is_empty() {
+                if param.schema.info.required {
+                    mismatches.push(format!(
+                        "missing required {} parameter {name}",
+                        param.location
+                    ));
+                }
+                continue;
+            }
+            let value = coerce(&found, &param.schema, &self.spec.schema_nodes);
+            for v in validate::validate(&value, &param.schema, &self.spec, &param.pointer) {
+                mismatches.push(format!(
+                    "{} parameter {name}{}: {} ({})",
+                    param.location, v.pointer, v.message, v.schema
+                ));
+            }
+        }
+        mismatches
+    }
+
+    /// The request body checked against the documented JSON schema.
+    fn check_body(&self, op: &Operation, request: &Request) -> Vec<String> {
+        let Some(documented) = &op.request_body else {
+            return Vec::new();
+        };
+        if request.body.iter().all(u8::is_ascii_whitespace) {
+            if documented.required {
+                return vec!["missing required request body".to_string()];
+            }
+            return Vec::new();
+        }
+        let (Some(mime), Some(schema)) = (&documented.content_type, &documented.schema) else {
+            return Vec::new();
+        };
+        let mut mismatches = Vec::new();
+        if let Some(sent) = header(request.headers, "Content-Type") {
+            let essence = sent.split(';').next().unwrap_or_default().trim();
+            if !essence.eq_ignore_ascii_case(mime) {
+                mismatches.push(format!("request body is {essence}, expected {mime}"));
+            }
+        }
+        if !mime.contains("json") {
+            return mismatches;
+        }
+        let value: Value = match serde_json::from_slice(request.body) {
+            Ok(value) => value,
+            Err(e) => {
+                mismatches.push(format!("request body is not valid JSON: {e}"));
+                return mismatches;
+            }
+        };
+        let pointer = documented.pointer.as_deref().unwrap_or_default();
+        for v in validate::validate(&value, schema, &self.spec, pointer) {
+            let at = if v.pointer.is_empty() {
+                String::new()
+            } else {
+                format!(" {}", v.pointer)
+            };
+            mismatches.push(format!("request body{at}: {} ({})", v.message, v.schema));
+        }
+        mismatches
+    }
+}
+
+/// Serve `spec` on `port` of the loopback interface until interrupted,
+/// logging each request, and how it strays from the spec, to stderr.
+pub fn serve(spec: LoadedSpec, document: &Value, port: u16) -> Result<()> {
+    let server = Server::http(("127.0.0.1", port))
+        .map_err(|e| anyhow!("cannot listen on port {port}: {e}"))?;
+    let mock = Mock::new(spec, document);
+    let spec = &mock.spec;
+    let prefix = mock.bases.first().map_or("", String::as_str);
+    let base = format!("http://{}{prefix}", server.server_addr());
+    eprintln!(
+        "mocking {} ({} operations) on {base}",
+        spec.title,
+        spec.operations.len()
+    );
+    for op in &spec.operations {
+        eprintln!("  {:<7} {base}{}", op.method.to_ascii_uppercase(), op.path);
+    }
+    let color = std::io::stderr().is_terminal();
+
+    for mut request in server.incoming_requests() {
+        let method = request.method().to_string();
+        let url = request.url().to_string();
+        let headers: Vec<(String, String)> = request
+            .headers()
+            .iter()
+            .map(|h| (h.field.to_string(), h.value.to_string()))
+            .collect();
+        let mut body = Vec::new();
+        if let Err(e) = request.as_reader().read_to_end(&mut body) {
+            eprintln!("{method} {url}: cannot read the body: {e}");
+            continue;
+        }
+
+        let reply = mock.respond(&Request {
+            method: &method,
+            url: &url,
+            headers: &headers,
+            body: &body,
+        });
+        let operation = reply
+            .operation
+            .as_deref()
+            .map(|op| format!("  {op}"))
+            .unwrap_or_default();
+        eprintln!("{method} {url} → {}{operation}", reply.status);
+        for mismatch in &reply.mismatches {
+            if color {
+                eprintln!("  \x1b[33m✗ {mismatch}\x1b[0m");
+            } else {
+                eprintln!("  ✗ {mismatch}");
+            }
+        }
+
+        let mut response =
+            tiny_http::Response::from_data(reply.body).with_status_code(reply.status);
+        if let Some(content_type) = reply.content_type
+            && let Ok(header) = Header::from_bytes("Content-Type", content_type)
+        {
+            response = response.with_header(header);
+        }
+        if let Err(e) = request.respond(response) {
+            eprintln!("{method} {url}: cannot answer: {e}");
+        }
+    }
+    Ok(())
+}
+
+/// The path of each server URL, without a trailing `/`: `https://host/v1`
+/// and `/v1` → `/v1`, `{variables}` filled in with their defaults. For
+/// Swagger 2.0, the `basePath`. Servers at the root give none.
+fn base_paths(document: &Value) -> Vec<String> {
+    let mut bases: Vec<String> = Vec::new();
+    let servers = document
+        .get("servers")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten();
+    let urls = servers.filter_map(|server| {
+        let mut url = server.get("url")?.as_str()?.to_string();
+        let variables = server
+            .get("variables")
+            .and_then(Value::as_object)
+            .into_iter()
+            .flatten();
+        for (name, variable) in variables {
+            let default = variable
+                .get("default")
+                .and_then(Value::as_str)
+                .unwrap_or_default();
+            url = url.replace(&format!("{{{name}}}"), default);
+        }
+        Some(url)
+    });
+    let base_path = document
+        .get("basePath")
+        .and_then(Value::as_str)
+        .map(str::to_string);
+    for url in urls.chain(base_path) {
+        let path = match url.split_once("://") {
+            Some((_, rest)) => rest.find('/').map_or("", |at| &rest[at..]),
+            None => url.as_str(),
+        };
+        let path = path.trim_end_matches('/');
+        if path.starts_with('/') && !bases.iter().any(|b| b == path) {
+            bases.push(path.to_string());
+        }
+    }
+    bases
+}
+
+/// `/pets/{petId}` → `^/pets/([^/]+)$`.
+fn route(operation: usize, template: &str) -> Route {
+    let mut pattern = String::from("^");
+    let mut names = Vec::new();
+    let mut literal = 0;
+    let mut rest = template;
+    while let Some((before, after)) = rest.split_once('{') {
+        let Some((name, after)) = after.split_once('}') else {
+            break;
+        };
+        pattern.push_str(&regex::escape(before));
+        pattern.push_str("([^/]+)");
+        literal += before.len();
+        names.push(name.to_string());
+        rest = after;
+    }
+    pattern.push_str(&regex::escape(rest));
+    pattern.push('$');
+    literal += rest.len();
+    Route {
+        operation,
+        pattern: Regex::new(&pattern).expect("escaped template is a valid pattern"),
+        names,
+        literal,
+    }
+}
+
+/// The response for status `code`: listed exactly, by its range (`4XX`), or `default`.
+fn response_for(op: &Operation, code: u16) -> Option<(&Response, u16)> {
+    let exact = code.to_string();
+    let range = format!("{}XX", code / 100);
+    op.responses
+        .iter()
+        .find(|r| r.status == exact)
+        .or_else(|| {
+            op.responses
+                .iter()
+                .find(|r| r.status.eq_ignore_ascii_case(&range))
+        })
+        .or_else(|| op.responses.iter().find(|r| r.status == "default"))
+        .map(|r| (r, code))
+}
+
+/// The first success response, else the first one listed.
+fn success(op: &Operation) -> Option<(&Response, u16)> {
+    let response = op
+        .responses
+        .iter()
+        .find(|r| r.status.starts_with('2'))
+        .or_else(|| op.responses.first())?;
+    let status = match response.status.parse() {
+        Ok(code) => code,
+        // `2XX` → 200; `default` stands for success when nothing else is listed.
+        Err(_) => response.status[..1]
+            .parse::<u16>()
+            .map_or(200, |digit| digit * 100),
+    };
+    Some((response, status))
+}
+
+fn error(status: u16, message: String) -> Reply {
+    Reply {
+        status,
+        content_type: Some("application/json".to_string()),
+        body: serde_json::to_vec_pretty(&json!({ "error": message })).unwrap_or_default(),
+        operation: None,
+        mismatches: vec![message],
+    }
+}
+
+/// A parameter's text as the JSON value its schema expects: numbers and
+/// booleans parsed, arrays split on commas or taken from repeated keys.
+fn coerce(found: &[String], node: &TreeNode, schemas: &[TreeNode]) -> Value {
+    let node = resolve(node, schemas);
+    if !matches!(node.info.kind, NodeKind::Array) {
+        return scalar(&found[0], node, schemas);
+    }
+    let texts: Vec<&str> = match found {
+        [one] => one.split(',').collect(),
+        many => many.iter().map(String::as_str).collect(),
+    };
+    let items = node.children.iter().find(|c| c.name == "items");
+    Value::Array(
+        texts
+            .into_iter()
+            .map(|text| match items {
+                Some(items) => scalar(text, items, schemas),
+                None => Value::String(text.to_string()),
+            })
+            .collect(),
+    )
+}
+
+fn scalar(text: &str, node: &TreeNode, schemas: &[TreeNode]) -> Value {
+    let parsed = match &resolve(node, schemas).info.kind {
+        NodeKind::Integer | NodeKind::Number | NodeKind::Boolean => true,
+        NodeKind::Multi(types) => types
+            .iter()
+            .any(|t| matches!(t.as_str(), "integer" | "number" | "boolean")),
+        _ => false,
+    };
+    parsed
+        .then(|| serde_json::from_str::<Value>(text).ok())
+        .flatten()
+        .filter(|v| v.is_number() || v.is_boolean())
+        .unwrap_or_else(|| Value::String(text.to_string()))
+}
+
+/// Follow `$ref`s to the component schema they name.
+fn resolve<'a>(mut node: &'a TreeNode, schemas: &'a [TreeNode]) -> &'a TreeNode {
+    for _ in 0..8 {
+        let NodeKind::Ref(target) = &node.info.kind else {
+            break;
+        };
+        match schemas.iter().find(|s| s.name == *target) {
+            Some(schema) => node = schema,
+            None => break,
+        }
+    }
+    node
+}
+
+fn header<'a>(headers: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    headers
+        .iter()
+        .find(|(n, _)| n.eq_ignore_ascii_case(name))
+        .map(|(_, v)| v.as_str())
+}
+
+fn lookup(pairs: &[(String, String)], name: &str, any_case: bool) -> Vec<String> {
+    pairs
+        .iter()
+        .filter(|(n, _)| {
+            if any_case {
+                n.eq_ignore_ascii_case(name)
+            } else {
+                n == name
+            }
+        })
+        .map(|(_, v)| v.clone())
+        .collect()
+}
+
+/// `a=1&b=2` (or a `Cookie` header, split on `;`) as decoded pairs.
+fn pairs(text: &str, separator: char) -> Vec<(String, String)> {
+    text.split(separator)
+        .map(str::trim)
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (
+                decode(&name.replace('+', " ")),
+                decode(&value.replace('+', " ")),
+            )
+        })
+        .collect()
+}
+
+/// Undo percent-encoding; malformed escapes are kept as written.
+fn decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|h| u8::from_str_radix(std::str::from_utf8(h).ok()?, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(byte)) => {
+                out.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                out.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+// ── tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser;
+
+    const SPEC: &str = r##"
+openapi: 3.0.3
+info: { title: Shop, version: "1" }
+paths:
+  /orders:
+    get:
+      operationId: listOrders
+      parameters:
+        - { name: limit, in: query, schema: { type: integer, maximum: 50 } }
+        - { name: status, in: query, schema: { type: array, items: { type: string, enum: [open, paid] } } }
+        - { name: X-Tenant, in: header, required: true, schema: { type: string } }
+      responses:
+        "200":
+          description: Orders
+          content:
+            application/json:
+              schema: { type: array, items: { $ref: "#/components/schemas/Order" } }
+    post:
+      operationId: createOrder
+      requestBody:
+        required: true
+        content:
+          application/json:
+            schema: { $ref: "#/components/schemas/Order" }
+      responses:
+        "201":
+          description: Created
+          content:
+            application/json:
+              schema: { $ref: "#/components/schemas/Order" }
+              examples:
+                big: { $ref: "#/components/examples/BigOrder" }
+        4XX:
+          description: Rejected
+          content:
+            application/json:
+              example: { error: rejected }
+  /orders/{id}:
+    get:
+      operationId: getOrder
+      parameters:
+        - { name: id, in: path, required: true, schema: { type: integer } }
+      responses:
+        "200":
+          description: An order
+          content:
+            application/json:
+              schema: { $ref: "#/components/schemas/Order" }
+  /orders/latest:
+    get:
+      responses:
+        default:
+          description: The latest order
+          content:
+            text/plain:
+              example: "order 7"
+components:
+  examples:
+    BigOrder:
+      value: { id: 7, total: 1000 }
+  schemas:
+    Order:
+      type: object
+      required: [id, total]
+      properties:
+        id: { type: integer }
+        total: { type: number, minimum: 0 }
+"##;
+
+    fn mock(text: &str) -> Mock {
+        let bundle = parser::bundle(text, None).unwrap();
+        Mock::new(parser::parse_bundle(&bundle).unwrap(), &bundle.document)
+    }
+
+    fn send(mock: &Mock, method: &str, url: &str, headers: &[(&str, &str)], body: &str) -> Reply {
+        let headers: Vec<(String, String)> = headers
+            .iter()
+            .map(|(n, v)| (n.to_string(), v.to_string()))
+            .collect();
+        mock.respond(&Request {
+            method,
+            url,
+            headers: &headers,
+            body: body.as_bytes(),
+        })
+    }
+
+    fn json(reply: &Reply) -> Value {
+        serde_json::from_slice(&reply.body).unwrap()
+    }
+
+    #[test]
+    fn routes_by_method_and_template() {
+        let mock = mock(SPEC);
+        let latest = send(&mock, "GET", "/orders/latest", &[], "");
+        assert_eq!(
+            (latest.status, latest.operation.as_deref()),
+            (200, Some("GET /orders/latest"))
+        );
+        assert_eq!(latest.content_type.as_deref(), Some("text/plain"));
+        assert_eq!(latest.body, b"order 7");
+
+        let order = send(&mock, "GET", "/orders/42", &[], "");
+        assert_eq!(order.operation.as_deref(), Some("getOrder"));
+        assert!(order.mismatches.is_empty(), "{:?}", order.mismatches);
+        assert_eq!(json(&order), json!({ "id": 1, "total": 1.5 }));
+
+        let missing = send(&mock, "GET", "/customers", &[], "");
+        assert_eq!(missing.status, 404);
+        let wrong_method = send(&mock, "DELETE", "/orders", &[], "");
+        assert_eq!(wrong_method.status, 405);
+        assert_eq!(
+            wrong_method.mismatches,
+            vec!["DELETE is not defined for /orders (only GET, POST)"]
+        );
+    }
+
+    #[test]
+    fn serves_paths_with_or_without_the_server_base_path() {
+        for fixture in [
+            include_str!("../fixtures/petstore.yaml"),
+            include_str!("../fixtures/petstore-v2.yaml"),
+        ] {
+            let mock = mock(fixture);
+            for url in ["/v1/pets/12", "/pets/12"] {
+                let pet = send(&mock, "GET", url, &[], "");
+                let answered = (pet.status, pet.operation.as_deref());
+                assert_eq!(answered, (200, Some("showPetById")), "{url}");
+                assert!(pet.mismatches.is_empty(), "{url}: {:?}", pet.mismatches);
+            }
+            assert_eq!(send(&mock, "GET", "/v1x/pets/12", &[], "").status, 404);
+        }
+
+        let servers = json!({
+            "servers": [
+                { "url": "https://{region}.example.com/{version}/", "variables": {
+                    "region": { "default": "eu" },
+                    "version": { "default": "v2" }
+                } },
+                { "url": "/internal" },
+                { "url": "https://example.com" }
+            ]
+        });
+        assert_eq!(base_paths(&servers), ["/v2", "/internal"]);
+    }
+
+    #[test]
+    fn answers_with_documented_examples_for_the_chosen_status() {
+        let mock = mock(SPEC);
+        let order = r#"{"id": 1, "total": 5}"#;
+        let created = send(
+            &mock,
+            "POST",
+            "/orders",
+            &[("Content-Type", "application/json")],
+            order,
+        );
+        assert_eq!(created.status, 201);
+        assert_eq!(json(&created), json!({ "id": 7, "total": 1000 }));
+
+        let rejected = send(&mock, "POST", "/orders", &[("Prefer", "code=422")], order);
+        assert_eq!(rejected.status, 422);
+        assert_eq!(json(&rejected), json!({ "error": "rejected" }));
+
+        let undocumented = send(&mock, "POST", "/orders", &[("Prefer", "code=503")], order);
+        assert_eq!(undocumented.status, 201);
+        assert_eq!(
+            undocumented.mismatches,
+            vec!["no 503 response is documented"]
+        );
+    }
+
+    #[test]
+    fn logs_requests_that_stray_from_the_spec() {
+        let mock = mock(SPEC);
+        let listed = send(
+            &mock,
+            "GET",
+            "/orders?limit=80&status=open,shipped",
+            &[],
+            "",
+        );
+        assert_eq!(listed.status, 200);
+        assert_eq!(
+            listed.mismatches,
+            vec![
+                "query parameter limit: 80 is out of range, expected at most 50 (#/paths/~1orders/get/parameters/0/schema)",
+                "query parameter status/1: \"shipped\" is not one of \"open\" · \"paid\" (#/paths/~1orders/get/parameters/1/schema/items)",
+                "missing required header parameter X-Tenant",
+            ]
+        );
+        let repeated = send(
+            &mock,
+            "GET",
+            "/orders?status=open&status=paid",
+            &[("x-tenant", "acme")],
+            "",
+        );
+        assert!(repeated.mismatches.is_empty(), "{:?}", repeated.mismatches);
+
+        let not_a_number = send(&mock, "GET", "/orders/abc", &[], "");
+        assert_eq!(not_a_number.mismatches.len(), 1);
+        assert!(not_a_number.mismatches[0].starts_with("path parameter id: "));
+
+        let body = r#"{"id": "7", "total": -1}"#;
+        let created = send(
+            &mock,
+            "POST",
+            "/orders",
+            &[("Content-Type", "text/plain")],
+            body,
+        );
+        assert_eq!(
+            created.mismatches,
+            vec![
+                "request body is text/plain, expected application/json",
+                "request body /id: expected integer, got string (#/components/schemas/Order/properties/id)",
+                "request body /total: -1 is out of range, expected at least 0 (#/components/schemas/Order/properties/total)",
+            ]
+        );
+        let empty = send(&mock, "POST", "/orders", &[], "");
+        assert_eq!(empty.mismatches, vec!["missing required request body"]);
+        let garbled = send(&mock, "POST", "/orders", &[], "{");
+        assert!(garbled.mismatches[0].starts_with("request body is not valid JSON"));
+    }
+}
@@ -0,0 +1,474 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::breadcrumb::escape;
+use crate::lint::{Rule, Severity, schema_roots, walk_schema};
+
+/// The per-repository config file, looked up from the spec's directory.
+pub const CONFIG_FILE: &str = ".speq.toml";
+
+/// Naming rules: every violation is an error, since they are opted into.
+pub const RULES: &[Rule] = &[
+    Rule {
+        id: "schema-name-case",
+        severity: Severity::Error,
+        summary: "Schema names must use the case style in .speq.toml",
+    },
+    Rule {
+        id: "property-name-case",
+        severity: Severity::Error,
+        summary: "Property names must use the case style in .speq.toml",
+    },
+    Rule {
+        id: "forbidden-suffix",
+        severity: Severity::Error,
+        summary: "Schema names must not end in a suffix .speq.toml forbids",
+    },
+];
+
+/// `.speq.toml`. Only `[naming]` is read for now.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub naming: Conventions,
+}
+
+/// The `[naming]` table. Anything left out is not checked.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Conventions {
+    pub schemas: Option<CaseStyle>,
+    pub properties: Option<CaseStyle>,
+    pub forbidden_suffixes: Vec<String>, // matched case-sensitively
+    pub abbreviations: Vec<String>,      // all-caps words allowed in camel / Pascal case, e.g. `ID`
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub enum CaseStyle {
+    #[serde(rename = "camelCase")]
+    Camel,
+    #[serde(rename = "PascalCase")]
+    Pascal,
+    #[serde(rename = "snake_case")]
+    Snake,
+    #[serde(rename = "kebab-case")]
+    Kebab,
+    #[serde(rename = "SCREAMING_SNAKE_CASE")]
+    Screaming,
+}
+
+impl CaseStyle {
+    pub fn label(self) -> &'static str {
+        match self {
+            CaseStyle::Camel => "camelCase",
+            CaseStyle::Pascal => "PascalCase",
+            CaseStyle::Snake => "snake_case",
+            CaseStyle::Kebab => "kebab-case",
+            CaseStyle::Screaming => "SCREAMING_SNAKE_CASE",
+        }
+    }
+
+    /// Strict: camel and Pascal case allow no two capitals in a row, the
+    /// separated styles no empty words.
+    fn fits(self, name: &str) -> bool {
+        let Some(first) = name.chars().next() else {
+            return true;
+        };
+        let alnum = |c: char| c.is_ascii_alphanumeric();
+        let separated = |sep: char, ok: fn(&char) -> bool| {
+            name.split(sep)
+                .all(|word| !word.is_empty() && word.chars().all(|c| ok(&c)))
+        };
+        match self {
+            CaseStyle::Camel | CaseStyle::Pascal => {
+                let leading = match self {
+                    CaseStyle::Camel => !first.is_ascii_uppercase(),
+                    _ => first.is_ascii_uppercase(),
+                };
+                leading
+                    && name.chars().all(alnum)
+                    && !name
+                        .as_bytes()
+                        .windows(2)
+                        .any(|pair| pair[0].is_ascii_uppercase() && pair[1].is_ascii_uppercase())
+            }
+            CaseStyle::Snake => separated('_', |c| c.is_ascii_lowercase() || c.is_ascii_digit()),
+            CaseStyle::Kebab => separated('-', |c| c.is_ascii_lowercase() || c.is_ascii_digit()),
+            CaseStyle::Screaming => {
+                separated('_', |c| c.is_ascii_uppercase() || c.is_ascii_digit())
+            }
+        }
+    }
+
+    /// `name` rewritten in this style, for the message.
+    fn apply(self, name: &str) -> String {
+        let words = words(name);
+        let capitalised = |word: &str| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|c| c.to_ascii_uppercase().to_string() + &chars.as_str().to_ascii_lowercase())
+                .unwrap_or_default()
+        };
+        match self {
+            CaseStyle::Camel => words
+                .iter()
+                .enumerate()
+                .map(|(i, w)| {
+                    if i == 0 {
+                        w.to_ascii_lowercase()
+                    } else {
+                        capitalised(w)
+                    }
+                })
+                .collect(),
+            CaseStyle::Pascal => words.iter().map(|w| capitalised(w)).collect(),
+            CaseStyle::Snake => words
+                .iter()
+                .map(|w| w.to_ascii_lowercase())
+                .collect::<Vec<_>>()
+                .join("_"),
+            CaseStyle::Kebab => words
+                .iter()
+                .map(|w| w.to_ascii_lowercase())
+                .collect::<Vec<_>>()
+                .join("-"),
+            CaseStyle::Screaming => words
+                .iter()
+                .map(|w| w.to_ascii_uppercase())
+                .collect::<Vec<_>>()
+                .join("_"),
+        }
+    }
+}
+
+/// Split a name into words at separators and case changes:
+/// `customerID_list` → `customer`, `ID`, `list`; `URLPath` → `URL`, `Path`.
+fn words(name: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    for part in name
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|p| !p.is_empty())
+    {
+        let chars: Vec<char> = part.chars().collect();
+        let mut start = 0;
+        for i in 1..chars.len() {
+            let (prev, cur) = (chars[i - 1], chars[i]);
+            let next_lower = chars.get(i + 1).is_some_and(|c| c.is_ascii_lowercase());
+            if cur.is_ascii_uppercase() && (!prev.is_ascii_uppercase() || next_lower) {
+                words.push(chars[start..i].iter().collect());
+                start = i;
+            }
+        }
+        words.push(chars[start..].iter().collect());
+    }
+    words
+}
+
+/// Find `.speq.toml` in `dir` or the nearest directory above it, stopping at
+/// the repository root (the first directory holding `.git`).
+pub fn find_config(dir: &Path) -> Option<PathBuf> {
+    for dir in dir.ancestors() {
+        let candidate = dir.join(CONFIG_FILE);
+        if candidate.is_file() {
+            return Some(candidate);
+        }
+        if dir.join(".git").exists() {
+            break;
+        }
+    }
+    None
+}
+
+pub fn load(path: &Path) -> Result<Config> {
+    let text =
+        fs::read_to_string(path).with_context(|| format!("cannot read {}", path.display()))?;
+    toml::from_str(&text).with_context(|| format!("invalid {}", path.display()))
+}
+
+/// Check schema and property names in a bundled document. Returns each
+/// violation as `(rule, pointer into the document, message)`, like the lint
+/// rules. `x-speq-ignore: true` on a schema skips it and everything inside
+/// it; a list of names skips just those (the schema's own, or its properties').
+pub fn check(
+    document: &Value,
+    swagger: bool,
+    conventions: &Conventions,
+) -> Vec<(&'static Rule, String, String)> {
+    let rule = |id: &str| {
+        RULES
+            .iter()
+            .find(|r| r.id == id)
+            .expect("unknown naming rule")
+    };
+    let mut schemas = Vec::new();
+    for (pointer, schema) in schema_roots(document, swagger) {
+        walk_schema(schema, pointer, &mut schemas);
+    }
+    // `walk_schema` lists a schema after everything inside it.
+    let ignored: Vec<&String> = schemas
+        .iter()
+        .filter(|(_, schema)| schema.get("x-speq-ignore") == Some(&Value::Bool(true)))
+        .map(|(pointer, _)| pointer)
+        .collect();
+    let skipped = |pointer: &str| {
+        ignored
+            .iter()
+            .any(|at| pointer == at.as_str() || pointer.starts_with(&format!("{at}/")))
+    };
+    let container = if swagger {
+        "/definitions/"
+    } else {
+        "/components/schemas/"
+    };
+
+    let mut found = Vec::new();
+    for (pointer, schema) in schemas.iter().rev() {
+        if skipped(pointer) {
+            continue;
+        }
+        let allowed: Vec<&str> = schema
+            .get("x-speq-ignore")
+            .and_then(Value::as_array)
+            .map(|names| names.iter().filter_map(Value::as_str).collect())
+            .unwrap_or_default();
+
+        // Component schemas and 3.1 `$defs` are named; inline schemas are not.
+        let name = pointer
+            .strip_prefix(container)
+            .filter(|rest| !rest.contains('/'))
+            .or_else(|| {
+                pointer
+                    .rsplit_once("/$defs/")
+                    .map(|(_, name)| name)
+                    .filter(|n| !n.contains('/'))
+            });
+        if let Some(escaped) = name {
+            let name = unescape(escaped);
+            if !allowed.contains(&name.as_str()) {
+                if let Some(style) = conventions.schemas
+                    && let Some(message) = conventions.mismatch(&name, style)
+                {
+                    found.push((
+                        rule("schema-name-case"),
+                        pointer.clone(),
+                        format!("schema {message}"),
+                    ));
+                }
+                if let Some(suffix) = conventions
+                    .forbidden_suffixes
+                    .iter()
+                    .find(|s| name.len() > s.len() && name.ends_with(s.as_str()))
+                {
+                    found.push((
+                        rule("forbidden-suffix"),
+                        pointer.clone(),
+                        format!("schema {name} ends in forbidden suffix {suffix}"),
+                    ));
+                }
+            }
+        }
+
+        let (Some(style), Some(props)) = (
+            conventions.properties,
+            schema.get("properties").and_then(Value::as_object),
+        ) else {
+            continue;
+        };
+        for name in props.keys() {
+            if allowed.contains(&name.as_str()) {
+                continue;
+            }
+            let at = format!("{pointer}/properties/{}", escape(name));
+            if !skipped(&at)
+                && let Some(message) = conventions.mismatch(name, style)
+            {
+                found.push((
+                    rule("property-name-case"),
+                    at,
+                    format!("property {message}"),
+                ));
+            }
+        }
+    }
+    found
+}
+
+impl Conventions {
+    /// Why `name` does not fit `style`, or `None` if it does. Leading `_`,
+    /// `@` and `$` (`_links`, `@type`) are conventions of their own; allowed
+    /// abbreviations count as one capitalised word (`customerID` as `customerId`).
+    fn mismatch(&self, name: &str, style: CaseStyle) -> Option<String> {
+        let bare = name.trim_start_matches(['_', '@', '$']);
+        let mut normalised = bare.to_string();
+        if matches!(style, CaseStyle::Camel | CaseStyle::Pascal) {
+            for abbreviation in &self.abbreviations {
+                let mut word = abbreviation.to_ascii_lowercase();
+                if let Some(first) = word.get_mut(..1) {
+                    first.make_ascii_uppercase();
+                }
+                normalised = normalised.replace(abbreviation.as_str(), &word);
+            }
+        }
+        if style.fits(&normalised) {
+            return None;
+        }
+        let prefix = &name[..name.len() - bare.len()];
+        Some(format!(
+            "{name} is not {} (rename to {prefix}{})",
+            style.label(),
+            style.apply(bare)
+        ))
+    }
+}
+
+fn unescape(token: &str) -> String {
+    token.replace("~1", "/").replace("~0", "~")
+}
+
+// ── tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SPEC: &str = r##"
+openapi: 3.0.3
+info: { title: Names, version: "1" }
+paths:
+  /orders:
+    post:
+      requestBody:
+        content:
+          application/json:
+            schema:
+              type: object
+              properties:
+                order_ref: { type: string }
+      responses:
+        "200": { description: ok }
+components:
+  schemas:
+    OrderDTO:
+      type: object
+      properties:
+        customerID: { type: string }
+        shipping_address: { type: string }
+        _links: { type: object }
+    line_item:
+      type: object
+      x-speq-ignore: [legacy_code]
+      properties:
+        legacy_code: { type: string }
+        unitPrice: { type: number }
+    LegacyBlob:
+      x-speq-ignore: true
+      type: object
+      properties:
+        OLD_FIELD: { type: string }
+"##;
+
+    fn conventions() -> Conventions {
+        let config: Config = toml::from_str(
+            r#"
+[naming]
+schemas = "PascalCase"
+properties = "camelCase"
+forbidden_suffixes = ["DTO", "Dto"]
+abbreviations = ["ID"]
+"#,
+        )
+        .unwrap();
+        config.naming
+    }
+
+    #[test]
+    fn checks_schema_and_property_names() {
+        let document: Value = serde_yaml::from_str(SPEC).unwrap();
+        let mut found: Vec<(&str, String, String)> = check(&document, false, &conventions())
+            .into_iter()
+            .map(|(rule, pointer, message)| (rule.id, pointer, message))
+            .collect();
+        found.sort();
+        let expected = [
+            (
+                "forbidden-suffix",
+                "/components/schemas/OrderDTO",
+                "schema OrderDTO ends in forbidden suffix DTO",
+            ),
+            (
+                "property-name-case",
+                "/components/schemas/OrderDTO/properties/shipping_address",
+                "property shipping_address is not camelCase (rename to shippingAddress)",
+            ),
+            (
+                "property-name-case",
+                "/paths/~1orders/post/requestBody/content/application~1json/schema/properties/order_ref",
+                "property order_ref is not camelCase (rename to orderRef)",
+            ),
+            (
+                "schema-name-case",
+                "/components/schemas/OrderDTO",
+                "schema OrderDTO is not PascalCase (rename to OrderDto)",
+            ),
+            (
+                "schema-name-case",
+                "/components/schemas/line_item",
+                "schema line_item is not PascalCase (rename to LineItem)",
+            ),
+        ];
+        let expected: Vec<(&str, String, String)> = expected
+            .iter()
+            .map(|(rule, pointer, message)| (*rule, pointer.to_string(), message.to_string()))
+            .collect();
+        assert_eq!(found, expected);
+    }
+
+    #[test]
+    fn styles_are_strict_and_suggest_a_rename() {
+        assert!(CaseStyle::Camel.fits("customerId2"));
+        assert!(!CaseStyle::Camel.fits("customerID"));
+        assert!(!CaseStyle::Pascal.fits("customer"));
+        assert!(CaseStyle::Snake.fits("line_item_2"));
+        assert!(!CaseStyle::Snake.fits("line__item"));
+        assert!(CaseStyle::Screaming.fits("MAX_SIZE"));
+        assert_eq!(CaseStyle::Snake.apply("URLPathID"), "url_path_id");
+        assert_eq!(CaseStyle::Pascal.apply("line-item"), "LineItem");
+        assert_eq!(CaseStyle::Camel.apply("Customer_ID"), "customerId");
+    }
+
+    #[test]
+    fn config_is_found_up_to_the_repository_root() {
+        let root = std::env::temp_dir().join(format!("speq-naming-{}", std::process::id()));
+        let nested = root.join("repo/specs/v1");
+        fs::create_dir_all(&nested).unwrap();
+        fs::create_dir_all(root.join("repo/.git")).unwrap();
+        fs::write(
+            root.join(CONFIG_FILE),
+            "[naming]\nschemas = \"PascalCase\"\n",
+        )
+        .unwrap();
+        // Outside the repository: not used.
+        assert_eq!(find_config(&nested), None);
+
+        fs::write(
+            root.join("repo").join(CONFIG_FILE),
+            "[naming]\nproperties = \"snake_case\"\n",
+        )
+        .unwrap();
+        let found = find_config(&nested).unwrap();
+        assert_eq!(found, root.join("repo").join(CONFIG_FILE));
+        assert_eq!(
+            load(&found).unwrap().naming.properties,
+            Some(CaseStyle::Snake)
+        );
+
+        fs::write(&found, "[naming]\nproperties = \"Title Case\"\n").unwrap();
+        assert!(load(&found).is_err());
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
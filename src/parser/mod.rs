@@ -1,27 +1,128 @@
+pub(crate) mod operations;
+mod resolver;
+pub mod v2;
 pub mod v3;
+pub mod v31;
 
-use anyhow::{Context, Result, bail};
+use std::collections::HashSet;
+use std::path::Path;
+
+use anyhow::{Result, bail};
 use serde_json::Value;
 
+pub(crate) use resolver::pointer_tokens;
+pub use resolver::{Bundle, Files, bundle, bundle_from};
+
+use crate::breadcrumb::escape;
+use crate::diagnostics;
+use crate::graph::Graph;
 use crate::spec::LoadedSpec;
+use crate::tree::{NodeKind, TreeNode};
+use crate::usage;
 
 /// Detect OpenAPI version and parse the spec from YAML/JSON bytes.
 pub fn parse_spec(content: &str) -> Result<LoadedSpec> {
-    // Parse as generic JSON value first for version detection.
-    // serde_yaml can deserialise both YAML and JSON.
-    let raw: Value = serde_yaml::from_str(content).context("failed to parse spec as YAML/JSON")?;
+    parse_spec_at(content, None)
+}
+
+/// Parse a spec read from `path`, so `$ref`s to other files resolve relative to it.
+pub fn parse_spec_at(content: &str, path: Option<&Path>) -> Result<LoadedSpec> {
+    parse_bundle(&bundle(content, path)?)
+}
+
+/// Parse an already-bundled document (see `resolver::bundle`).
+pub fn parse_bundle(bundle: &Bundle) -> Result<LoadedSpec> {
+    let mut spec = parse_version(&bundle.document, &bundle.content)?;
+    spec.schema_sources = bundle.sources.clone();
+    spec.diagnostics = diagnostics::collect(&spec, &bundle.document);
+    spec.graph = Graph::build(&spec.schema_nodes);
+    spec.usages = usage::collect(&spec.schema_nodes, &spec.operation_nodes);
+    Ok(spec)
+}
+
+fn parse_version(raw: &Value, content: &str) -> Result<LoadedSpec> {
+    if let Some(version) = raw.get("swagger").and_then(|v| v.as_str())
+        && version.starts_with("2.")
+    {
+        return v2::parse(content);
+    }
+
+    if let Some(version) = raw.get("openapi").and_then(|v| v.as_str())
+        && version.starts_with("3.1")
+    {
+        return v31::parse(content);
+    }
 
-    if let Some(version) = raw.get("swagger").and_then(|v| v.as_str()) {
-        if version.starts_with("2.") {
-            bail!("Swagger 2.0 support is not yet implemented");
+    if let Some(version) = raw.get("openapi").and_then(|v| v.as_str())
+        && version.starts_with("3.")
+    {
+        return v3::parse(content);
+    }
+
+    bail!("Cannot determine OpenAPI version from spec (expected 'openapi: 3.x' or 'swagger: 2.0')")
+}
+
+// ── shared helpers ────────────────────────────────────────────────────────────
+
+/// Walk the tree and, for every `Ref` node that has no children yet,
+/// populate its children from `children_of(target)` (with cycle detection).
+/// Each parser version supplies its own lookup into its schema model.
+pub(crate) fn resolve_refs(
+    nodes: &mut [TreeNode],
+    children_of: &dyn Fn(&str) -> Option<Vec<TreeNode>>,
+    visited: &mut HashSet<String>,
+) {
+    for node in nodes.iter_mut() {
+        // Extract the ref target if this is an unresolved Ref node.
+        let target_opt: Option<String> = match &node.info.kind {
+            NodeKind::Ref(t) if node.children.is_empty() && !visited.contains(t.as_str()) => {
+                Some(t.clone())
+            }
+            _ => None,
+        };
+
+        if let Some(target) = target_opt {
+            if let Some(children) = children_of(&target) {
+                visited.insert(target.clone());
+                node.children = children;
+                // Recurse into the newly-added children before releasing the cycle guard.
+                resolve_refs(&mut node.children, children_of, visited);
+                visited.remove(&target);
+            }
+        } else {
+            // Not a Ref (or already resolved / cycle detected) — just recurse.
+            resolve_refs(&mut node.children, children_of, visited);
         }
     }
+}
 
-    if let Some(version) = raw.get("openapi").and_then(|v| v.as_str()) {
-        if version.starts_with("3.") {
-            return v3::parse(content);
+/// The schema a `$ref` names: a top-level schema, or a `$defs` entry named by
+/// its escaped path below the schemas (`Shape/$defs/Radius`), so entries of
+/// the same name under different schemas stay apart. Anything else — a ref
+/// left pointing into another file, or at a non-schema location — is kept
+/// whole, so it shows as unresolved rather than being mistaken for a schema
+/// with the same last segment.
+pub(crate) fn extract_ref_name(reference: &str) -> String {
+    let Some(pointer) = reference.strip_prefix('#') else {
+        return reference.to_string();
+    };
+    let tokens = resolver::pointer_tokens(pointer);
+    match tokens.as_slice() {
+        [components, schemas, name] if components == "components" && schemas == "schemas" => {
+            name.clone()
+        }
+        [definitions, name] if definitions == "definitions" => name.clone(),
+        [components, schemas, path @ ..]
+            if components == "components" && schemas == "schemas" && is_defs_path(path) =>
+        {
+            let tokens: Vec<String> = path.iter().map(|t| escape(t)).collect();
+            tokens.join("/")
         }
+        _ => reference.to_string(),
     }
+}
 
-    bail!("Cannot determine OpenAPI version from spec (expected 'openapi: 3.x' or 'swagger: 2.0')")
+/// `Name/$defs/Entry`, possibly nested further through `$defs`.
+fn is_defs_path(path: &[String]) -> bool {
+    path.len() >= 3 && path.len() % 2 == 1 && path.iter().skip(1).step_by(2).all(|t| t == "$defs")
 }
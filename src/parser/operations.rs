@@ -0,0 +1,559 @@
+use std::collections::HashSet;
+
+use serde_json::Value;
+
+use crate::breadcrumb::escape;
+use crate::parser::resolve_refs;
+use crate::spec::{Operation, Parameter, RequestBody, Response};
+use crate::tree::{NodeInfo, NodeKind, TreeNode};
+
+/// Converts one raw schema value into a tree node: `(name, schema, required)`.
+/// Each parser version passes in its own schema model.
+pub(crate) type SchemaToNode<'a> = &'a dyn Fn(String, &Value, bool) -> TreeNode;
+
+const METHODS: [&str; 8] = [
+    "get", "put", "post", "delete", "options", "head", "patch", "trace",
+];
+
+/// Media types tried first when a body offers several.
+const PREFERRED_MEDIA: [&str; 2] = ["application/json", "application/problem+json"];
+
+/// Read every path + method from `paths` (3.x layout — Swagger 2.0 is upgraded first).
+/// Parameter, request body and response `$ref`s are followed within the document.
+pub(crate) fn extract(raw: &Value, schema_node: SchemaToNode) -> Vec<Operation> {
+    let Some(paths) = raw.get("paths").and_then(Value::as_object) else {
+        return Vec::new();
+    };
+
+    let mut operations = Vec::new();
+    for (path, item) in paths {
+        let (item, item_pointer) = located(raw, item, format!("#/paths/{}", escape(path)));
+        let shared_params = located_list(
+            raw,
+            item.get("parameters"),
+            &format!("{item_pointer}/parameters"),
+        );
+
+        for method in METHODS {
+            let Some(op) = item.get(method) else {
+                continue;
+            };
+            let pointer = format!("{item_pointer}/{method}");
+            operations.push(operation(
+                raw,
+                path,
+                method,
+                op,
+                &pointer,
+                &shared_params,
+                schema_node,
+            ));
+        }
+    }
+    operations
+}
+
+fn operation(
+    raw: &Value,
+    path: &str,
+    method: &str,
+    op: &Value,
+    pointer: &str,
+    shared_params: &[(&Value, String)],
+    schema_node: SchemaToNode,
+) -> Operation {
+    // Path-level parameters apply unless the operation redefines the same name + location.
+    let own = located_list(raw, op.get("parameters"), &format!("{pointer}/parameters"));
+    let shared = shared_params.iter().filter(|(p, _)| {
+        !own.iter()
+            .any(|(o, _)| o.get("name") == p.get("name") && o.get("in") == p.get("in"))
+    });
+    let parameters = shared
+        .chain(&own)
+        .map(|(p, at)| parameter(raw, p, at, schema_node))
+        .collect();
+
+    let request_body = op.get("requestBody").map(|body| {
+        let (body, at) = located(raw, body, format!("{pointer}/requestBody"));
+        request_body(raw, body, at, schema_node)
+    });
+
+    let mut responses: Vec<Response> = op
+        .get("responses")
+        .and_then(Value::as_object)
+        .into_iter()
+        .flatten()
+        .filter(|(status, _)| !status.starts_with("x-"))
+        .map(|(status, resp)| {
+            let (resp, at) = located(raw, resp, format!("{pointer}/responses/{}", escape(status)));
+            response(raw, status, resp, at, schema_node)
+        })
+        .collect();
+    // Status codes in ascending order, `default` last.
+    responses.sort_by_key(|r| (r.status == "default", r.status.clone()));
+
+    Operation {
+        path: path.to_string(),
+        method: method.to_string(),
+        tags: op
+            .get("tags")
+            .and_then(Value::as_array)
+            .map(|t| {
+                t.iter()
+                    .filter_map(Value::as_str)
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default(),
+        operation_id: str_field(op, "operationId"),
+        summary: str_field(op, "summary"),
+        description: str_field(op, "description"),
+        deprecated: op
+            .get("deprecated")
+            .and_then(Value::as_bool)
+            .unwrap_or(false),
+        parameters,
+        request_body,
+        responses,
+    }
+}
+
+/// `param` is written at `pointer` (after following its `$ref`, if any).
+fn parameter(raw: &Value, param: &Value, pointer: &str, schema_node: SchemaToNode) -> Parameter {
+    let name = str_field(param, "name").unwrap_or_default();
+    let required = param
+        .get("required")
+        .and_then(Value::as_bool)
+        .unwrap_or(false);
+
+    // A parameter carries either `schema` or a single-entry `content` map.
+    let schema = match param.get("schema") {
+        Some(schema) => Some((schema, format!("{pointer}/schema"))),
+        None => preferred_media(param.get("content")).and_then(|(mime, m)| {
+            Some((
+                m.get("schema")?,
+                format!("{pointer}/content/{}/schema", escape(mime)),
+            ))
+        }),
+    };
+    let (mut node, pointer) = match schema {
+        Some((schema, at)) => {
+            let (schema, at) = schema_at(raw, schema, at);
+            (schema_node(name.clone(), schema, required), at)
+        }
+        None => (
+            placeholder_node(name, NodeKind::Unknown, required),
+            pointer.to_string(),
+        ),
+    };
+    if let Some(desc) = str_field(param, "description") {
+        node.info.description = Some(desc);
+    }
+
+    Parameter {
+        location: str_field(param, "in").unwrap_or_default(),
+        schema: node,
+        pointer,
+    }
+}
+
+fn request_body(
+    raw: &Value,
+    body: &Value,
+    pointer: String,
+    schema_node: SchemaToNode,
+) -> RequestBody {
+    let required = body
+        .get("required")
+        .and_then(Value::as_bool)
+        .unwrap_or(false);
+    let media = preferred_media(body.get("content"));
+    let schema = media.and_then(|(mime, m)| {
+        let at = format!("{pointer}/content/{}/schema", escape(mime));
+        Some(schema_at(raw, m.get("schema")?, at))
+    });
+    RequestBody {
+        content_type: media.map(|(mime, _)| mime.to_string()),
+        description: str_field(body, "description"),
+        required,
+        pointer: Some(schema.as_ref().map_or(pointer, |(_, at)| at.clone())),
+        schema: schema.map(|(s, _)| schema_node("requestBody".to_string(), s, required)),
+    }
+}
+
+fn response(
+    raw: &Value,
+    status: &str,
+    resp: &Value,
+    pointer: String,
+    schema_node: SchemaToNode,
+) -> Response {
+    let media = preferred_media(resp.get("content"));
+    let schema = media.and_then(|(mime, m)| {
+        let at = format!("{pointer}/content/{}/schema", escape(mime));
+        Some(schema_at(raw, m.get("schema")?, at))
+    });
+    Response {
+        status: status.to_string(),
+        description: str_field(resp, "description").unwrap_or_default(),
+        content_type: media.map(|(mime, _)| mime.to_string()),
+        pointer: schema.as_ref().map_or(pointer, |(_, at)| at.clone()),
+        schema: schema.map(|(s, _)| schema_node(status.to_string(), s, false)),
+        example: media.and_then(|(_, m)| media_example(raw, m)),
+    }
+}
+
+/// A media type's `example`, else the value of its first `examples` entry
+/// (which may be a `$ref` to `components/examples`).
+fn media_example(raw: &Value, media: &Value) -> Option<Value> {
+    if let Some(example) = media.get("example") {
+        return Some(example.clone());
+    }
+    let (_, first) = media.get("examples")?.as_object()?.iter().next()?;
+    deref(raw, first).get("value").cloned()
+}
+
+/// Pick the JSON-ish media type if offered, else the first one.
+fn preferred_media(content: Option<&Value>) -> Option<(&str, &Value)> {
+    let content = content?.as_object()?;
+    PREFERRED_MEDIA
+        .iter()
+        .find_map(|mime| content.get_key_value(*mime))
+        .or_else(|| content.iter().next())
+        .map(|(mime, media)| (mime.as_str(), media))
+}
+
+/// Follow local `$ref`s on parameters / bodies / responses (bounded, to survive loops).
+fn deref<'a>(raw: &'a Value, value: &'a Value) -> &'a Value {
+    located(raw, value, String::new()).0
+}
+
+/// `deref`, also moving `pointer` (where `value` is written) to each target.
+pub(crate) fn located<'a>(
+    raw: &'a Value,
+    mut value: &'a Value,
+    mut pointer: String,
+) -> (&'a Value, String) {
+    for _ in 0..8 {
+        let Some((fragment, target)) = value
+            .get("$ref")
+            .and_then(Value::as_str)
+            .and_then(|r| r.strip_prefix('#'))
+            .and_then(|fragment| Some((fragment, raw.pointer(fragment)?)))
+        else {
+            break;
+        };
+        pointer = format!("#{fragment}");
+        value = target;
+    }
+    (value, pointer)
+}
+
+/// Each entry of a `parameters` array written at `pointer`, dereferenced.
+fn located_list<'a>(
+    raw: &'a Value,
+    list: Option<&'a Value>,
+    pointer: &str,
+) -> Vec<(&'a Value, String)> {
+    list.and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .enumerate()
+        .map(|(i, p)| located(raw, p, format!("{pointer}/{i}")))
+        .collect()
+}
+
+/// Schema refs into `components/schemas` stay as `Ref` nodes (resolved later like
+/// any other); only refs elsewhere in the document are followed here.
+fn schema_at<'a>(raw: &'a Value, schema: &'a Value, pointer: String) -> (&'a Value, String) {
+    match schema.get("$ref").and_then(Value::as_str) {
+        Some(r) if !r.starts_with("#/components/schemas/") => located(raw, schema, pointer),
+        _ => (schema, pointer),
+    }
+}
+
+/// Populate `Ref` nodes in every parameter / body / response schema.
+pub(crate) fn resolve_operation_refs(
+    operations: &mut [Operation],
+    children_of: &dyn Fn(&str) -> Option<Vec<TreeNode>>,
+) {
+    for op in operations.iter_mut() {
+        for param in op.parameters.iter_mut() {
+            resolve_refs(
+                std::slice::from_mut(&mut param.schema),
+                children_of,
+                &mut HashSet::new(),
+            );
+        }
+        if let Some(schema) = op.request_body.as_mut().and_then(|b| b.schema.as_mut()) {
+            resolve_refs(
+                std::slice::from_mut(schema),
+                children_of,
+                &mut HashSet::new(),
+            );
+        }
+        for resp in op.responses.iter_mut() {
+            if let Some(schema) = resp.schema.as_mut() {
+                resolve_refs(
+                    std::slice::from_mut(schema),
+                    children_of,
+                    &mut HashSet::new(),
+                );
+            }
+        }
+    }
+}
+
+// ── operations tree ───────────────────────────────────────────────────────────
+
+/// Build the left-pane tree for the operations view:
+/// tag → `METHOD /path` → parameters / requestBody / responses.
+/// Untagged operations are grouped under `default`; an operation with several
+/// tags appears under each of them.
+pub(crate) fn build_tree(operations: &[Operation]) -> Vec<TreeNode> {
+    let mut tags: Vec<(String, Vec<TreeNode>)> = Vec::new();
+    for (index, op) in operations.iter().enumerate() {
+        let op_tags = if op.tags.is_empty() {
+            vec!["default".to_string()]
+        } else {
+            op.tags.clone()
+        };
+        for tag in op_tags {
+            let node = operation_node(index, op);
+            match tags.iter_mut().find(|(t, _)| *t == tag) {
+                Some((_, nodes)) => nodes.push(node),
+                None => tags.push((tag, vec![node])),
+            }
+        }
+    }
+
+    tags.into_iter()
+        .map(|(tag, children)| {
+            let mut node = placeholder_node(tag, NodeKind::Tag, false);
+            node.children = children;
+            node
+        })
+        .collect()
+}
+
+fn operation_node(index: usize, op: &Operation) -> TreeNode {
+    let mut children = Vec::new();
+
+    if !op.parameters.is_empty() {
+        let mut params = placeholder_node(
+            "parameters".to_string(),
+            NodeKind::Group("parameters".to_string()),
+            false,
+        );
+        params.children = op.parameters.iter().map(|p| p.schema.clone()).collect();
+        children.push(params);
+    }
+
+    if let Some(body) = &op.request_body {
+        let mut node = body.schema.clone().unwrap_or_else(|| {
+            placeholder_node(
+                "requestBody".to_string(),
+                NodeKind::NoContent,
+                body.required,
+            )
+        });
+        if let Some(desc) = &body.description {
+            node.info.description = Some(desc.clone());
+        }
+        children.push(node);
+    }
+
+    if !op.responses.is_empty() {
+        let mut responses = placeholder_node(
+            "responses".to_string(),
+            NodeKind::Group("responses".to_string()),
+            false,
+        );
+        responses.children = op
+            .responses
+            .iter()
+            .map(|resp| {
+                let mut node = resp.schema.clone().unwrap_or_else(|| {
+                    placeholder_node(resp.status.clone(), NodeKind::NoContent, false)
+                });
+                if !resp.description.is_empty() {
+                    node.info.description = Some(resp.description.clone());
+                }
+                node
+            })
+            .collect();
+        children.push(responses);
+    }
+
+    let mut node = placeholder_node(
+        format!("{} {}", op.method.to_uppercase(), op.path),
+        NodeKind::Operation(index),
+        false,
+    );
+    node.info.description = op.summary.clone().or_else(|| op.description.clone());
+    node.children = children;
+    node
+}
+
+fn placeholder_node(name: String, kind: NodeKind, required: bool) -> TreeNode {
+    TreeNode {
+        name,
+        info: NodeInfo {
+            kind,
+            format: None,
+            description: None,
+            required,
+            constraints: Vec::new(),
+            enum_values: Vec::new(),
+            example: None,
+            default_val: None,
+            nullable: false,
+            examples: Vec::new(),
+        },
+        children: Vec::new(),
+        expanded: false,
+    }
+}
+
+fn str_field(value: &Value, key: &str) -> Option<String> {
+    value.get(key).and_then(Value::as_str).map(str::to_string)
+}
+
+// ── tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn leaf(name: String, _schema: &Value, required: bool) -> TreeNode {
+        placeholder_node(name, NodeKind::Unknown, required)
+    }
+
+    #[test]
+    fn located_follows_local_ref_chains() {
+        let raw = json!({
+            "components": {
+                "parameters": {
+                    "Limit": { "$ref": "#/components/parameters/Size" },
+                    "Size": { "name": "size", "in": "query" }
+                }
+            }
+        });
+        let param = json!({ "$ref": "#/components/parameters/Limit" });
+        let (value, pointer) = located(&raw, &param, "#/paths/~1pets/parameters/0".to_string());
+        assert_eq!(value["name"], "size");
+        assert_eq!(pointer, "#/components/parameters/Size");
+
+        // Not a ref, or a ref out of the document: left where it is.
+        let inline = json!({ "name": "q" });
+        assert_eq!(located(&raw, &inline, "#/here".to_string()).1, "#/here");
+        let external = json!({ "$ref": "common.yaml#/components/parameters/Size" });
+        assert_eq!(located(&raw, &external, "#/here".to_string()).1, "#/here");
+    }
+
+    #[test]
+    fn located_gives_up_after_eight_hops() {
+        let mut chain = serde_json::Map::new();
+        for i in 0..10 {
+            chain.insert(
+                format!("P{i}"),
+                json!({ "$ref": format!("#/p/P{}", i + 1) }),
+            );
+        }
+        chain.insert("P10".to_string(), json!({ "name": "end" }));
+        let raw = json!({ "p": chain });
+        let start = json!({ "$ref": "#/p/P0" });
+        let (value, pointer) = located(&raw, &start, String::new());
+        assert_eq!(pointer, "#/p/P7");
+        assert_eq!(value["$ref"], "#/p/P8");
+
+        // A loop ends too.
+        let raw = json!({ "a": { "$ref": "#/b" }, "b": { "$ref": "#/a" } });
+        let start = json!({ "$ref": "#/a" });
+        let (_, pointer) = located(&raw, &start, String::new());
+        assert_eq!(pointer, "#/b");
+    }
+
+    #[test]
+    fn prefers_json_media_types() {
+        let content = json!({
+            "text/plain": {},
+            "application/problem+json": {},
+            "application/json": {}
+        });
+        assert_eq!(
+            preferred_media(Some(&content)).unwrap().0,
+            "application/json"
+        );
+        let content = json!({ "text/plain": {}, "application/problem+json": {} });
+        assert_eq!(
+            preferred_media(Some(&content)).unwrap().0,
+            "application/problem+json"
+        );
+        let content = json!({ "text/csv": {}, "text/plain": {} });
+        assert_eq!(preferred_media(Some(&content)).unwrap().0, "text/csv");
+        assert!(preferred_media(Some(&json!({}))).is_none());
+        assert!(preferred_media(None).is_none());
+    }
+
+    #[test]
+    fn orders_responses_by_status_with_default_last() {
+        let raw = json!({
+            "paths": {
+                "/pets": {
+                    "get": {
+                        "responses": {
+                            "default": { "description": "error" },
+                            "404": { "description": "missing" },
+                            "x-internal": { "description": "not a response" },
+                            "201": { "description": "created" },
+                            "200": { "description": "ok" }
+                        }
+                    }
+                }
+            }
+        });
+        let operations = extract(&raw, &leaf);
+        let statuses: Vec<&str> = operations[0]
+            .responses
+            .iter()
+            .map(|r| r.status.as_str())
+            .collect();
+        assert_eq!(statuses, ["200", "201", "404", "default"]);
+    }
+
+    #[test]
+    fn groups_operations_by_tag() {
+        let raw = json!({
+            "paths": {
+                "/pets": {
+                    "get": { "tags": ["pets", "admin"] },
+                    "post": { "tags": ["pets"] }
+                },
+                "/health": { "get": {} }
+            }
+        });
+        let operations = extract(&raw, &leaf);
+        let tree = build_tree(&operations);
+        let tags: Vec<(&str, Vec<&str>)> = tree
+            .iter()
+            .map(|tag| {
+                let ops = tag.children.iter().map(|op| op.name.as_str()).collect();
+                (tag.name.as_str(), ops)
+            })
+            .collect();
+        assert_eq!(
+            tags,
+            [
+                ("pets", vec!["GET /pets", "POST /pets"]),
+                ("admin", vec!["GET /pets"]),
+                ("default", vec!["GET /health"]),
+            ]
+        );
+        assert!(
+            tree.iter()
+                .all(|tag| matches!(tag.info.kind, NodeKind::Tag))
+        );
+    }
+}
@@ -0,0 +1,547 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde_json::{Map, Value};
+
+use crate::breadcrumb::escape;
+
+/// A spec document with its external `$ref`s pulled in, ready for the
+/// version parsers.
+pub struct Bundle {
+    pub document: Value,
+    pub content: String, // `document` as text: the original if nothing changed
+    /// Schemas imported from other files: name → `common.yaml#/components/schemas/Money`.
+    pub sources: HashMap<String, String>,
+    pub files: Vec<PathBuf>, // every other file read, canonical and sorted
+}
+
+/// A loaded document: `None` is the spec itself, `Some(path)` another file.
+type DocId = Option<PathBuf>;
+
+/// Where the files a spec refers to are read from: the disk, or a git
+/// revision (`speq diff --git`).
+pub trait Files {
+    /// The file's canonical path (the cache key), if it exists.
+    fn locate(&self, path: &Path) -> Option<PathBuf>;
+    /// The text of a file `locate` returned.
+    fn read(&self, path: &Path) -> Option<String>;
+}
+
+/// Files on disk.
+pub struct Disk;
+
+impl Files for Disk {
+    fn locate(&self, path: &Path) -> Option<PathBuf> {
+        fs::canonicalize(path).ok()
+    }
+
+    fn read(&self, path: &Path) -> Option<String> {
+        fs::read_to_string(path).ok()
+    }
+}
+
+/// Resolve the `$ref`s the version parsers cannot follow on their own.
+///
+/// - Refs into another file (`./common.yaml#/components/schemas/Money`) are
+///   resolved from the referring file's directory. A referenced schema is
+///   imported as a schema of this document (renamed if its name is taken) and
+///   the ref pointed at it; anything else (a response, a parameter, a schema
+///   nested inside another) is copied in place of the ref.
+/// - Local refs deeper than a top-level schema
+///   (`#/components/schemas/Pet/properties/id`) are copied in place too.
+///   Refs to `$defs` entries and to other components are left for the parsers.
+///
+/// `path` is the spec's own file; without one (stdin, URL) external refs are
+/// left as they are. So are refs that cannot be followed — missing files, bad
+/// pointers, or a copy that would contain itself.
+pub fn bundle(content: &str, path: Option<&Path>) -> Result<Bundle> {
+    bundle_from(content, path, &Disk)
+}
+
+/// `bundle`, reading the files `path` refers to from `files`.
+pub fn bundle_from(content: &str, path: Option<&Path>, files: &dyn Files) -> Result<Bundle> {
+    let mut document: Value =
+        serde_yaml::from_str(content).context("failed to parse spec as YAML/JSON")?;
+
+    let container = if document.get("swagger").is_some() {
+        vec!["definitions".to_string()]
+    } else {
+        vec!["components".to_string(), "schemas".to_string()]
+    };
+    let taken = schema_map(&document, &container)
+        .map(|schemas| schemas.keys().cloned().collect())
+        .unwrap_or_default();
+
+    let root_path = path.and_then(|p| files.locate(p));
+    let mut resolver = Resolver {
+        files,
+        base_dir: root_path
+            .as_deref()
+            .and_then(Path::parent)
+            .map(Path::to_path_buf),
+        root_path,
+        root: document.clone(),
+        container,
+        docs: HashMap::new(),
+        imported: HashMap::new(),
+        new_schemas: Vec::new(),
+        taken,
+        sources: HashMap::new(),
+        inlining: Vec::new(),
+        changed: false,
+    };
+    resolver.visit(&mut document, &None);
+    let mut files: Vec<PathBuf> = resolver.docs.keys().cloned().collect();
+    files.sort();
+
+    if !resolver.changed {
+        return Ok(Bundle {
+            document,
+            content: content.to_string(),
+            sources: HashMap::new(),
+            files,
+        });
+    }
+    if let Some(schemas) = schema_map_mut(&mut document, &resolver.container) {
+        for (name, schema) in resolver.new_schemas {
+            schemas.insert(name, schema);
+        }
+    }
+    Ok(Bundle {
+        content: serde_json::to_string(&document)?,
+        document,
+        sources: resolver.sources,
+        files,
+    })
+}
+
+struct Resolver<'a> {
+    files: &'a dyn Files,
+    root_path: Option<PathBuf>, // canonical path of the spec, if it is a file
+    base_dir: Option<PathBuf>,
+    root: Value, // the spec as read, for local pointer lookups
+    /// Where this version keeps schemas: `components/schemas` or `definitions`.
+    container: Vec<String>,
+    docs: HashMap<PathBuf, Value>, // every other file loaded so far
+    imported: HashMap<(PathBuf, String), String>, // (file, fragment) → schema name here
+    new_schemas: Vec<(String, Value)>,
+    taken: HashSet<String>, // schema names in use
+    sources: HashMap<String, String>,
+    inlining: Vec<(DocId, String)>, // refs being copied in, to stop on a ref to itself
+    changed: bool,
+}
+
+impl Resolver<'_> {
+    /// Walk `value` (part of document `doc`), resolving every `$ref` in it.
+    fn visit(&mut self, value: &mut Value, doc: &DocId) {
+        match value {
+            Value::Object(map) => {
+                if let Some(reference) = map.get("$ref").and_then(Value::as_str) {
+                    let reference = reference.to_string();
+                    if self.resolve(value, &reference, doc) {
+                        return;
+                    }
+                }
+                if let Value::Object(map) = value {
+                    for child in map.values_mut() {
+                        self.visit(child, doc);
+                    }
+                }
+            }
+            Value::Array(items) => {
+                for item in items {
+                    self.visit(item, doc);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Handle one `$ref` object. Returns true when `value` was replaced by a
+    /// copy of the target (which has then been visited already).
+    fn resolve(&mut self, value: &mut Value, reference: &str, doc: &DocId) -> bool {
+        let (file, fragment) = reference.split_once('#').unwrap_or((reference, ""));
+        let fragment = percent_decode(fragment);
+        let target_doc = if file.is_empty() {
+            doc.clone()
+        } else {
+            match self.load(file, doc) {
+                Some(target) => target,
+                None => return false,
+            }
+        };
+        let tokens = pointer_tokens(&fragment);
+
+        let Some(path) = target_doc else {
+            // A ref into the spec itself, possibly from another file.
+            let local = format!("#{fragment}");
+            if self.is_nested_schema(&tokens) {
+                return self.inline(value, None, &fragment);
+            }
+            if doc.is_some() {
+                value["$ref"] = Value::String(local);
+                self.changed = true;
+            }
+            return false;
+        };
+
+        let Some(target) = self.docs.get(&path).and_then(|d| d.pointer(&fragment)) else {
+            return false;
+        };
+        let is_schema = match tokens.as_slice() {
+            [] => looks_like_schema(target),
+            [components, schemas, _] => components == "components" && schemas == "schemas",
+            [definitions, _] => definitions == "definitions",
+            _ => false,
+        };
+        if is_schema {
+            let name = self.import(path, &fragment, &tokens);
+            let pointer: Vec<String> = self.container.iter().map(|t| escape(t)).collect();
+            value["$ref"] = Value::String(format!("#/{}/{}", pointer.join("/"), escape(&name)));
+            self.changed = true;
+            false
+        } else {
+            self.inline(value, Some(path), &fragment)
+        }
+    }
+
+    /// A local pointer into a schema below the top level, other than a `$defs`
+    /// entry (the 3.1 parser looks those up by name).
+    fn is_nested_schema(&self, tokens: &[String]) -> bool {
+        tokens.len() > self.container.len() + 1
+            && tokens.starts_with(&self.container)
+            && tokens[tokens.len() - 2] != "$defs"
+    }
+
+    /// Read `file` (relative to the document `from`) into the cache.
+    fn load(&mut self, file: &str, from: &DocId) -> Option<DocId> {
+        let dir = match from {
+            Some(path) => path.parent()?.to_path_buf(),
+            None => self.base_dir.clone()?,
+        };
+        let path = self.files.locate(&dir.join(percent_decode(file)))?;
+        if Some(&path) == self.root_path.as_ref() {
+            return Some(None);
+        }
+        if !self.docs.contains_key(&path) {
+            let text = self.files.read(&path)?;
+            let document: Value = serde_yaml::from_str(&text).ok()?;
+            self.docs.insert(path.clone(), document);
+        }
+        Some(Some(path))
+    }
+
+    /// Make the schema at `fragment` in `path` a schema of this document.
+    fn import(&mut self, path: PathBuf, fragment: &str, tokens: &[String]) -> String {
+        let key = (path.clone(), fragment.to_string());
+        if let Some(name) = self.imported.get(&key) {
+            return name.clone();
+        }
+        let stem = path
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let wanted = tokens.last().cloned().unwrap_or_else(|| stem.clone());
+        let mut name = wanted.clone();
+        if self.taken.contains(&name) {
+            name = format!("{stem}.{wanted}");
+        }
+        let mut n = 2;
+        while self.taken.contains(&name) {
+            name = format!("{stem}.{wanted}{n}");
+            n += 1;
+        }
+        self.taken.insert(name.clone());
+        self.imported.insert(key, name.clone());
+        self.sources
+            .insert(name.clone(), format!("{}#{fragment}", self.display(&path)));
+
+        // Registered first, so a ref back to this schema finds the name.
+        let mut schema = self.docs[&path]
+            .pointer(fragment)
+            .cloned()
+            .unwrap_or_default();
+        self.visit(&mut schema, &Some(path));
+        self.new_schemas.push((name.clone(), schema));
+        name
+    }
+
+    /// Replace the `$ref` object with a copy of its target. Sibling keys
+    /// (allowed next to `$ref` in 3.1) are kept and win over the target's.
+    fn inline(&mut self, value: &mut Value, doc: DocId, fragment: &str) -> bool {
+        let key = (doc.clone(), fragment.to_string());
+        if self.inlining.contains(&key) {
+            return false;
+        }
+        let source = match &doc {
+            Some(path) => &self.docs[path],
+            None => &self.root,
+        };
+        let Some(mut target) = source.pointer(fragment).cloned() else {
+            return false;
+        };
+
+        self.inlining.push(key);
+        self.visit(&mut target, &doc);
+        self.inlining.pop();
+
+        if let (Value::Object(siblings), Value::Object(copy)) = (&*value, &mut target) {
+            for (k, v) in siblings {
+                if k != "$ref" {
+                    copy.insert(k.clone(), v.clone());
+                }
+            }
+        }
+        *value = target;
+        self.changed = true;
+        true
+    }
+
+    /// `path` relative to the spec's directory, for display.
+    fn display(&self, path: &Path) -> String {
+        self.base_dir
+            .as_deref()
+            .and_then(|base| path.strip_prefix(base).ok())
+            .unwrap_or(path)
+            .display()
+            .to_string()
+    }
+}
+
+fn schema_map<'a>(document: &'a Value, container: &[String]) -> Option<&'a Map<String, Value>> {
+    container
+        .iter()
+        .try_fold(document, |v, key| v.get(key))?
+        .as_object()
+}
+
+fn schema_map_mut<'a>(
+    document: &'a mut Value,
+    container: &[String],
+) -> Option<&'a mut Map<String, Value>> {
+    let mut value = document;
+    for key in container {
+        let map = value.as_object_mut()?;
+        value = map
+            .entry(key.clone())
+            .or_insert_with(|| Value::Object(Map::new()));
+    }
+    value.as_object_mut()
+}
+
+/// A whole-file ref is imported as a schema only if the file looks like one.
+fn looks_like_schema(value: &Value) -> bool {
+    const KEYWORDS: [&str; 9] = [
+        "type",
+        "properties",
+        "items",
+        "allOf",
+        "oneOf",
+        "anyOf",
+        "enum",
+        "$ref",
+        "format",
+    ];
+    value
+        .as_object()
+        .is_some_and(|o| KEYWORDS.iter().any(|k| o.contains_key(*k)))
+}
+
+/// Split a JSON Pointer into unescaped reference tokens (RFC 6901).
+pub(crate) fn pointer_tokens(pointer: &str) -> Vec<String> {
+    pointer
+        .split('/')
+        .skip(1)
+        .map(|token| token.replace("~1", "/").replace("~0", "~"))
+        .collect()
+}
+
+/// `$ref`s are URI references, so `%7B` and friends may appear in them.
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|h| std::str::from_utf8(h).ok())
+            .and_then(|h| u8::from_str_radix(h, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(byte)) => {
+                out.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                out.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+// ── tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::{parse_bundle, parse_spec};
+    use crate::tree::{NodeKind, TreeNode};
+
+    fn find<'a>(nodes: &'a [TreeNode], name: &str) -> &'a TreeNode {
+        nodes
+            .iter()
+            .find(|n| n.name == name)
+            .unwrap_or_else(|| panic!("no node named {name}"))
+    }
+
+    /// Write `files` into a fresh temp directory and return it.
+    fn spec_dir(label: &str, files: &[(&str, &str)]) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("speq-refs-{label}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        for (name, text) in files {
+            let path = dir.join(name);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, text).unwrap();
+        }
+        dir
+    }
+
+    const COMMON: &str = r##"
+components:
+  schemas:
+    Money:
+      type: object
+      properties:
+        amount: { type: string }
+        currency: { $ref: "#/components/schemas/Currency" }
+    Currency:
+      type: string
+      description: ISO 4217 code
+  responses:
+    NotFound:
+      description: Not found
+      content:
+        application/json:
+          schema: { $ref: "../openapi.yaml#/components/schemas/Error" }
+"##;
+
+    const ROOT: &str = r##"
+openapi: "3.0.3"
+info: { title: Orders, version: "1" }
+paths:
+  /orders/{id}:
+    get:
+      responses:
+        "200":
+          description: ok
+          content:
+            application/json:
+              schema: { $ref: "#/components/schemas/Order" }
+        "404": { $ref: "shared/common.yaml#/components/responses/NotFound" }
+components:
+  schemas:
+    Error:
+      type: object
+      properties:
+        message: { type: string }
+    Currency:
+      type: integer
+    Order:
+      type: object
+      properties:
+        total: { $ref: "shared/common.yaml#/components/schemas/Money" }
+        id: { $ref: "#/components/schemas/Error/properties/message" }
+        note: { $ref: "./note.yaml" }
+"##;
+
+    #[test]
+    fn imports_external_schemas_and_inlines_other_components() {
+        let dir = spec_dir(
+            "external",
+            &[
+                ("api/openapi.yaml", ROOT),
+                ("api/shared/common.yaml", COMMON),
+                ("api/note.yaml", "type: string\ndescription: free text\n"),
+            ],
+        );
+        let path = dir.join("api/openapi.yaml");
+        let bundle = bundle(ROOT, Some(&path)).unwrap();
+        let spec = parse_bundle(&bundle).unwrap();
+
+        // Money comes in under its own name; its Currency clashes with ours.
+        let order = find(&spec.schema_nodes, "Order");
+        let total = find(&order.children, "total");
+        assert!(matches!(&total.info.kind, NodeKind::Ref(t) if t == "Money"));
+        let currency = find(&total.children, "currency");
+        assert!(matches!(&currency.info.kind, NodeKind::Ref(t) if t == "common.Currency"));
+        assert_eq!(
+            spec.schema_sources["common.Currency"],
+            "shared/common.yaml#/components/schemas/Currency"
+        );
+        assert!(!spec.schema_sources.contains_key("Order"));
+
+        // The detail pane names the file a node was read from.
+        let amount = find(&total.children, "amount");
+        let file = |chain: &[&TreeNode]| crate::breadcrumb::source_file(&spec, chain);
+        assert_eq!(file(&[order, total, amount]), Some("shared/common.yaml"));
+        assert_eq!(file(&[order]), None);
+
+        // Whole-file schema, named after the file.
+        let note = find(&order.children, "note");
+        assert!(matches!(&note.info.kind, NodeKind::Ref(t) if t == "note"));
+        assert_eq!(note.children.len(), 0);
+        assert_eq!(
+            find(&spec.schema_nodes, "note").info.description.as_deref(),
+            Some("free text")
+        );
+
+        // A local pointer below a top-level schema is copied, not mis-named.
+        let id = find(&order.children, "id");
+        assert!(matches!(id.info.kind, NodeKind::Str));
+
+        // The 404 response is inlined, and its ref back into the spec resolves.
+        let not_found = &spec.operations[0].responses[1];
+        assert_eq!(not_found.description, "Not found");
+        let schema = not_found.schema.as_ref().unwrap();
+        assert!(matches!(&schema.info.kind, NodeKind::Ref(t) if t == "Error"));
+        assert_eq!(schema.children.len(), 1);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn leaves_unresolvable_refs_and_single_files_alone() {
+        let spec = "openapi: 3.0.3\ninfo: {title: t, version: '1'}\npaths: {}\n";
+        let unchanged = bundle(spec, None).unwrap();
+        assert_eq!(unchanged.content, spec);
+
+        // No file to resolve against: the external ref stays, unresolved.
+        let external = r##"
+openapi: 3.0.3
+info: {title: t, version: '1'}
+paths: {}
+components:
+  schemas:
+    A:
+      type: object
+      properties:
+        b: { $ref: "other.yaml#/components/schemas/B" }
+"##;
+        let parsed = parse_spec(external).unwrap();
+        let b = &parsed.schema_nodes[0].children[0];
+        assert!(
+            matches!(&b.info.kind, NodeKind::Ref(t) if t == "other.yaml#/components/schemas/B")
+        );
+    }
+
+    #[test]
+    fn pointer_tokens_unescape() {
+        assert_eq!(
+            pointer_tokens("/paths/~1pets~1{id}/get/x~0y"),
+            vec!["paths", "/pets/{id}", "get", "x~y"]
+        );
+        assert_eq!(percent_decode("/paths/%7Bid%7D"), "/paths/{id}");
+    }
+}
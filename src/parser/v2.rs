@@ -0,0 +1,774 @@
+use std::collections::HashSet;
+
+use anyhow::{Context, Result};
+use openapiv3::OpenAPI;
+use serde_json::{Map, Value, json};
+
+use crate::breadcrumb::escape;
+use crate::parser::{operations, v3};
+use crate::spec::{LoadedSpec, Operation, SpecVersion};
+
+/// Parse a Swagger 2.0 document.
+///
+/// The document is upgraded to the OpenAPI 3.0 shape first (`definitions` →
+/// `components.schemas`, body parameters → `requestBody`, ...) so that it goes
+/// through exactly the same tree construction and `$ref` resolution as 3.x.
+pub fn parse(content: &str) -> Result<LoadedSpec> {
+    let raw: Value = serde_yaml::from_str(content).context("failed to parse Swagger 2.0 spec")?;
+
+    let swagger_version = raw
+        .get("swagger")
+        .and_then(Value::as_str)
+        .unwrap_or("2.0")
+        .to_string();
+
+    let upgraded = upgrade(&raw);
+    let api: OpenAPI =
+        serde_json::from_value(upgraded).context("failed to parse Swagger 2.0 spec")?;
+
+    let mut spec = v3::from_api(&api, SpecVersion::V20, swagger_version);
+    locate(&raw, &mut spec.operations);
+    Ok(spec)
+}
+
+/// Point each operation's parameters, body and responses back into the
+/// Swagger 2.0 document: the upgrade copied path-level parameters into every
+/// operation and turned body parameters into request bodies.
+fn locate(raw: &Value, operations: &mut [Operation]) {
+    for op in operations {
+        let Some(item) = raw.get("paths").and_then(|paths| paths.get(&op.path)) else {
+            continue;
+        };
+        let item_pointer = format!("#/paths/{}", escape(&op.path));
+        let mut params = Vec::new();
+        for (param, at) in operation_params(raw, item, &op.method) {
+            let (param, at) = operations::located(raw, param, format!("{item_pointer}/{at}"));
+            match param.get("in").and_then(Value::as_str) {
+                Some("body") => {
+                    if let Some(body) = op.request_body.as_mut() {
+                        body.pointer = Some(format!("{at}/schema"));
+                    }
+                }
+                Some("formData") => {
+                    if let Some(body) = op.request_body.as_mut() {
+                        body.pointer = None;
+                    }
+                }
+                // Everything but `in: body` keeps its schema keywords inline.
+                _ => params.push(at),
+            }
+        }
+        for (param, at) in op.parameters.iter_mut().zip(params) {
+            param.pointer = at;
+        }
+
+        let op_pointer = format!("{item_pointer}/{}", op.method);
+        for resp in &mut op.responses {
+            let Some(raw_resp) = item
+                .get(&op.method)
+                .and_then(|o| o.get("responses")?.get(&resp.status))
+            else {
+                continue;
+            };
+            let at = format!("{op_pointer}/responses/{}", escape(&resp.status));
+            let (_, at) = operations::located(raw, raw_resp, at);
+            resp.pointer = if resp.schema.is_some() {
+                format!("{at}/schema")
+            } else {
+                at
+            };
+        }
+    }
+}
+
+// ── document upgrade ──────────────────────────────────────────────────────────
+
+const DEFAULT_MIME: &str = "application/json";
+const METHODS: [&str; 7] = ["get", "put", "post", "delete", "options", "head", "patch"];
+
+/// Document-wide facts needed while rewriting individual objects.
+struct Upgrader<'a> {
+    raw: &'a Value,
+    consumes: Vec<String>,
+    produces: Vec<String>,
+    /// Names of global parameters that are `in: body` (these become requestBodies).
+    body_params: HashSet<String>,
+}
+
+impl Upgrader<'_> {
+    /// Map a Swagger 2.0 `$ref` onto its OpenAPI 3.0 location.
+    fn rewrite_ref(&self, reference: &str) -> String {
+        if let Some(name) = reference.strip_prefix("#/definitions/") {
+            format!("#/components/schemas/{name}")
+        } else if let Some(name) = reference.strip_prefix("#/parameters/") {
+            if self.body_params.contains(name) {
+                format!("#/components/requestBodies/{name}")
+            } else {
+                format!("#/components/parameters/{name}")
+            }
+        } else if let Some(name) = reference.strip_prefix("#/responses/") {
+            format!("#/components/responses/{name}")
+        } else {
+            reference.to_string()
+        }
+    }
+
+    /// Look up a global parameter by `$ref` (used to inline `formData` parameters).
+    fn global_param(&self, reference: &str) -> Option<&Value> {
+        let name = reference.strip_prefix("#/parameters/")?;
+        self.raw.get("parameters")?.get(name)
+    }
+}
+
+/// Rewrite a whole Swagger 2.0 document into an equivalent OpenAPI 3.0 document.
+fn upgrade(raw: &Value) -> Value {
+    let global_params = raw.get("parameters").and_then(Value::as_object);
+    let body_params = global_params
+        .map(|params| {
+            params
+                .iter()
+                .filter(|(_, p)| p.get("in").and_then(Value::as_str) == Some("body"))
+                .map(|(name, _)| name.clone())
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let ctx = Upgrader {
+        raw,
+        consumes: string_list(raw.get("consumes")),
+        produces: string_list(raw.get("produces")),
+        body_params,
+    };
+
+    let mut out = Map::new();
+    out.insert("openapi".to_string(), json!("3.0.3"));
+    out.insert(
+        "info".to_string(),
+        raw.get("info").cloned().unwrap_or_else(|| json!({})),
+    );
+
+    let servers = servers(raw);
+    if !servers.is_empty() {
+        out.insert("servers".to_string(), Value::Array(servers));
+    }
+    for key in ["tags", "security", "externalDocs"] {
+        if let Some(v) = raw.get(key) {
+            out.insert(key.to_string(), v.clone());
+        }
+    }
+
+    // components
+    let mut components = Map::new();
+    if let Some(defs) = raw.get("definitions").and_then(Value::as_object) {
+        let schemas = defs
+            .iter()
+            .map(|(name, schema)| (name.clone(), convert_schema(schema, &ctx)))
+            .collect();
+        components.insert("schemas".to_string(), Value::Object(schemas));
+    }
+    if let Some(params) = global_params {
+        let mut parameters = Map::new();
+        let mut request_bodies = Map::new();
+        for (name, param) in params {
+            match param.get("in").and_then(Value::as_str) {
+                Some("body") => {
+                    request_bodies.insert(name.clone(), body_param(param, &ctx.consumes, &ctx));
+                }
+                // Global formData parameters are inlined into each operation's form body.
+                Some("formData") => {}
+                _ => {
+                    parameters.insert(name.clone(), convert_parameter(param, &ctx));
+                }
+            }
+        }
+        if !parameters.is_empty() {
+            components.insert("parameters".to_string(), Value::Object(parameters));
+        }
+        if !request_bodies.is_empty() {
+            components.insert("requestBodies".to_string(), Value::Object(request_bodies));
+        }
+    }
+    if let Some(responses) = raw.get("responses").and_then(Value::as_object) {
+        let responses = responses
+            .iter()
+            .map(|(name, resp)| (name.clone(), convert_response(resp, &ctx.produces, &ctx)))
+            .collect();
+        components.insert("responses".to_string(), Value::Object(responses));
+    }
+    if let Some(defs) = raw.get("securityDefinitions").and_then(Value::as_object) {
+        let schemes = defs
+            .iter()
+            .map(|(name, def)| (name.clone(), convert_security_scheme(def)))
+            .collect();
+        components.insert("securitySchemes".to_string(), Value::Object(schemes));
+    }
+    out.insert("components".to_string(), Value::Object(components));
+
+    // paths
+    let mut paths = Map::new();
+    if let Some(raw_paths) = raw.get("paths").and_then(Value::as_object) {
+        for (path, item) in raw_paths {
+            if path.starts_with("x-") {
+                continue;
+            }
+            paths.insert(path.clone(), convert_path_item(item, &ctx));
+        }
+    }
+    out.insert("paths".to_string(), Value::Object(paths));
+
+    Value::Object(out)
+}
+
+fn servers(raw: &Value) -> Vec<Value> {
+    let base_path = raw.get("basePath").and_then(Value::as_str).unwrap_or("");
+    let Some(host) = raw.get("host").and_then(Value::as_str) else {
+        return if base_path.is_empty() {
+            Vec::new()
+        } else {
+            vec![json!({ "url": base_path })]
+        };
+    };
+
+    let mut schemes = string_list(raw.get("schemes"));
+    if schemes.is_empty() {
+        schemes.push("https".to_string());
+    }
+    schemes
+        .iter()
+        .map(|scheme| json!({ "url": format!("{scheme}://{host}{base_path}") }))
+        .collect()
+}
+
+fn convert_path_item(item: &Value, ctx: &Upgrader) -> Value {
+    let mut out = Map::new();
+    for (key, val) in item.as_object().into_iter().flatten() {
+        if METHODS.contains(&key.as_str()) {
+            let params = operation_params(ctx.raw, item, key)
+                .into_iter()
+                .map(|(p, _)| p)
+                .collect();
+            out.insert(key.clone(), convert_operation(val, params, ctx));
+        } else if key != "parameters" {
+            out.insert(key.clone(), val.clone());
+        }
+    }
+    Value::Object(out)
+}
+
+/// The parameters of `item`'s `method`: the path-level ones first, unless the
+/// operation redefines them by name + location (either side may be a `$ref`
+/// into `raw`), then its own. Each comes with where it is written, relative
+/// to the path item.
+fn operation_params<'a>(raw: &'a Value, item: &'a Value, method: &str) -> Vec<(&'a Value, String)> {
+    let list = |value: Option<&'a Value>| {
+        value
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .enumerate()
+    };
+    let own_params: Vec<(usize, &Value)> =
+        list(item.get(method).and_then(|op| op.get("parameters"))).collect();
+    let resolved = |p: &'a Value| operations::located(raw, p, String::new()).0;
+    let overridden = |p: &'a Value| {
+        let p = resolved(p);
+        own_params.iter().any(|&(_, o)| {
+            let o = resolved(o);
+            o.get("name") == p.get("name") && o.get("in") == p.get("in")
+        })
+    };
+    list(item.get("parameters"))
+        .filter(|(_, p)| !overridden(p))
+        .map(|(i, p)| (p, format!("parameters/{i}")))
+        .chain(
+            own_params
+                .iter()
+                .map(|&(i, p)| (p, format!("{method}/parameters/{i}"))),
+        )
+        .collect()
+}
+
+fn convert_operation(op: &Value, all_params: Vec<&Value>, ctx: &Upgrader) -> Value {
+    let consumes = op
+        .get("consumes")
+        .map(|v| string_list(Some(v)))
+        .unwrap_or_else(|| ctx.consumes.clone());
+    let produces = op
+        .get("produces")
+        .map(|v| string_list(Some(v)))
+        .unwrap_or_else(|| ctx.produces.clone());
+
+    let mut out = Map::new();
+    for (key, val) in op.as_object().into_iter().flatten() {
+        match key.as_str() {
+            "parameters" | "responses" | "consumes" | "produces" | "schemes" => {}
+            _ => {
+                out.insert(key.clone(), val.clone());
+            }
+        }
+    }
+
+    let mut parameters = Vec::new();
+    let mut form_fields: Vec<&Value> = Vec::new();
+    for param in all_params {
+        if let Some(reference) = param.get("$ref").and_then(Value::as_str) {
+            match ctx.global_param(reference) {
+                Some(global) if global.get("in").and_then(Value::as_str) == Some("body") => {
+                    out.insert(
+                        "requestBody".to_string(),
+                        json!({ "$ref": ctx.rewrite_ref(reference) }),
+                    );
+                }
+                Some(global) if global.get("in").and_then(Value::as_str) == Some("formData") => {
+                    form_fields.push(global);
+                }
+                _ => parameters.push(json!({ "$ref": ctx.rewrite_ref(reference) })),
+            }
+            continue;
+        }
+        match param.get("in").and_then(Value::as_str) {
+            Some("body") => {
+                out.insert("requestBody".to_string(), body_param(param, &consumes, ctx));
+            }
+            Some("formData") => form_fields.push(param),
+            _ => parameters.push(convert_parameter(param, ctx)),
+        }
+    }
+    if !parameters.is_empty() {
+        out.insert("parameters".to_string(), Value::Array(parameters));
+    }
+    if !form_fields.is_empty() {
+        out.insert(
+            "requestBody".to_string(),
+            form_body(&form_fields, &consumes, ctx),
+        );
+    }
+
+    let mut responses = Map::new();
+    for (status, resp) in op
+        .get("responses")
+        .and_then(Value::as_object)
+        .into_iter()
+        .flatten()
+    {
+        if status.starts_with("x-") {
+            continue;
+        }
+        responses.insert(status.clone(), convert_response(resp, &produces, ctx));
+    }
+    out.insert("responses".to_string(), Value::Object(responses));
+
+    Value::Object(out)
+}
+
+/// Parameter-level keys that stay on the parameter; everything else moves into `schema`.
+const PARAM_KEYS: [&str; 5] = ["name", "in", "description", "required", "allowEmptyValue"];
+
+fn convert_parameter(param: &Value, ctx: &Upgrader) -> Value {
+    if let Some(reference) = param.get("$ref").and_then(Value::as_str) {
+        return json!({ "$ref": ctx.rewrite_ref(reference) });
+    }
+
+    let mut out = Map::new();
+    let mut schema = Map::new();
+    for (key, val) in param.as_object().into_iter().flatten() {
+        if PARAM_KEYS.contains(&key.as_str()) || key.starts_with("x-") {
+            out.insert(key.clone(), val.clone());
+        } else if key != "collectionFormat" {
+            schema.insert(key.clone(), val.clone());
+        }
+    }
+    // Path parameters are always required in 3.0.
+    if param.get("in").and_then(Value::as_str) == Some("path") {
+        out.insert("required".to_string(), json!(true));
+    }
+    out.insert(
+        "schema".to_string(),
+        convert_schema(&Value::Object(schema), ctx),
+    );
+    Value::Object(out)
+}
+
+fn body_param(param: &Value, consumes: &[String], ctx: &Upgrader) -> Value {
+    let schema = param
+        .get("schema")
+        .map(|s| convert_schema(s, ctx))
+        .unwrap_or_else(|| json!({}));
+
+    let mut out = Map::new();
+    if let Some(desc) = param.get("description") {
+        out.insert("description".to_string(), desc.clone());
+    }
+    if let Some(required) = param.get("required") {
+        out.insert("required".to_string(), required.clone());
+    }
+    out.insert("content".to_string(), content_for(consumes, &schema, None));
+    Value::Object(out)
+}
+
+/// Fold `in: formData` parameters into a single object-typed request body.
+fn form_body(fields: &[&Value], consumes: &[String], ctx: &Upgrader) -> Value {
+    let mut properties = Map::new();
+    let mut required = Vec::new();
+    let mut has_file = false;
+    for field in fields {
+        let Some(name) = field.get("name").and_then(Value::as_str) else {
+            continue;
+        };
+        has_file |= field.get("type").and_then(Value::as_str) == Some("file");
+        if field.get("required").and_then(Value::as_bool) == Some(true) {
+            required.push(json!(name));
+        }
+        let converted = convert_parameter(field, ctx);
+        let mut schema = converted
+            .get("schema")
+            .cloned()
+            .unwrap_or_else(|| json!({}));
+        if let (Some(desc), Some(obj)) = (field.get("description"), schema.as_object_mut()) {
+            obj.insert("description".to_string(), desc.clone());
+        }
+        properties.insert(name.to_string(), schema);
+    }
+
+    let mut schema = Map::new();
+    schema.insert("type".to_string(), json!("object"));
+    schema.insert("properties".to_string(), Value::Object(properties));
+    if !required.is_empty() {
+        schema.insert("required".to_string(), Value::Array(required));
+    }
+
+    let mime = if has_file || consumes.iter().any(|c| c == "multipart/form-data") {
+        "multipart/form-data"
+    } else {
+        "application/x-www-form-urlencoded"
+    };
+    json!({ "content": { mime: { "schema": Value::Object(schema) } } })
+}
+
+fn convert_response(resp: &Value, produces: &[String], ctx: &Upgrader) -> Value {
+    if let Some(reference) = resp.get("$ref").and_then(Value::as_str) {
+        return json!({ "$ref": ctx.rewrite_ref(reference) });
+    }
+
+    let mut out = Map::new();
+    out.insert(
+        "description".to_string(),
+        resp.get("description")
+            .cloned()
+            .unwrap_or_else(|| json!("")),
+    );
+    if let Some(headers) = resp.get("headers").and_then(Value::as_object) {
+        let headers = headers
+            .iter()
+            .map(|(name, header)| {
+                let mut h = Map::new();
+                if let Some(desc) = header.get("description") {
+                    h.insert("description".to_string(), desc.clone());
+                }
+                let schema: Map<String, Value> = header
+                    .as_object()
+                    .into_iter()
+                    .flatten()
+                    .filter(|(k, _)| {
+                        k.as_str() != "description" && k.as_str() != "collectionFormat"
+                    })
+                    .map(|(k, v)| (k.clone(), v.clone()))
+                    .collect();
+                h.insert(
+                    "schema".to_string(),
+                    convert_schema(&Value::Object(schema), ctx),
+                );
+                (name.clone(), Value::Object(h))
+            })
+            .collect();
+        out.insert("headers".to_string(), Value::Object(headers));
+    }
+    if let Some(schema) = resp.get("schema") {
+        let schema = convert_schema(schema, ctx);
+        out.insert(
+            "content".to_string(),
+            content_for(produces, &schema, resp.get("examples")),
+        );
+    }
+    Value::Object(out)
+}
+
+/// Build an OpenAPI 3.0 `content` map: one media type entry per mime type.
+fn content_for(mimes: &[String], schema: &Value, examples: Option<&Value>) -> Value {
+    let mut content = Map::new();
+    let default = [DEFAULT_MIME.to_string()];
+    let mimes = if mimes.is_empty() {
+        &default[..]
+    } else {
+        mimes
+    };
+    for mime in mimes {
+        let mut media = Map::new();
+        media.insert("schema".to_string(), schema.clone());
+        if let Some(example) = examples.and_then(|e| e.get(mime)) {
+            media.insert("example".to_string(), example.clone());
+        }
+        content.insert(mime.clone(), Value::Object(media));
+    }
+    Value::Object(content)
+}
+
+fn convert_security_scheme(def: &Value) -> Value {
+    let description = def.get("description").cloned();
+    let mut out = match def.get("type").and_then(Value::as_str) {
+        Some("basic") => json!({ "type": "http", "scheme": "basic" }),
+        Some("apiKey") => json!({
+            "type": "apiKey",
+            "name": def.get("name").cloned().unwrap_or_else(|| json!("")),
+            "in": def.get("in").cloned().unwrap_or_else(|| json!("header")),
+        }),
+        Some("oauth2") => {
+            let scopes = def.get("scopes").cloned().unwrap_or_else(|| json!({}));
+            let auth_url = def
+                .get("authorizationUrl")
+                .cloned()
+                .unwrap_or_else(|| json!(""));
+            let token_url = def.get("tokenUrl").cloned().unwrap_or_else(|| json!(""));
+            let flows = match def.get("flow").and_then(Value::as_str) {
+                Some("implicit") => {
+                    json!({ "implicit": { "authorizationUrl": auth_url, "scopes": scopes } })
+                }
+                Some("password") => {
+                    json!({ "password": { "tokenUrl": token_url, "scopes": scopes } })
+                }
+                Some("application") => {
+                    json!({ "clientCredentials": { "tokenUrl": token_url, "scopes": scopes } })
+                }
+                _ => json!({ "authorizationCode": {
+                    "authorizationUrl": auth_url,
+                    "tokenUrl": token_url,
+                    "scopes": scopes,
+                } }),
+            };
+            json!({ "type": "oauth2", "flows": flows })
+        }
+        _ => def.clone(),
+    };
+    if let (Some(desc), Some(obj)) = (description, out.as_object_mut()) {
+        obj.insert("description".to_string(), desc);
+    }
+    out
+}
+
+/// Rewrite the few places where a Swagger 2.0 schema differs from a 3.0 one:
+/// `$ref` targets, string discriminators, `type: file` and `x-nullable`.
+fn convert_schema(schema: &Value, ctx: &Upgrader) -> Value {
+    let Some(obj) = schema.as_object() else {
+        return schema.clone();
+    };
+
+    let mut out = Map::new();
+    for (key, val) in obj {
+        let converted = match key.as_str() {
+            "$ref" => json!(ctx.rewrite_ref(val.as_str().unwrap_or_default())),
+            "properties" => Value::Object(
+                val.as_object()
+                    .into_iter()
+                    .flatten()
+                    .map(|(name, prop)| (name.clone(), convert_schema(prop, ctx)))
+                    .collect(),
+            ),
+            "items" | "additionalProperties" | "not" => convert_schema(val, ctx),
+            "allOf" | "anyOf" | "oneOf" => Value::Array(
+                val.as_array()
+                    .into_iter()
+                    .flatten()
+                    .map(|s| convert_schema(s, ctx))
+                    .collect(),
+            ),
+            "discriminator" => match val {
+                Value::String(prop) => json!({ "propertyName": prop }),
+                other => other.clone(),
+            },
+            "type" if val == "file" => {
+                out.insert("format".to_string(), json!("binary"));
+                json!("string")
+            }
+            "x-nullable" => {
+                out.insert("nullable".to_string(), val.clone());
+                continue;
+            }
+            _ => val.clone(),
+        };
+        out.insert(key.clone(), converted);
+    }
+    Value::Object(out)
+}
+
+fn string_list(value: Option<&Value>) -> Vec<String> {
+    value
+        .and_then(Value::as_array)
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(Value::as_str)
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+// ── tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tree::NodeKind;
+
+    const PETSTORE_V2: &str = include_str!("../../fixtures/petstore-v2.yaml");
+
+    #[test]
+    fn parses_definitions_as_schemas() {
+        let spec = parse(PETSTORE_V2).expect("should parse swagger fixture");
+        assert_eq!(spec.title, "Petstore");
+        assert_eq!(spec.schema_names, vec!["Error", "NewPet", "Pet", "Pets"]);
+    }
+
+    #[test]
+    fn detects_swagger_20() {
+        let spec = parse(PETSTORE_V2).expect("should parse swagger fixture");
+        assert_eq!(spec.version, SpecVersion::V20);
+        assert_eq!(spec.openapi_version, "2.0");
+    }
+
+    #[test]
+    fn definitions_refs_resolved_inline() {
+        let spec = parse(PETSTORE_V2).expect("should parse swagger fixture");
+        let pets = spec.schema_nodes.iter().find(|n| n.name == "Pets").unwrap();
+        let items = &pets.children[0];
+        assert!(matches!(&items.info.kind, NodeKind::Ref(t) if t == "Pet"));
+        let names: Vec<&str> = items.children.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["id", "name", "tag"]);
+    }
+
+    #[test]
+    fn operations_survive_the_upgrade() {
+        let spec = parse(PETSTORE_V2).expect("should parse swagger fixture");
+        let show = spec
+            .operations
+            .iter()
+            .find(|op| op.operation_id.as_deref() == Some("showPetById"))
+            .unwrap();
+        // Path-level `$ref` parameter is inherited by the operation.
+        assert_eq!(show.parameters[0].schema.name, "petId");
+        assert_eq!(show.parameters[0].location, "path");
+        // `$ref: '#/responses/NotFound'` is followed to its schema.
+        let not_found = show.responses.iter().find(|r| r.status == "404").unwrap();
+        assert_eq!(not_found.description, "The requested pet does not exist");
+        let schema = not_found.schema.as_ref().unwrap();
+        assert!(matches!(&schema.info.kind, NodeKind::Ref(t) if t == "Error"));
+    }
+
+    #[test]
+    fn upgrades_parameters_responses_and_security() {
+        let raw: Value = serde_yaml::from_str(PETSTORE_V2).unwrap();
+        let doc = upgrade(&raw);
+
+        assert_eq!(doc["servers"][0]["url"], "https://petstore.example.com/v1");
+        assert_eq!(
+            doc["components"]["parameters"]["limitParam"]["schema"]["format"],
+            "int32"
+        );
+        let not_found = &doc["components"]["responses"]["NotFound"];
+        assert_eq!(
+            not_found["content"]["application/json"]["schema"]["$ref"],
+            "#/components/schemas/Error"
+        );
+        assert_eq!(
+            doc["components"]["securitySchemes"]["api_key"]["type"],
+            "apiKey"
+        );
+        let oauth = &doc["components"]["securitySchemes"]["petstore_auth"];
+        assert_eq!(
+            oauth["flows"]["implicit"]["authorizationUrl"],
+            "https://petstore.example.com/oauth/dialog"
+        );
+
+        let create = &doc["paths"]["/pets"]["post"];
+        assert_eq!(
+            create["requestBody"]["content"]["application/json"]["schema"]["$ref"],
+            "#/components/schemas/NewPet"
+        );
+        assert_eq!(
+            create["responses"]["404"]["$ref"],
+            "#/components/responses/NotFound"
+        );
+    }
+
+    #[test]
+    fn operation_parameters_override_path_level_refs() {
+        let spec = parse(
+            r##"
+swagger: "2.0"
+info: { title: Shop, version: "1" }
+parameters:
+  Limit: { name: limit, in: query, type: integer }
+  Tenant: { name: tenant, in: header, type: string }
+paths:
+  /orders:
+    parameters:
+      - $ref: "#/parameters/Limit"
+      - { name: tenant, in: header, type: integer }
+    get:
+      parameters:
+        - { name: limit, in: query, type: string, maxLength: 3 }
+        - $ref: "#/parameters/Tenant"
+      responses:
+        "200": { description: ok }
+"##,
+        )
+        .unwrap();
+        let parameters = &spec.operations[0].parameters;
+        let params: Vec<(&str, &str, String)> = parameters
+            .iter()
+            .map(|p| {
+                (
+                    p.schema.name.as_str(),
+                    p.location.as_str(),
+                    p.schema.type_label(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            params,
+            [
+                ("limit", "query", "string".to_string()),
+                ("tenant", "header", "string".to_string()),
+            ]
+        );
+        let pointers: Vec<&str> = parameters.iter().map(|p| p.pointer.as_str()).collect();
+        assert_eq!(
+            pointers,
+            ["#/paths/~1orders/get/parameters/0", "#/parameters/Tenant"]
+        );
+    }
+
+    #[test]
+    fn converts_swagger_only_schema_keywords() {
+        let raw = json!({});
+        let ctx = Upgrader {
+            raw: &raw,
+            consumes: Vec::new(),
+            produces: Vec::new(),
+            body_params: HashSet::new(),
+        };
+        let schema = json!({
+            "type": "object",
+            "discriminator": "petType",
+            "properties": {
+                "photo": { "type": "file" },
+                "nickname": { "type": "string", "x-nullable": true },
+            },
+        });
+        let converted = convert_schema(&schema, &ctx);
+        assert_eq!(converted["discriminator"]["propertyName"], "petType");
+        assert_eq!(converted["properties"]["photo"]["type"], "string");
+        assert_eq!(converted["properties"]["photo"]["format"], "binary");
+        assert_eq!(converted["properties"]["nickname"]["nullable"], true);
+    }
+}
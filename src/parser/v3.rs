@@ -2,11 +2,15 @@ use std::collections::{HashMap, HashSet};
 
 use anyhow::{Context, Result};
 use openapiv3::{
-    IntegerFormat, NumberFormat, OpenAPI, ReferenceOr, Schema, SchemaKind, StringFormat, Type,
-    VariantOrUnknownOrEmpty,
+    AdditionalProperties, IntegerFormat, NumberFormat, ObjectType, OpenAPI, ReferenceOr, Schema,
+    SchemaKind, StringFormat, Type, VariantOrUnknownOrEmpty,
 };
+use serde_json::Value;
 
-use crate::spec::{LoadedSpec, SpecVersion};
+use crate::diagnostics::Diagnostics;
+use crate::graph::Graph;
+use crate::parser::{extract_ref_name, operations, resolve_refs};
+use crate::spec::{LoadedSpec, Operation, SpecVersion};
 use crate::tree::{NodeInfo, NodeKind, TreeNode};
 
 pub fn parse(content: &str) -> Result<LoadedSpec> {
@@ -17,9 +21,15 @@ pub fn parse(content: &str) -> Result<LoadedSpec> {
     } else {
         SpecVersion::V30
     };
+    let spec_version_str = api.openapi.clone();
+
+    Ok(from_api(&api, version, spec_version_str))
+}
 
+/// Build a `LoadedSpec` from an already-deserialised 3.x document.
+/// The Swagger 2.0 parser upgrades its input to this model and lands here too.
+pub(crate) fn from_api(api: &OpenAPI, version: SpecVersion, openapi_version: String) -> LoadedSpec {
     let title = api.info.title.clone();
-    let spec_version_str = api.openapi.clone();
 
     // Extract top-level schema names (sorted) — kept for tests
     let schema_names: Vec<String> = api
@@ -32,92 +42,68 @@ pub fn parse(content: &str) -> Result<LoadedSpec> {
         })
         .unwrap_or_default();
 
-    let schema_nodes = build_tree(&api);
+    let (schema_nodes, operations) = build_tree(api);
+    let operation_nodes = operations::build_tree(&operations);
 
-    Ok(LoadedSpec {
+    LoadedSpec {
         title,
-        openapi_version: spec_version_str,
+        openapi_version,
         version,
         schema_names,
         schema_nodes,
-    })
+        operations,
+        operation_nodes,
+        schema_sources: HashMap::new(),
+        diagnostics: Diagnostics::default(),
+        graph: Graph::default(),
+        usages: Vec::new(),
+    }
 }
 
 // ── tree construction ─────────────────────────────────────────────────────────
 
-fn build_tree(api: &OpenAPI) -> Vec<TreeNode> {
-    let Some(components) = &api.components else {
-        return Vec::new();
-    };
-
-    // Pass 1: build the tree (Ref nodes are leaves with no children yet).
-    let mut schemas: Vec<(&String, &ReferenceOr<Schema>)> = components.schemas.iter().collect();
+fn build_tree(api: &OpenAPI) -> (Vec<TreeNode>, Vec<Operation>) {
+    // Pass 1: build the trees (Ref nodes are leaves with no children yet).
+    let mut schemas: Vec<(&String, &ReferenceOr<Schema>)> = api
+        .components
+        .iter()
+        .flat_map(|c| c.schemas.iter())
+        .collect();
     schemas.sort_by_key(|(k, _)| k.as_str());
     let mut roots: Vec<TreeNode> = schemas
         .into_iter()
         .map(|(name, schema_ref)| schema_ref_to_node(name.clone(), schema_ref, false))
         .collect();
 
+    // Operations are read from the serialised document so 3.0 and 3.1 share one extractor.
+    let raw = serde_json::to_value(api).unwrap_or_default();
+    let mut operations = operations::extract(&raw, &schema_value_to_node);
+
     // Pass 2: populate children of Ref nodes from their target schemas.
     // A HashMap<name → &Schema> makes lookups O(1).
-    let schema_map: HashMap<&str, &Schema> = components
-        .schemas
+    let schema_map: HashMap<&str, &Schema> = api
+        .components
         .iter()
+        .flat_map(|c| c.schemas.iter())
         .filter_map(|(k, v)| match v {
             ReferenceOr::Item(s) => Some((k.as_str(), s)),
             _ => None,
         })
         .collect();
 
+    let children_of = |target: &str| schema_map.get(target).map(|s| children_from_schema(s));
     let mut visited: HashSet<String> = HashSet::new();
-    resolve_refs(&mut roots, &schema_map, &mut visited);
-
-    roots
-}
-
-/// Walk the tree and, for every `Ref` node that has no children yet,
-/// populate its children from the target schema (with cycle detection).
-fn resolve_refs(
-    nodes: &mut Vec<TreeNode>,
-    schemas: &HashMap<&str, &Schema>,
-    visited: &mut HashSet<String>,
-) {
-    for node in nodes.iter_mut() {
-        // Extract the ref target if this is an unresolved Ref node.
-        let target_opt: Option<String> = match &node.info.kind {
-            NodeKind::Ref(t) if node.children.is_empty() && !visited.contains(t.as_str()) => {
-                Some(t.clone())
-            }
-            _ => None,
-        };
+    resolve_refs(&mut roots, &children_of, &mut visited);
+    operations::resolve_operation_refs(&mut operations, &children_of);
 
-        if let Some(target) = target_opt {
-            if let Some(&schema) = schemas.get(target.as_str()) {
-                visited.insert(target.clone());
-                node.children = children_from_schema(schema);
-                // Recurse into the newly-added children before releasing the cycle guard.
-                resolve_refs(&mut node.children, schemas, visited);
-                visited.remove(&target);
-            }
-        } else {
-            // Not a Ref (or already resolved / cycle detected) — just recurse.
-            resolve_refs(&mut node.children, schemas, visited);
-        }
-    }
+    (roots, operations)
 }
 
 /// Build the immediate children of a schema without recursing into Ref targets
-/// (that is deferred to the `resolve_refs` pass).
+/// (that is deferred to the shared `resolve_refs` pass).
 fn children_from_schema(schema: &Schema) -> Vec<TreeNode> {
     match &schema.schema_kind {
-        SchemaKind::Type(Type::Object(obj)) => obj
-            .properties
-            .iter()
-            .map(|(name, prop_ref)| {
-                let is_req = obj.required.contains(name);
-                boxed_schema_ref_to_node(name.clone(), prop_ref, is_req)
-            })
-            .collect(),
+        SchemaKind::Type(Type::Object(obj)) => object_children(obj),
         SchemaKind::Type(Type::Array(arr)) => arr
             .items
             .as_ref()
@@ -127,6 +113,27 @@ fn children_from_schema(schema: &Schema) -> Vec<TreeNode> {
     }
 }
 
+/// An object's properties, then its `additionalProperties` schema (named after
+/// the keyword, as in 3.1) when it has one.
+fn object_children(obj: &ObjectType) -> Vec<TreeNode> {
+    let mut children: Vec<TreeNode> = obj
+        .properties
+        .iter()
+        .map(|(name, prop_ref)| {
+            let is_req = obj.required.contains(name);
+            boxed_schema_ref_to_node(name.clone(), prop_ref, is_req)
+        })
+        .collect();
+    if let Some(AdditionalProperties::Schema(schema)) = &obj.additional_properties {
+        children.push(schema_ref_to_node(
+            "additionalProperties".to_string(),
+            schema,
+            false,
+        ));
+    }
+    children
+}
+
 /// Convert a `ReferenceOr<Schema>` (used in components.schemas) into a TreeNode.
 fn schema_ref_to_node(name: String, schema_ref: &ReferenceOr<Schema>, required: bool) -> TreeNode {
     match schema_ref {
@@ -135,6 +142,30 @@ fn schema_ref_to_node(name: String, schema_ref: &ReferenceOr<Schema>, required:
     }
 }
 
+/// Convert a raw schema value (from `paths`) into a TreeNode via the 3.0 model.
+fn schema_value_to_node(name: String, value: &Value, required: bool) -> TreeNode {
+    match serde_json::from_value::<ReferenceOr<Schema>>(value.clone()) {
+        Ok(schema_ref) => schema_ref_to_node(name, &schema_ref, required),
+        Err(_) => TreeNode {
+            name,
+            info: NodeInfo {
+                kind: NodeKind::Unknown,
+                format: None,
+                description: None,
+                required,
+                constraints: Vec::new(),
+                enum_values: Vec::new(),
+                example: None,
+                default_val: None,
+                nullable: false,
+                examples: Vec::new(),
+            },
+            children: Vec::new(),
+            expanded: false,
+        },
+    }
+}
+
 /// Convert a `ReferenceOr<Box<Schema>>` (used in properties / array items) into a TreeNode.
 fn boxed_schema_ref_to_node(
     name: String,
@@ -160,6 +191,8 @@ fn ref_node(name: String, reference: &str, required: bool) -> TreeNode {
             enum_values: Vec::new(),
             example: None,
             default_val: None,
+            nullable: false,
+            examples: Vec::new(),
         },
         children: Vec::new(),
         expanded: false,
@@ -177,17 +210,11 @@ fn schema_to_node(name: String, schema: &Schema, required: bool) -> TreeNode {
         .default
         .as_ref()
         .map(|v| serde_json::to_string(v).unwrap_or_default());
+    let nullable = data.nullable;
 
     match &schema.schema_kind {
         SchemaKind::Type(Type::Object(obj)) => {
-            let children: Vec<TreeNode> = obj
-                .properties
-                .iter()
-                .map(|(prop_name, prop_ref)| {
-                    let is_req = obj.required.contains(prop_name);
-                    boxed_schema_ref_to_node(prop_name.clone(), prop_ref, is_req)
-                })
-                .collect();
+            let children = object_children(obj);
 
             let mut constraints = Vec::new();
             if let Some(n) = obj.min_properties {
@@ -196,6 +223,9 @@ fn schema_to_node(name: String, schema: &Schema, required: bool) -> TreeNode {
             if let Some(n) = obj.max_properties {
                 constraints.push(format!("maxProperties: {n}"));
             }
+            if let Some(AdditionalProperties::Any(false)) = obj.additional_properties {
+                constraints.push("additionalProperties: false".to_string());
+            }
 
             TreeNode {
                 name,
@@ -208,6 +238,8 @@ fn schema_to_node(name: String, schema: &Schema, required: bool) -> TreeNode {
                     enum_values: Vec::new(),
                     example,
                     default_val,
+                    nullable,
+                    examples: Vec::new(),
                 },
                 children,
                 expanded: false,
@@ -217,7 +249,11 @@ fn schema_to_node(name: String, schema: &Schema, required: bool) -> TreeNode {
         SchemaKind::Type(Type::Array(arr)) => {
             let mut children = Vec::new();
             if let Some(items_ref) = &arr.items {
-                children.push(boxed_schema_ref_to_node("items".to_string(), items_ref, false));
+                children.push(boxed_schema_ref_to_node(
+                    "items".to_string(),
+                    items_ref,
+                    false,
+                ));
             }
 
             let mut constraints = Vec::new();
@@ -242,6 +278,8 @@ fn schema_to_node(name: String, schema: &Schema, required: bool) -> TreeNode {
                     enum_values: Vec::new(),
                     example,
                     default_val,
+                    nullable,
+                    examples: Vec::new(),
                 },
                 children,
                 expanded: false,
@@ -283,6 +321,8 @@ fn schema_to_node(name: String, schema: &Schema, required: bool) -> TreeNode {
                     enum_values,
                     example,
                     default_val,
+                    nullable,
+                    examples: Vec::new(),
                 },
                 children: Vec::new(),
                 expanded: false,
@@ -332,6 +372,8 @@ fn schema_to_node(name: String, schema: &Schema, required: bool) -> TreeNode {
                     enum_values,
                     example,
                     default_val,
+                    nullable,
+                    examples: Vec::new(),
                 },
                 children: Vec::new(),
                 expanded: false,
@@ -381,6 +423,8 @@ fn schema_to_node(name: String, schema: &Schema, required: bool) -> TreeNode {
                     enum_values,
                     example,
                     default_val,
+                    nullable,
+                    examples: Vec::new(),
                 },
                 children: Vec::new(),
                 expanded: false,
@@ -398,6 +442,8 @@ fn schema_to_node(name: String, schema: &Schema, required: bool) -> TreeNode {
                 enum_values: Vec::new(),
                 example,
                 default_val,
+                nullable,
+                examples: Vec::new(),
             },
             children: Vec::new(),
             expanded: false,
@@ -420,6 +466,8 @@ fn schema_to_node(name: String, schema: &Schema, required: bool) -> TreeNode {
                     enum_values: Vec::new(),
                     example,
                     default_val,
+                    nullable,
+                    examples: Vec::new(),
                 },
                 children,
                 expanded: false,
@@ -443,6 +491,8 @@ fn schema_to_node(name: String, schema: &Schema, required: bool) -> TreeNode {
                     enum_values: Vec::new(),
                     example,
                     default_val,
+                    nullable,
+                    examples: Vec::new(),
                 },
                 children,
                 expanded: false,
@@ -466,6 +516,8 @@ fn schema_to_node(name: String, schema: &Schema, required: bool) -> TreeNode {
                     enum_values: Vec::new(),
                     example,
                     default_val,
+                    nullable,
+                    examples: Vec::new(),
                 },
                 children,
                 expanded: false,
@@ -484,6 +536,8 @@ fn schema_to_node(name: String, schema: &Schema, required: bool) -> TreeNode {
                 enum_values: Vec::new(),
                 example,
                 default_val,
+                nullable,
+                examples: Vec::new(),
             },
             children: Vec::new(),
             expanded: false,
@@ -493,14 +547,6 @@ fn schema_to_node(name: String, schema: &Schema, required: bool) -> TreeNode {
 
 // ── format helpers ────────────────────────────────────────────────────────────
 
-fn extract_ref_name(reference: &str) -> String {
-    reference
-        .rsplit('/')
-        .next()
-        .unwrap_or(reference)
-        .to_string()
-}
-
 fn string_format_label(f: &StringFormat) -> &'static str {
     match f {
         StringFormat::Date => "date",
@@ -541,6 +587,51 @@ mod tests {
         assert_eq!(spec.schema_names, vec!["Error", "NewPet", "Pet", "Pets"]);
     }
 
+    #[test]
+    fn shows_additional_properties_schemas() {
+        let spec = parse(
+            r##"
+openapi: 3.0.3
+info: { title: t, version: "1" }
+paths: {}
+components:
+  schemas:
+    Labels:
+      type: object
+      properties:
+        owner: { type: string }
+      additionalProperties: { type: string, maxLength: 20 }
+    Prices:
+      type: object
+      additionalProperties: { $ref: "#/components/schemas/Price" }
+    Price: { type: number }
+    Open:
+      type: object
+      additionalProperties: true
+"##,
+        )
+        .unwrap();
+        let schema = |name: &str| spec.schema_nodes.iter().find(|n| n.name == name).unwrap();
+        let names = |node: &TreeNode| {
+            node.children
+                .iter()
+                .map(|c| c.name.clone())
+                .collect::<Vec<_>>()
+        };
+
+        let labels = schema("Labels");
+        assert_eq!(names(labels), ["owner", "additionalProperties"]);
+        let values = &labels.children[1];
+        assert!(matches!(values.info.kind, NodeKind::Str));
+        assert!(!values.info.required);
+        assert_eq!(values.info.constraint("maxLength"), Some("20"));
+
+        let prices = &schema("Prices").children;
+        assert!(matches!(&prices[0].info.kind, NodeKind::Ref(t) if t == "Price"));
+        // `true` allows anything, and there is no schema to show.
+        assert!(schema("Open").children.is_empty());
+    }
+
     #[test]
     fn detects_openapi_30() {
         let spec = parse(PETSTORE).expect("should parse petstore fixture");
@@ -585,8 +676,60 @@ mod tests {
         let spec = parse(PETSTORE).expect("should parse petstore fixture");
         let pets = spec.schema_nodes.iter().find(|n| n.name == "Pets").unwrap();
         let items = &pets.children[0]; // items →Pet
-        assert_eq!(items.children.len(), 3, "items →Pet should have Pet's 3 properties");
+        assert_eq!(
+            items.children.len(),
+            3,
+            "items →Pet should have Pet's 3 properties"
+        );
         let names: Vec<&str> = items.children.iter().map(|c| c.name.as_str()).collect();
         assert_eq!(names, vec!["id", "name", "tag"]);
     }
+
+    #[test]
+    fn reads_operations_from_paths() {
+        let spec = parse(PETSTORE).expect("should parse petstore fixture");
+        let ops: Vec<String> = spec
+            .operations
+            .iter()
+            .map(|op| format!("{} {}", op.method, op.path))
+            .collect();
+        assert_eq!(ops, vec!["get /pets", "post /pets", "get /pets/{petId}"]);
+
+        let list = &spec.operations[0];
+        assert_eq!(list.operation_id.as_deref(), Some("listPets"));
+        assert_eq!(list.parameters[0].location, "query");
+        assert_eq!(list.parameters[0].schema.name, "limit");
+
+        let ok = &list.responses[0];
+        assert_eq!(ok.status, "200");
+        let schema = ok.schema.as_ref().unwrap();
+        assert!(matches!(&schema.info.kind, NodeKind::Ref(t) if t == "Pets"));
+        assert_eq!(
+            schema.children.len(),
+            1,
+            "→Pets should resolve to its items"
+        );
+
+        let create = &spec.operations[1];
+        let body = create.request_body.as_ref().unwrap();
+        assert!(body.required);
+        assert_eq!(body.content_type.as_deref(), Some("application/json"));
+    }
+
+    #[test]
+    fn groups_operation_tree_by_tag() {
+        let spec = parse(PETSTORE).expect("should parse petstore fixture");
+        assert_eq!(spec.operation_nodes.len(), 1);
+        let pets = &spec.operation_nodes[0];
+        assert_eq!(pets.name, "pets");
+        let names: Vec<&str> = pets.children.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["GET /pets", "POST /pets", "GET /pets/{petId}"]);
+
+        let sections: Vec<&str> = pets.children[0]
+            .children
+            .iter()
+            .map(|c| c.name.as_str())
+            .collect();
+        assert_eq!(sections, vec!["parameters", "responses"]);
+    }
 }
@@ -0,0 +1,606 @@
+use std::collections::{HashMap, HashSet};
+
+use anyhow::{Context, Result};
+use serde_json::{Map, Value};
+
+use crate::breadcrumb::escape;
+use crate::diagnostics::Diagnostics;
+use crate::graph::Graph;
+use crate::parser::{extract_ref_name, operations, resolve_refs};
+use crate::spec::{LoadedSpec, Operation, SpecVersion};
+use crate::tree::{NodeInfo, NodeKind, TreeNode};
+
+/// Parse an OpenAPI 3.1 document.
+///
+/// 3.1 schemas are full JSON Schema 2020-12, which the `openapiv3` (3.0) model
+/// cannot represent, so schemas are read straight from the raw JSON value.
+pub fn parse(content: &str) -> Result<LoadedSpec> {
+    let raw: Value = serde_yaml::from_str(content).context("failed to parse OpenAPI 3.1 spec")?;
+
+    let title = raw
+        .pointer("/info/title")
+        .and_then(Value::as_str)
+        .context("failed to parse OpenAPI 3.1 spec: missing info.title")?
+        .to_string();
+    let openapi_version = raw
+        .get("openapi")
+        .and_then(Value::as_str)
+        .unwrap_or("3.1.0")
+        .to_string();
+
+    let schemas = raw
+        .pointer("/components/schemas")
+        .and_then(Value::as_object);
+
+    // Extract top-level schema names (sorted) — kept for tests
+    let schema_names: Vec<String> = schemas
+        .map(|s| {
+            let mut names: Vec<String> = s.keys().cloned().collect();
+            names.sort();
+            names
+        })
+        .unwrap_or_default();
+
+    let empty = Map::new();
+    let (schema_nodes, operations) = build_tree(&raw, schemas.unwrap_or(&empty));
+    let operation_nodes = operations::build_tree(&operations);
+
+    Ok(LoadedSpec {
+        title,
+        openapi_version,
+        version: SpecVersion::V31,
+        schema_names,
+        schema_nodes,
+        operations,
+        operation_nodes,
+        schema_sources: HashMap::new(),
+        diagnostics: Diagnostics::default(),
+        graph: Graph::default(),
+        usages: Vec::new(),
+    })
+}
+
+// ── tree construction ─────────────────────────────────────────────────────────
+
+fn build_tree(raw: &Value, schemas: &Map<String, Value>) -> (Vec<TreeNode>, Vec<Operation>) {
+    // Pass 1: build the trees (Ref nodes are leaves with no children yet).
+    let mut entries: Vec<(&String, &Value)> = schemas.iter().collect();
+    entries.sort_by_key(|(k, _)| k.as_str());
+    let mut roots: Vec<TreeNode> = entries
+        .into_iter()
+        .map(|(name, schema)| schema_to_node(name.clone(), schema, false))
+        .collect();
+    let mut operations = operations::extract(raw, &schema_to_node);
+
+    // Pass 2: populate children of Ref nodes. Targets are component schemas,
+    // or `$defs` entries nested anywhere inside them.
+    let mut targets: HashMap<String, &Value> = HashMap::new();
+    for (name, schema) in schemas {
+        collect_defs(&escape(name), schema, &mut targets);
+        targets.insert(name.clone(), schema);
+    }
+
+    let children_of = |target: &str| targets.get(target).map(|s| schema_children(s));
+    let mut visited: HashSet<String> = HashSet::new();
+    resolve_refs(&mut roots, &children_of, &mut visited);
+    operations::resolve_operation_refs(&mut operations, &children_of);
+
+    (roots, operations)
+}
+
+/// The `$defs` entries nested in `schema`, keyed the way `extract_ref_name`
+/// names them: by their path from `owner`, the schema's own escaped path.
+pub(crate) fn collect_defs<'a>(
+    owner: &str,
+    schema: &'a Value,
+    out: &mut HashMap<String, &'a Value>,
+) {
+    if let Some(defs) = schema.get("$defs").and_then(Value::as_object) {
+        for (name, def) in defs {
+            let path = format!("{owner}/$defs/{}", escape(name));
+            collect_defs(&path, def, out);
+            out.insert(path, def);
+        }
+    }
+}
+
+fn schema_to_node(name: String, schema: &Value, required: bool) -> TreeNode {
+    let Some(obj) = schema.as_object() else {
+        return boolean_schema_node(name, schema, required);
+    };
+
+    let description = str_field(obj, "description");
+    let example = obj.get("example").map(json_string);
+    let default_val = obj.get("default").map(json_string);
+    let examples: Vec<String> = obj
+        .get("examples")
+        .and_then(Value::as_array)
+        .map(|items| items.iter().map(json_string).collect())
+        .unwrap_or_default();
+    let enum_values: Vec<String> = obj
+        .get("enum")
+        .and_then(Value::as_array)
+        .map(|items| items.iter().map(json_string).collect())
+        .unwrap_or_default();
+
+    let (kind, nullable) = match obj.get("$ref").and_then(Value::as_str) {
+        Some(reference) => (NodeKind::Ref(extract_ref_name(reference)), false),
+        None => schema_kind(obj),
+    };
+
+    TreeNode {
+        name,
+        info: NodeInfo {
+            kind,
+            format: str_field(obj, "format"),
+            description,
+            required,
+            constraints: constraints(obj),
+            enum_values,
+            example,
+            default_val,
+            nullable,
+            examples,
+        },
+        children: schema_children(schema),
+        expanded: false,
+    }
+}
+
+/// `true` / `false` are valid schemas in 3.1 (accept anything / accept nothing).
+fn boolean_schema_node(name: String, schema: &Value, required: bool) -> TreeNode {
+    let constraints = if schema == &Value::Bool(false) {
+        vec!["no value allowed (false schema)".to_string()]
+    } else {
+        Vec::new()
+    };
+    TreeNode {
+        name,
+        info: NodeInfo {
+            kind: NodeKind::Unknown,
+            format: None,
+            description: None,
+            required,
+            constraints,
+            enum_values: Vec::new(),
+            example: None,
+            default_val: None,
+            nullable: false,
+            examples: Vec::new(),
+        },
+        children: Vec::new(),
+        expanded: false,
+    }
+}
+
+/// Work out the node kind from `type` (string or array), falling back to the
+/// keywords present when `type` is omitted. Returns `(kind, nullable)`.
+fn schema_kind(obj: &Map<String, Value>) -> (NodeKind, bool) {
+    if obj.contains_key("prefixItems") {
+        return (NodeKind::Tuple, type_list(obj).contains(&"null"));
+    }
+
+    let types = type_list(obj);
+    if !types.is_empty() {
+        let nullable = types.contains(&"null");
+        let non_null: Vec<&str> = types.into_iter().filter(|t| *t != "null").collect();
+        let kind = match non_null.as_slice() {
+            [] => NodeKind::Null,
+            [single] => type_kind(single),
+            many => NodeKind::Multi(many.iter().map(|t| t.to_string()).collect()),
+        };
+        return (kind, nullable);
+    }
+
+    let kind = if obj.contains_key("properties")
+        || obj.contains_key("additionalProperties")
+        || obj.contains_key("unevaluatedProperties")
+        || obj.contains_key("patternProperties")
+    {
+        NodeKind::Object
+    } else if obj.contains_key("items") {
+        NodeKind::Array
+    } else if obj.contains_key("allOf") {
+        NodeKind::AllOf
+    } else if obj.contains_key("oneOf") {
+        NodeKind::OneOf
+    } else if obj.contains_key("anyOf") {
+        NodeKind::AnyOf
+    } else if obj.contains_key("if") {
+        NodeKind::Conditional
+    } else if obj.contains_key("not") {
+        NodeKind::Not
+    } else if let Some(value) = obj.get("const") {
+        value_kind(value)
+    } else if let Some(first) = obj
+        .get("enum")
+        .and_then(Value::as_array)
+        .and_then(|e| e.first())
+    {
+        value_kind(first)
+    } else {
+        NodeKind::Unknown
+    };
+    (kind, false)
+}
+
+fn type_list(obj: &Map<String, Value>) -> Vec<&str> {
+    match obj.get("type") {
+        Some(Value::String(t)) => vec![t.as_str()],
+        Some(Value::Array(ts)) => ts.iter().filter_map(Value::as_str).collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn type_kind(t: &str) -> NodeKind {
+    match t {
+        "object" => NodeKind::Object,
+        "array" => NodeKind::Array,
+        "string" => NodeKind::Str,
+        "integer" => NodeKind::Integer,
+        "number" => NodeKind::Number,
+        "boolean" => NodeKind::Boolean,
+        "null" => NodeKind::Null,
+        _ => NodeKind::Unknown,
+    }
+}
+
+/// Infer a kind from a literal (`const` / `enum` value) when `type` is omitted.
+fn value_kind(value: &Value) -> NodeKind {
+    match value {
+        Value::Null => NodeKind::Null,
+        Value::Bool(_) => NodeKind::Boolean,
+        Value::Number(n) if n.is_i64() || n.is_u64() => NodeKind::Integer,
+        Value::Number(_) => NodeKind::Number,
+        Value::String(_) => NodeKind::Str,
+        Value::Array(_) => NodeKind::Array,
+        Value::Object(_) => NodeKind::Object,
+    }
+}
+
+/// Build the immediate children of a schema without recursing into Ref targets
+/// (that is deferred to the shared `resolve_refs` pass).
+///
+/// Unlike the 3.0 model a 3.1 schema may combine keywords freely, so an object
+/// with `allOf` and `if`/`then` shows its properties followed by keyword nodes.
+fn schema_children(schema: &Value) -> Vec<TreeNode> {
+    let Some(obj) = schema.as_object() else {
+        return Vec::new();
+    };
+    if obj.contains_key("$ref") {
+        return Vec::new();
+    }
+
+    let (kind, _) = schema_kind(obj);
+    let mut children = Vec::new();
+
+    // properties
+    let required: Vec<&str> = obj
+        .get("required")
+        .and_then(Value::as_array)
+        .map(|r| r.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default();
+    if let Some(props) = obj.get("properties").and_then(Value::as_object) {
+        for (prop_name, prop) in props {
+            let is_req = required.contains(&prop_name.as_str());
+            children.push(schema_to_node(prop_name.clone(), prop, is_req));
+        }
+    }
+    for keyword in [
+        "additionalProperties",
+        "unevaluatedProperties",
+        "propertyNames",
+    ] {
+        if let Some(sub) = obj.get(keyword).filter(|v| v.is_object()) {
+            children.push(schema_to_node(keyword.to_string(), sub, false));
+        }
+    }
+
+    // arrays / tuples
+    if let Some(prefix) = obj.get("prefixItems").and_then(Value::as_array) {
+        for (i, sub) in prefix.iter().enumerate() {
+            children.push(schema_to_node(format!("[{i}]"), sub, false));
+        }
+    }
+    for keyword in ["items", "contains", "unevaluatedItems"] {
+        if let Some(sub) = obj.get(keyword).filter(|v| v.is_object()) {
+            children.push(schema_to_node(keyword.to_string(), sub, false));
+        }
+    }
+
+    // combiners: branches are direct children when the combiner *is* the node's
+    // kind, otherwise they are grouped under a combiner node of their own.
+    for (keyword, combiner_kind) in [
+        ("allOf", NodeKind::AllOf),
+        ("oneOf", NodeKind::OneOf),
+        ("anyOf", NodeKind::AnyOf),
+    ] {
+        let Some(branches) = obj.get(keyword).and_then(Value::as_array) else {
+            continue;
+        };
+        let branch_nodes: Vec<TreeNode> = branches
+            .iter()
+            .enumerate()
+            .map(|(i, s)| schema_to_node(format!("[{i}]"), s, false))
+            .collect();
+        if std::mem::discriminant(&kind) == std::mem::discriminant(&combiner_kind) {
+            children.extend(branch_nodes);
+        } else {
+            children.push(keyword_node(keyword, combiner_kind, branch_nodes));
+        }
+    }
+
+    // conditionals / negation
+    for keyword in ["if", "then", "else", "not"] {
+        if let Some(sub) = obj.get(keyword) {
+            children.push(schema_to_node(keyword.to_string(), sub, false));
+        }
+    }
+
+    // keyword containers
+    for keyword in ["dependentSchemas", "patternProperties", "$defs"] {
+        if let Some(entries) = obj.get(keyword).and_then(Value::as_object) {
+            let nodes: Vec<TreeNode> = entries
+                .iter()
+                .map(|(k, s)| schema_to_node(k.clone(), s, false))
+                .collect();
+            children.push(keyword_node(
+                keyword,
+                NodeKind::Group(keyword.to_string()),
+                nodes,
+            ));
+        }
+    }
+
+    children
+}
+
+fn keyword_node(name: &str, kind: NodeKind, children: Vec<TreeNode>) -> TreeNode {
+    TreeNode {
+        name: name.to_string(),
+        info: NodeInfo {
+            kind,
+            format: None,
+            description: None,
+            required: false,
+            constraints: Vec::new(),
+            enum_values: Vec::new(),
+            example: None,
+            default_val: None,
+            nullable: false,
+            examples: Vec::new(),
+        },
+        children,
+        expanded: false,
+    }
+}
+
+fn constraints(obj: &Map<String, Value>) -> Vec<String> {
+    let mut out = Vec::new();
+
+    // 3.1 uses numeric exclusive bounds; 3.0-style booleans are still accepted.
+    match (obj.get("minimum"), obj.get("exclusiveMinimum")) {
+        (_, Some(Value::Number(n))) => out.push(format!("min: >{n}")),
+        (Some(min), Some(Value::Bool(true))) => out.push(format!("min: >{min}")),
+        (Some(min), _) => out.push(format!("min: {min}")),
+        _ => {}
+    }
+    match (obj.get("maximum"), obj.get("exclusiveMaximum")) {
+        (_, Some(Value::Number(n))) => out.push(format!("max: <{n}")),
+        (Some(max), Some(Value::Bool(true))) => out.push(format!("max: <{max}")),
+        (Some(max), _) => out.push(format!("max: {max}")),
+        _ => {}
+    }
+
+    for key in [
+        "multipleOf",
+        "minLength",
+        "maxLength",
+        "minItems",
+        "maxItems",
+        "minContains",
+        "maxContains",
+        "minProperties",
+        "maxProperties",
+    ] {
+        if let Some(n) = obj.get(key) {
+            out.push(format!("{key}: {n}"));
+        }
+    }
+    if let Some(pat) = obj.get("pattern").and_then(Value::as_str) {
+        out.push(format!("pattern: {pat}"));
+    }
+    if obj.get("uniqueItems") == Some(&Value::Bool(true)) {
+        out.push("uniqueItems: true".to_string());
+    }
+    if let Some(value) = obj.get("const") {
+        out.push(format!("const: {}", json_string(value)));
+    }
+    for keyword in [
+        "additionalProperties",
+        "unevaluatedProperties",
+        "unevaluatedItems",
+    ] {
+        if obj.get(keyword) == Some(&Value::Bool(false)) {
+            out.push(format!("{keyword}: false"));
+        }
+    }
+    if let Some(deps) = obj.get("dependentRequired").and_then(Value::as_object) {
+        for (prop, needs) in deps {
+            let needs: Vec<&str> = needs
+                .as_array()
+                .map(|n| n.iter().filter_map(Value::as_str).collect())
+                .unwrap_or_default();
+            out.push(format!("dependentRequired: {prop} → {}", needs.join(", ")));
+        }
+    }
+
+    out
+}
+
+fn str_field(obj: &Map<String, Value>, key: &str) -> Option<String> {
+    obj.get(key).and_then(Value::as_str).map(str::to_string)
+}
+
+fn json_string(value: &Value) -> String {
+    serde_json::to_string(value).unwrap_or_default()
+}
+
+// ── tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SPEC_31: &str = include_str!("../../fixtures/petstore-3.1.yaml");
+
+    fn find<'a>(nodes: &'a [TreeNode], name: &str) -> &'a TreeNode {
+        nodes
+            .iter()
+            .find(|n| n.name == name)
+            .unwrap_or_else(|| panic!("{name} should exist"))
+    }
+
+    #[test]
+    fn detects_openapi_31() {
+        let spec = parse(SPEC_31).expect("should parse 3.1 fixture");
+        assert_eq!(spec.version, SpecVersion::V31);
+        assert_eq!(
+            spec.schema_names,
+            vec!["Error", "Pet", "Pets", "Point", "Shape"]
+        );
+    }
+
+    #[test]
+    fn type_arrays_become_nullable_kinds() {
+        let spec = parse(SPEC_31).unwrap();
+        let pet = find(&spec.schema_nodes, "Pet");
+        let tag = find(&pet.children, "tag");
+        assert!(matches!(tag.info.kind, NodeKind::Str));
+        assert!(tag.info.nullable);
+
+        let id = find(&pet.children, "id");
+        assert!(matches!(&id.info.kind, NodeKind::Multi(ts) if ts == &["integer", "string"]));
+    }
+
+    #[test]
+    fn const_and_examples_are_kept() {
+        let spec = parse(SPEC_31).unwrap();
+        let pet = find(&spec.schema_nodes, "Pet");
+        let kind = find(&pet.children, "kind");
+        assert!(matches!(kind.info.kind, NodeKind::Str));
+        assert!(
+            kind.info
+                .constraints
+                .contains(&"const: \"pet\"".to_string())
+        );
+
+        let name = find(&pet.children, "name");
+        assert_eq!(name.info.examples, vec!["\"Rex\"", "\"Tom\""]);
+    }
+
+    #[test]
+    fn prefix_items_build_a_tuple() {
+        let spec = parse(SPEC_31).unwrap();
+        let point = find(&spec.schema_nodes, "Point");
+        assert!(matches!(point.info.kind, NodeKind::Tuple));
+        let names: Vec<&str> = point.children.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["[0]", "[1]"]);
+    }
+
+    #[test]
+    fn conditionals_defs_and_dependent_schemas() {
+        let spec = parse(SPEC_31).unwrap();
+        let shape = find(&spec.schema_nodes, "Shape");
+        let names: Vec<&str> = shape.children.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "kind",
+                "radius",
+                "unevaluatedProperties",
+                "if",
+                "then",
+                "else",
+                "dependentSchemas",
+                "$defs"
+            ]
+        );
+
+        // A ref into the schema's own `$defs` resolves against the nested definition.
+        let radius = find(&shape.children, "radius");
+        assert!(matches!(&radius.info.kind, NodeKind::Ref(t) if t == "Shape/$defs/Radius"));
+        let names: Vec<&str> = radius.children.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["value", "unit"]);
+        let defs = find(&shape.children, "$defs");
+        assert!(matches!(&defs.info.kind, NodeKind::Group(k) if k == "$defs"));
+    }
+
+    #[test]
+    fn defs_of_the_same_name_stay_apart() {
+        let spec = crate::parser::parse_spec(
+            r##"
+openapi: 3.1.0
+info: { title: Defs, version: "1" }
+components:
+  schemas:
+    Cart:
+      type: object
+      properties:
+        items: { type: array, items: { $ref: "#/components/schemas/Cart/$defs/Item" } }
+      $defs:
+        Item:
+          type: object
+          properties:
+            sku: { type: string }
+    Menu:
+      type: object
+      properties:
+        items: { type: array, items: { $ref: "#/components/schemas/Menu/$defs/Item" } }
+      $defs:
+        Item:
+          type: object
+          properties:
+            dish: { type: string }
+            price: { type: number }
+"##,
+        )
+        .unwrap();
+        let item = |schema: &str| {
+            let items = &find(&find(&spec.schema_nodes, schema).children, "items").children[0];
+            let names: Vec<String> = items.children.iter().map(|c| c.name.clone()).collect();
+            (items.info.kind.label(), names)
+        };
+        assert_eq!(
+            item("Cart"),
+            ("→Cart/$defs/Item".to_string(), vec!["sku".to_string()])
+        );
+        assert_eq!(
+            item("Menu"),
+            (
+                "→Menu/$defs/Item".to_string(),
+                vec!["dish".to_string(), "price".to_string()]
+            )
+        );
+        assert!(spec.diagnostics.is_empty(), "both refs resolve");
+    }
+
+    #[test]
+    fn reads_operations_with_31_schemas() {
+        let spec = parse(SPEC_31).unwrap();
+        assert_eq!(spec.operations.len(), 1);
+        let ok = &spec.operations[0].responses[0];
+        let schema = ok.schema.as_ref().unwrap();
+        assert!(matches!(&schema.info.kind, NodeKind::Ref(t) if t == "Pets"));
+        assert_eq!(schema.children.len(), 1);
+    }
+
+    #[test]
+    fn refs_resolve_inline() {
+        let spec = parse(SPEC_31).unwrap();
+        let pets = find(&spec.schema_nodes, "Pets");
+        let items = &pets.children[0];
+        assert!(matches!(&items.info.kind, NodeKind::Ref(t) if t == "Pet"));
+        assert_eq!(items.children.len(), 4);
+    }
+}
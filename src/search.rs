@@ -0,0 +1,181 @@
+use crate::app::View;
+use crate::tree::TreeNode;
+
+/// One search hit: the node's path and which characters of its name matched
+/// (empty when only the description matched).
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchMatch {
+    pub path: Vec<usize>,
+    pub name_hits: Vec<usize>, // char indices into `TreeNode::name`
+}
+
+/// `/` search over the whole active tree, collapsed subtrees included.
+pub struct SearchState {
+    pub query: String,
+    pub typing: bool, // prompt open in the status bar
+    pub view: View,   // matches are paths into this view's tree
+    pub matches: Vec<SearchMatch>,
+    pub current: usize,
+    pub origin: Vec<usize>,               // cursor path when `/` was pressed
+    pub origin_scroll: u16,               // detail scroll when `/` was pressed
+    pub origin_expanded: Vec<Vec<usize>>, // expansion state when `/` was pressed
+}
+
+impl SearchState {
+    pub fn new(
+        view: View,
+        origin: Vec<usize>,
+        origin_scroll: u16,
+        origin_expanded: Vec<Vec<usize>>,
+    ) -> Self {
+        SearchState {
+            query: String::new(),
+            typing: true,
+            view,
+            matches: Vec::new(),
+            current: 0,
+            origin,
+            origin_scroll,
+            origin_expanded,
+        }
+    }
+
+    pub fn current_match(&self) -> Option<&SearchMatch> {
+        self.matches.get(self.current)
+    }
+
+    /// Name characters to highlight for the node at `path`, if it matched.
+    pub fn hits_at(&self, path: &[usize]) -> Option<&[usize]> {
+        self.matches
+            .iter()
+            .find(|m| m.path == path)
+            .map(|m| m.name_hits.as_slice())
+    }
+
+    /// Re-run the query and make the first match at or after `from` (in tree
+    /// order) current, wrapping to the top like nvim's `incsearch`.
+    pub fn update(&mut self, roots: &[TreeNode], from: &[usize]) {
+        self.matches = find_matches(roots, &self.query);
+        self.current = self
+            .matches
+            .iter()
+            .position(|m| m.path.as_slice() >= from)
+            .unwrap_or(0);
+    }
+
+    /// `n` / `N`: step through the matches, wrapping at either end.
+    pub fn step(&mut self, forward: bool) {
+        let count = self.matches.len();
+        if count == 0 {
+            return;
+        }
+        self.current = if forward {
+            (self.current + 1) % count
+        } else {
+            (self.current + count - 1) % count
+        };
+    }
+}
+
+/// Every node whose name fuzzy-matches `query`, or whose description contains
+/// it, in depth-first order. An empty query matches nothing.
+pub fn find_matches(roots: &[TreeNode], query: &str) -> Vec<SearchMatch> {
+    let mut out = Vec::new();
+    if query.is_empty() {
+        return out;
+    }
+    let query = query.to_lowercase();
+    for (i, root) in roots.iter().enumerate() {
+        collect(root, vec![i], &query, &mut out);
+    }
+    out
+}
+
+fn collect(node: &TreeNode, path: Vec<usize>, query: &str, out: &mut Vec<SearchMatch>) {
+    let name_hits = fuzzy_match(query, &node.name);
+    let in_description = node
+        .info
+        .description
+        .as_ref()
+        .is_some_and(|d| d.to_lowercase().contains(query));
+    if name_hits.is_some() || in_description {
+        out.push(SearchMatch {
+            path: path.clone(),
+            name_hits: name_hits.unwrap_or_default(),
+        });
+    }
+    for (i, child) in node.children.iter().enumerate() {
+        let mut child_path = path.clone();
+        child_path.push(i);
+        collect(child, child_path, query, out);
+    }
+}
+
+/// Case-insensitive subsequence match. Returns the char indices of `text` that
+/// matched, preferring a contiguous run over a scattered one.
+pub fn fuzzy_match(query: &str, text: &str) -> Option<Vec<usize>> {
+    let fold = |c: char| c.to_lowercase().next().unwrap_or(c);
+    let needle: Vec<char> = query.chars().map(fold).collect();
+    let hay: Vec<char> = text.chars().map(fold).collect();
+    if needle.is_empty() {
+        return None;
+    }
+
+    // A contiguous hit reads best, so try a plain substring first.
+    if let Some(start) = hay
+        .windows(needle.len())
+        .position(|w| w == needle.as_slice())
+    {
+        return Some((start..start + needle.len()).collect());
+    }
+
+    let mut hits = Vec::with_capacity(needle.len());
+    let mut from = 0;
+    for &c in &needle {
+        let offset = hay[from..].iter().position(|&h| h == c)?;
+        hits.push(from + offset);
+        from += offset + 1;
+    }
+    Some(hits)
+}
+
+// ── tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PETSTORE: &str = include_str!("../fixtures/petstore.yaml");
+
+    #[test]
+    fn fuzzy_prefers_contiguous_hits() {
+        assert_eq!(fuzzy_match("pet", "NewPet"), Some(vec![3, 4, 5]));
+        assert_eq!(fuzzy_match("npt", "NewPet"), Some(vec![0, 3, 5]));
+        assert_eq!(fuzzy_match("xyz", "NewPet"), None);
+    }
+
+    #[test]
+    fn searches_collapsed_subtrees_and_descriptions() {
+        let spec = crate::parser::parse_spec(PETSTORE).unwrap();
+        let paths: Vec<Vec<usize>> = find_matches(&spec.schema_nodes, "tag")
+            .into_iter()
+            .map(|m| m.path)
+            .collect();
+        // `tag` on NewPet and Pet, and again under Pets › items → Pet.
+        assert_eq!(paths, vec![vec![1, 1], vec![2, 2], vec![3, 0, 2]]);
+    }
+
+    #[test]
+    fn update_starts_from_cursor_and_step_wraps() {
+        let spec = crate::parser::parse_spec(PETSTORE).unwrap();
+        let mut search = SearchState::new(View::Schemas, vec![2], 0, Vec::new());
+        search.query = "tag".to_string();
+        search.update(&spec.schema_nodes, &[2]);
+        assert_eq!(search.current_match().unwrap().path, vec![2, 2]);
+        search.step(true);
+        search.step(true);
+        assert_eq!(search.current_match().unwrap().path, vec![1, 1]);
+        search.step(false);
+        assert_eq!(search.current_match().unwrap().path, vec![3, 0, 2]);
+    }
+}
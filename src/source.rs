@@ -0,0 +1,293 @@
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+
+use crate::config::cache_dir;
+use crate::indexer::iso8601;
+
+/// Where the spec given on the command line comes from.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Source {
+    File(PathBuf),
+    Stdin,       // `-`, or no argument with stdin piped in
+    Url(String), // `http://` / `https://`
+}
+
+/// A spec's text, plus a note for the status bar (e.g. served from the cache).
+pub struct Fetched {
+    pub content: String,
+    pub note: Option<String>,
+}
+
+/// Validators for a cached download, stored next to the body as JSON.
+#[derive(Debug, Serialize, Deserialize)]
+struct CacheEntry {
+    url: String,
+    etag: Option<String>,
+    last_modified: Option<String>,
+    fetched_at: String,
+}
+
+impl Source {
+    pub fn parse(arg: &str) -> Source {
+        if arg == "-" {
+            Source::Stdin
+        } else if arg.starts_with("http://") || arg.starts_with("https://") {
+            Source::Url(arg.to_string())
+        } else {
+            Source::File(PathBuf::from(arg))
+        }
+    }
+
+    /// The spec's file, which relative `$ref`s to other files resolve against.
+    pub fn file(&self) -> Option<&Path> {
+        match self {
+            Source::File(path) => Some(path),
+            _ => None,
+        }
+    }
+
+    /// How the source is named in error messages.
+    pub fn label(&self) -> String {
+        match self {
+            Source::File(path) => path.display().to_string(),
+            Source::Stdin => "<stdin>".to_string(),
+            Source::Url(url) => url.clone(),
+        }
+    }
+
+    pub fn read(&self) -> Result<Fetched> {
+        match self {
+            Source::File(path) => {
+                let content = fs::read_to_string(path)
+                    .with_context(|| format!("cannot read spec file: {}", path.display()))?;
+                Ok(Fetched {
+                    content,
+                    note: None,
+                })
+            }
+            Source::Stdin => {
+                let mut content = String::new();
+                io::stdin()
+                    .read_to_string(&mut content)
+                    .context("cannot read spec from stdin")?;
+                if content.trim().is_empty() {
+                    bail!("no spec on stdin");
+                }
+                Ok(Fetched {
+                    content,
+                    note: None,
+                })
+            }
+            Source::Url(url) => match cache_dir() {
+                Some(cache) => fetch(url, &cache.join("http")),
+                None => fetch_uncached(url),
+            },
+        }
+    }
+}
+
+/// Download `url`, revalidating any copy cached under `cache` with
+/// `If-None-Match` / `If-Modified-Since`. When the server cannot be reached (or
+/// fails with a 5xx) a cached copy is used instead, with a note saying so.
+pub fn fetch(url: &str, cache: &Path) -> Result<Fetched> {
+    let key = cache_key(url);
+    let body_path = cache.join(format!("{key}.body"));
+    let entry_path = cache.join(format!("{key}.json"));
+    let cached: Option<CacheEntry> = fs::read_to_string(&entry_path)
+        .ok()
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .filter(|_| body_path.exists());
+
+    let mut request = agent().get(url);
+    if let Some(entry) = &cached {
+        if let Some(etag) = &entry.etag {
+            request = request.set("If-None-Match", etag);
+        }
+        if let Some(modified) = &entry.last_modified {
+            request = request.set("If-Modified-Since", modified);
+        }
+    }
+
+    let response = match request.call() {
+        Ok(response) => response,
+        Err(ureq::Error::Status(code, _)) if code < 500 => {
+            bail!("cannot fetch {url}: HTTP {code}")
+        }
+        Err(e) => {
+            let Some(entry) = cached else {
+                return Err(e).with_context(|| format!("cannot fetch {url}"));
+            };
+            let content = fs::read_to_string(&body_path)
+                .with_context(|| format!("cannot read {}", body_path.display()))?;
+            return Ok(Fetched {
+                content,
+                note: Some(format!(
+                    "offline: using the copy cached at {}",
+                    entry.fetched_at
+                )),
+            });
+        }
+    };
+
+    if response.status() == 304
+        && let Some(mut entry) = cached
+    {
+        let content = fs::read_to_string(&body_path)
+            .with_context(|| format!("cannot read {}", body_path.display()))?;
+        entry.fetched_at = iso8601(SystemTime::now());
+        write_entry(&entry_path, &entry)?;
+        return Ok(Fetched {
+            content,
+            note: None,
+        });
+    }
+
+    let entry = CacheEntry {
+        url: url.to_string(),
+        etag: response.header("ETag").map(str::to_string),
+        last_modified: response.header("Last-Modified").map(str::to_string),
+        fetched_at: iso8601(SystemTime::now()),
+    };
+    let content = response
+        .into_string()
+        .with_context(|| format!("cannot read the response from {url}"))?;
+    fs::create_dir_all(cache).with_context(|| format!("cannot create {}", cache.display()))?;
+    fs::write(&body_path, &content)
+        .with_context(|| format!("cannot write {}", body_path.display()))?;
+    write_entry(&entry_path, &entry)?;
+    Ok(Fetched {
+        content,
+        note: None,
+    })
+}
+
+/// Without a `HOME` there is nowhere to cache: plain download.
+fn fetch_uncached(url: &str) -> Result<Fetched> {
+    let content = agent()
+        .get(url)
+        .call()
+        .with_context(|| format!("cannot fetch {url}"))?
+        .into_string()
+        .with_context(|| format!("cannot read the response from {url}"))?;
+    Ok(Fetched {
+        content,
+        note: None,
+    })
+}
+
+fn agent() -> ureq::Agent {
+    ureq::AgentBuilder::new()
+        .timeout_connect(Duration::from_secs(5))
+        .timeout(Duration::from_secs(30))
+        .user_agent(concat!("speq/", env!("CARGO_PKG_VERSION")))
+        .build()
+}
+
+fn write_entry(path: &Path, entry: &CacheEntry) -> Result<()> {
+    let json = serde_json::to_string_pretty(entry)?;
+    fs::write(path, json).with_context(|| format!("cannot write {}", path.display()))
+}
+
+/// File name for a URL's cache entry: FNV-1a of the URL, which (unlike std's
+/// `DefaultHasher`) stays the same across Rust releases.
+fn cache_key(url: &str) -> String {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in url.bytes() {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    format!("{hash:016x}")
+}
+
+// ── tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+    use tiny_http::{Header, Response, Server};
+
+    const PETSTORE: &str = include_str!("../fixtures/petstore.yaml");
+
+    /// Serve `PETSTORE` with an ETag for `requests` requests, answering 304 when
+    /// the client already has it. Returns the URL and the If-None-Match headers seen.
+    fn serve(requests: usize) -> (String, thread::JoinHandle<Vec<Option<String>>>) {
+        let server = Server::http("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/openapi.yaml", server.server_addr());
+        let handle = thread::spawn(move || {
+            let mut seen = Vec::new();
+            for request in server.incoming_requests().take(requests) {
+                let etag = request
+                    .headers()
+                    .iter()
+                    .find(|h| h.field.equiv("If-None-Match"))
+                    .map(|h| h.value.to_string());
+                let response = if etag.as_deref() == Some("\"v1\"") {
+                    Response::from_string("").with_status_code(304)
+                } else {
+                    Response::from_string(PETSTORE)
+                };
+                let header = Header::from_bytes("ETag", "\"v1\"").unwrap();
+                request.respond(response.with_header(header)).unwrap();
+                seen.push(etag);
+            }
+            seen
+        });
+        (url, handle)
+    }
+
+    fn cache(label: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("speq-http-{label}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn parses_arguments() {
+        assert_eq!(Source::parse("-"), Source::Stdin);
+        assert_eq!(
+            Source::parse("https://example.com/openapi.yaml"),
+            Source::Url("https://example.com/openapi.yaml".to_string())
+        );
+        assert_eq!(
+            Source::parse("specs/openapi.yaml"),
+            Source::File(PathBuf::from("specs/openapi.yaml"))
+        );
+    }
+
+    #[test]
+    fn revalidates_with_etag_and_falls_back_offline() {
+        let dir = cache("revalidate");
+        let (url, server) = serve(2);
+
+        let first = fetch(&url, &dir).unwrap();
+        assert_eq!(first.content, PETSTORE);
+        // Second fetch sends the ETag back and gets a 304: served from the cache.
+        let second = fetch(&url, &dir).unwrap();
+        assert_eq!(second.content, PETSTORE);
+        assert!(second.note.is_none());
+        assert_eq!(
+            server.join().unwrap(),
+            vec![None, Some("\"v1\"".to_string())]
+        );
+
+        // The server is gone now: the cached copy is used, with a note.
+        let offline = fetch(&url, &dir).unwrap();
+        assert_eq!(offline.content, PETSTORE);
+        assert!(offline.note.unwrap().starts_with("offline"));
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn unreachable_without_cache_is_an_error() {
+        let dir = cache("unreachable");
+        let (url, server) = serve(0);
+        server.join().unwrap(); // server dropped: connection refused
+        assert!(fetch(&url, &dir).is_err());
+    }
+}
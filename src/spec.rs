@@ -1,4 +1,9 @@
+use std::collections::HashMap;
+
+use crate::diagnostics::Diagnostics;
+use crate::graph::Graph;
 use crate::tree::TreeNode;
+use crate::usage::Usage;
 
 /// The loaded, version-detected representation of an OpenAPI spec.
 #[allow(dead_code)]
@@ -6,13 +11,83 @@ pub struct LoadedSpec {
     pub title: String,
     pub openapi_version: String,
     pub version: SpecVersion,
-    pub schema_names: Vec<String>,    // kept for tests
-    pub schema_nodes: Vec<TreeNode>,  // full schema tree
+    pub schema_names: Vec<String>,               // kept for tests
+    pub schema_nodes: Vec<TreeNode>,             // full schema tree
+    pub operations: Vec<Operation>,              // every path + method, in document order
+    pub operation_nodes: Vec<TreeNode>, // operations grouped by tag, for the operations view
+    pub schema_sources: HashMap<String, String>, // schemas imported from other files → where from
+    pub diagnostics: Diagnostics,       // dangling refs, cycle cuts, unsupported keywords
+    pub graph: Graph,                   // `$ref`s between component schemas
+    pub usages: Vec<Vec<Usage>>,        // where each component schema is used, by index
+}
+
+impl LoadedSpec {
+    /// Placeholder until a service is opened from the index.
+    pub fn empty() -> Self {
+        LoadedSpec {
+            title: String::new(),
+            openapi_version: String::new(),
+            version: SpecVersion::V30,
+            schema_names: Vec::new(),
+            schema_nodes: Vec::new(),
+            operations: Vec::new(),
+            operation_nodes: Vec::new(),
+            schema_sources: HashMap::new(),
+            diagnostics: Diagnostics::default(),
+            graph: Graph::default(),
+            usages: Vec::new(),
+        }
+    }
+}
+
+/// One path + method from `paths`.
+#[derive(Clone)]
+pub struct Operation {
+    pub path: String,
+    pub method: String, // lower-case, as written in the spec
+    pub tags: Vec<String>,
+    pub operation_id: Option<String>,
+    pub summary: Option<String>,
+    pub description: Option<String>,
+    pub deprecated: bool,
+    pub parameters: Vec<Parameter>,
+    pub request_body: Option<RequestBody>,
+    pub responses: Vec<Response>,
+}
+
+/// A path / query / header / cookie parameter. The schema node is named after
+/// the parameter and carries its `required` flag and description.
+#[derive(Clone)]
+pub struct Parameter {
+    pub location: String,
+    pub schema: TreeNode,
+    /// Where the schema is written: under the path item, the operation or a `$ref` target.
+    pub pointer: String,
+}
+
+#[derive(Clone)]
+pub struct RequestBody {
+    pub content_type: Option<String>,
+    pub description: Option<String>,
+    pub required: bool,
+    pub schema: Option<TreeNode>, // named `requestBody`
+    /// Where the schema (or the body, without one) is written; none for 2.0 form fields.
+    pub pointer: Option<String>,
+}
+
+#[derive(Clone)]
+pub struct Response {
+    pub status: String, // "200", "4XX", "default"
+    pub description: String,
+    pub content_type: Option<String>,
+    pub schema: Option<TreeNode>, // named after the status
+    /// The media type's `example`, or its first `examples` entry.
+    pub example: Option<serde_json::Value>,
+    pub pointer: String, // where the schema (or the response, without one) is written
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum SpecVersion {
-    #[allow(dead_code)]
     V20,
     V30,
     V31,
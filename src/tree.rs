@@ -1,11 +1,15 @@
+use std::collections::HashSet;
+
+use crate::search::{SearchMatch, find_matches};
+
 /// Kind of schema node.
 #[derive(Debug, Clone)]
 pub enum NodeKind {
     #[allow(dead_code)]
-    Schema,      // top-level schema root (no specific type)
+    Schema, // top-level schema root (no specific type)
     Object,
     Array,
-    Str,         // string (avoid shadowing std::string::String)
+    Str, // string (avoid shadowing std::string::String)
     Integer,
     Number,
     Boolean,
@@ -13,9 +17,64 @@ pub enum NodeKind {
     AllOf,
     OneOf,
     AnyOf,
+    Null,               // 3.1 `type: "null"`
+    Multi(Vec<String>), // 3.1 `type: [string, integer]` — non-null member types
+    Tuple,              // 3.1 `prefixItems` — children are the positional schemas
+    Conditional,        // 3.1 `if` / `then` / `else`
+    Not,
+    Group(String), // keyword container: `$defs`, `dependentSchemas`, `patternProperties`
+    Tag,           // operations view: a tag grouping operations
+    Operation(usize), // operations view: index into `LoadedSpec::operations`
+    NoContent,     // operations view: a body / response without a schema
+    ServiceGroup,  // services view: a `domain / team` grouping services
+    Service(usize), // services view: index into `Catalog::services`
     Unknown,
 }
 
+impl NodeKind {
+    pub fn label(&self) -> String {
+        match self {
+            NodeKind::Schema => "schema".to_string(),
+            NodeKind::Object => "object".to_string(),
+            NodeKind::Array => "array".to_string(),
+            NodeKind::Str => "string".to_string(),
+            NodeKind::Integer => "integer".to_string(),
+            NodeKind::Number => "number".to_string(),
+            NodeKind::Boolean => "boolean".to_string(),
+            NodeKind::Ref(t) => format!("→{}", t),
+            NodeKind::AllOf => "allOf".to_string(),
+            NodeKind::OneOf => "oneOf".to_string(),
+            NodeKind::AnyOf => "anyOf".to_string(),
+            NodeKind::Null => "null".to_string(),
+            NodeKind::Multi(types) => types.join(" | "),
+            NodeKind::Tuple => "tuple".to_string(),
+            NodeKind::Conditional => "if/then/else".to_string(),
+            NodeKind::Not => "not".to_string(),
+            NodeKind::Group(keyword) => keyword.clone(),
+            NodeKind::Tag => "tag".to_string(),
+            NodeKind::Operation(_) => "operation".to_string(),
+            NodeKind::NoContent => "no content".to_string(),
+            NodeKind::ServiceGroup => "group".to_string(),
+            NodeKind::Service(_) => "service".to_string(),
+            NodeKind::Unknown => "?".to_string(),
+        }
+    }
+
+    /// False for the nodes that only organise the tree: tags, operations,
+    /// keyword groups, services, and bodies without a schema.
+    pub fn is_schema(&self) -> bool {
+        !matches!(
+            self,
+            NodeKind::Tag
+                | NodeKind::Operation(_)
+                | NodeKind::NoContent
+                | NodeKind::ServiceGroup
+                | NodeKind::Service(_)
+                | NodeKind::Group(_)
+        )
+    }
+}
+
 /// Metadata for a tree node.
 #[derive(Debug, Clone)]
 pub struct NodeInfo {
@@ -27,6 +86,32 @@ pub struct NodeInfo {
     pub enum_values: Vec<String>,    // formatted enum variants
     pub example: Option<String>,     // JSON-formatted
     pub default_val: Option<String>, // JSON-formatted
+    pub nullable: bool,              // 3.0 `nullable: true` or 3.1 `type: [.., "null"]`
+    pub examples: Vec<String>,       // 3.1 `examples` array, JSON-formatted
+}
+
+impl NodeInfo {
+    /// The value of a `key: value` constraint ("maxLength: 255" → "255").
+    pub fn constraint(&self, key: &str) -> Option<&str> {
+        self.constraints
+            .iter()
+            .find_map(|c| c.strip_prefix(key)?.strip_prefix(": "))
+    }
+
+    /// A `min` / `max` constraint as `(limit, exclusive)`: "min: 0", "max: <10".
+    pub fn limit(&self, key: &str) -> Option<(f64, bool)> {
+        let raw = self.constraint(key)?;
+        let (raw, exclusive) = match raw.strip_prefix(['>', '<']) {
+            Some(rest) => (rest, true),
+            None => (raw, false),
+        };
+        Some((raw.trim().parse().ok()?, exclusive))
+    }
+
+    /// A count constraint such as `maxLength` or `minItems` ("minItems: 1" → 1).
+    pub fn bound(&self, key: &str) -> Option<usize> {
+        self.constraint(key)?.parse().ok()
+    }
 }
 
 /// A node in the schema tree.
@@ -44,20 +129,7 @@ impl TreeNode {
     }
 
     pub fn type_label(&self) -> String {
-        match &self.info.kind {
-            NodeKind::Schema  => "schema".to_string(),
-            NodeKind::Object  => "object".to_string(),
-            NodeKind::Array   => "array".to_string(),
-            NodeKind::Str     => "string".to_string(),
-            NodeKind::Integer => "integer".to_string(),
-            NodeKind::Number  => "number".to_string(),
-            NodeKind::Boolean => "boolean".to_string(),
-            NodeKind::Ref(t)  => format!("→{}", t),
-            NodeKind::AllOf   => "allOf".to_string(),
-            NodeKind::OneOf   => "oneOf".to_string(),
-            NodeKind::AnyOf   => "anyOf".to_string(),
-            NodeKind::Unknown => "?".to_string(),
-        }
+        self.info.kind.label()
     }
 }
 
@@ -65,28 +137,101 @@ impl TreeNode {
 pub struct FlatNode<'a> {
     pub node: &'a TreeNode,
     pub depth: usize,
+    pub path: Vec<usize>, // child indices from the root list down to this node
+    pub open: bool,       // children are listed (a filter forces ancestors open)
+}
+
+/// A filter applied on top of the tree: only matches and their ancestors are
+/// visible. `roots` (and their expansion state) are never touched by it.
+pub struct TreeFilter {
+    pub query: String,
+    pub matches: Vec<SearchMatch>,
+    matched: HashSet<Vec<usize>>,
+    ancestors: HashSet<Vec<usize>>, // shown, and forced open
+}
+
+impl TreeFilter {
+    fn new(roots: &[TreeNode], query: &str) -> Self {
+        let matches = find_matches(roots, query);
+        let ancestors = matches
+            .iter()
+            .flat_map(|m| (1..m.path.len()).map(|depth| m.path[..depth].to_vec()))
+            .collect();
+        let matched = matches.iter().map(|m| m.path.clone()).collect();
+        TreeFilter {
+            query: query.to_string(),
+            matches,
+            matched,
+            ancestors,
+        }
+    }
+
+    /// Name characters to highlight for the node at `path`, if it matched.
+    pub fn hits_at(&self, path: &[usize]) -> Option<&[usize]> {
+        self.matches
+            .iter()
+            .find(|m| m.path == path)
+            .map(|m| m.name_hits.as_slice())
+    }
+
+    /// Number of top-level nodes with at least one match in their subtree.
+    pub fn matched_roots(&self) -> usize {
+        let mut roots: Vec<usize> = self.matches.iter().map(|m| m.path[0]).collect();
+        roots.dedup();
+        roots.len()
+    }
 }
 
 /// Tree state: all schema roots + cursor into the flat visible list.
 pub struct TreeState {
     pub roots: Vec<TreeNode>,
     pub cursor: usize,
+    pub filter: Option<TreeFilter>,
 }
 
 impl TreeState {
     pub fn new(roots: Vec<TreeNode>) -> Self {
-        TreeState { roots, cursor: 0 }
+        TreeState {
+            roots,
+            cursor: 0,
+            filter: None,
+        }
     }
 
-    /// Flatten visible nodes depth-first, skipping collapsed subtrees.
+    /// Flatten visible nodes depth-first, skipping collapsed subtrees. With a
+    /// filter set, only matches and their (always open) ancestors are listed;
+    /// a match the user expands shows its whole subtree.
     pub fn flatten(&self) -> Vec<FlatNode<'_>> {
         let mut result = Vec::new();
-        for root in &self.roots {
-            flatten_node(root, 0, &mut result);
+        for (i, root) in self.roots.iter().enumerate() {
+            match &self.filter {
+                Some(filter) => flatten_filtered(root, vec![i], filter, &mut result),
+                None => flatten_node(root, vec![i], &mut result),
+            }
         }
         result
     }
 
+    /// Filter the visible list by `query` (an empty query clears the filter),
+    /// keeping the cursor on the same node when it is still visible.
+    pub fn set_filter(&mut self, query: &str) {
+        let here = self.cursor_path();
+        self.filter = (!query.is_empty()).then(|| TreeFilter::new(&self.roots, query));
+        self.reposition(here.as_deref());
+    }
+
+    /// Put the cursor on `path`, or its nearest visible ancestor, without
+    /// expanding anything.
+    fn reposition(&mut self, path: Option<&[usize]>) {
+        let flat = self.flatten();
+        let index = path.and_then(|path| {
+            (1..=path.len())
+                .rev()
+                .find_map(|len| flat.iter().position(|f| f.path == path[..len]))
+        });
+        self.cursor = index.unwrap_or(0);
+    }
+
     pub fn visible_count(&self) -> usize {
         self.flatten().len()
     }
@@ -95,6 +240,46 @@ impl TreeState {
         self.flatten().into_iter().nth(self.cursor).map(|f| f.node)
     }
 
+    /// The node under the cursor and its ancestors, root first.
+    pub fn selected_chain(&self) -> Vec<&TreeNode> {
+        match self.cursor_path() {
+            Some(path) => chain_at(&self.roots, &path),
+            None => Vec::new(),
+        }
+    }
+
+    /// Path (child indices from the roots) of the node under the cursor.
+    pub fn cursor_path(&self) -> Option<Vec<usize>> {
+        self.flatten().into_iter().nth(self.cursor).map(|f| f.path)
+    }
+
+    /// Move the cursor to the node at `path`, expanding its ancestors so it is
+    /// visible. Returns false (cursor untouched) if the path does not exist.
+    pub fn select_path(&mut self, path: &[usize]) -> bool {
+        if node_at(&self.roots, path).is_none() {
+            return false;
+        }
+        // Already visible (always the case for filter matches): leave expansion alone.
+        if let Some(index) = self.flatten().iter().position(|f| f.path == path) {
+            self.cursor = index;
+            return true;
+        }
+        for depth in 1..path.len() {
+            if let Some(ancestor) = node_at_mut(&mut self.roots, &path[..depth]) {
+                ancestor.expanded = true;
+            }
+        }
+        if let Some(index) = self.flatten().iter().position(|f| f.path == path) {
+            self.cursor = index;
+        }
+        true
+    }
+
+    /// Index of the top-level node with the given name.
+    pub fn root_index(&self, name: &str) -> Option<usize> {
+        self.roots.iter().position(|r| r.name == name)
+    }
+
     pub fn move_up(&mut self) {
         if self.cursor > 0 {
             self.cursor -= 1;
@@ -118,23 +303,21 @@ impl TreeState {
 
     /// Toggle expand/collapse on the node at the cursor.
     pub fn toggle_at_cursor(&mut self) {
-        let target = self.cursor;
-        let mut counter = 0;
-        toggle_at(&mut self.roots, target, &mut counter);
+        if let Some(node) = self.node_at_cursor_mut()
+            && node.is_expandable()
+        {
+            node.expanded = !node.expanded;
+        }
     }
 
     /// Expand (only) the node at the cursor.
     pub fn expand_at_cursor(&mut self) {
-        let target = self.cursor;
-        let mut counter = 0;
-        set_expanded_at(&mut self.roots, target, &mut counter, true);
+        self.set_expanded_at_cursor(true);
     }
 
     /// Collapse (only) the node at the cursor.
     pub fn collapse_at_cursor(&mut self) {
-        let target = self.cursor;
-        let mut counter = 0;
-        set_expanded_at(&mut self.roots, target, &mut counter, false);
+        self.set_expanded_at_cursor(false);
     }
 
     /// Expand every node in the entire tree.
@@ -147,58 +330,141 @@ impl TreeState {
         set_expanded_all(&mut self.roots, false);
         self.cursor = 0;
     }
+
+    /// Paths of every expanded node, so the expansion state can be put back later.
+    pub fn expanded_paths(&self) -> Vec<Vec<usize>> {
+        let mut out = Vec::new();
+        for (i, root) in self.roots.iter().enumerate() {
+            collect_expanded(root, vec![i], &mut out);
+        }
+        out
+    }
+
+    /// Expand exactly the nodes in `paths` (from `expanded_paths`), collapsing the rest.
+    pub fn restore_expanded(&mut self, paths: &[Vec<usize>]) {
+        set_expanded_all(&mut self.roots, false);
+        for path in paths {
+            if let Some(node) = node_at_mut(&mut self.roots, path) {
+                node.expanded = true;
+            }
+        }
+    }
+
+    fn set_expanded_at_cursor(&mut self, expanded: bool) {
+        if let Some(node) = self.node_at_cursor_mut()
+            && node.is_expandable()
+        {
+            node.expanded = expanded;
+        }
+    }
+
+    fn node_at_cursor_mut(&mut self) -> Option<&mut TreeNode> {
+        let path = self.cursor_path()?;
+        node_at_mut(&mut self.roots, &path)
+    }
 }
 
 // ── internal helpers ──────────────────────────────────────────────────────────
 
-fn flatten_node<'a>(node: &'a TreeNode, depth: usize, out: &mut Vec<FlatNode<'a>>) {
-    out.push(FlatNode { node, depth });
-    if node.expanded {
-        for child in &node.children {
-            flatten_node(child, depth + 1, out);
+fn flatten_filtered<'a>(
+    node: &'a TreeNode,
+    path: Vec<usize>,
+    filter: &TreeFilter,
+    out: &mut Vec<FlatNode<'a>>,
+) {
+    if filter.ancestors.contains(&path) {
+        out.push(FlatNode {
+            node,
+            depth: path.len() - 1,
+            path: path.clone(),
+            open: true,
+        });
+        for (i, child) in node.children.iter().enumerate() {
+            let mut child_path = path.clone();
+            child_path.push(i);
+            flatten_filtered(child, child_path, filter, out);
         }
+    } else if filter.matched.contains(&path) {
+        flatten_node(node, path, out);
     }
 }
 
-/// Walk visible nodes counting as we go; toggle the node whose flat index == target.
-fn toggle_at(nodes: &mut Vec<TreeNode>, target: usize, counter: &mut usize) -> bool {
-    for node in nodes.iter_mut() {
-        if *counter == target {
-            if node.is_expandable() {
-                node.expanded = !node.expanded;
-            }
-            return true;
-        }
-        *counter += 1;
-        if node.expanded && toggle_at(&mut node.children, target, counter) {
-            return true;
+fn flatten_node<'a>(node: &'a TreeNode, path: Vec<usize>, out: &mut Vec<FlatNode<'a>>) {
+    let depth = path.len() - 1;
+    out.push(FlatNode {
+        node,
+        depth,
+        path: path.clone(),
+        open: node.expanded,
+    });
+    if node.expanded {
+        for (i, child) in node.children.iter().enumerate() {
+            let mut child_path = path.clone();
+            child_path.push(i);
+            flatten_node(child, child_path, out);
         }
     }
-    false
 }
 
-fn set_expanded_at(
-    nodes: &mut Vec<TreeNode>,
-    target: usize,
-    counter: &mut usize,
-    expanded: bool,
-) -> bool {
-    for node in nodes.iter_mut() {
-        if *counter == target {
-            if node.is_expandable() {
-                node.expanded = expanded;
-            }
-            return true;
+fn collect_expanded(node: &TreeNode, path: Vec<usize>, out: &mut Vec<Vec<usize>>) {
+    if !node.expanded {
+        return;
+    }
+    for (i, child) in node.children.iter().enumerate() {
+        let mut child_path = path.clone();
+        child_path.push(i);
+        collect_expanded(child, child_path, out);
+    }
+    out.push(path);
+}
+
+/// Every node along `path`, root first.
+pub fn chain_at<'a>(roots: &'a [TreeNode], path: &[usize]) -> Vec<&'a TreeNode> {
+    (1..=path.len())
+        .filter_map(|depth| node_at(roots, &path[..depth]))
+        .collect()
+}
+
+/// Path of the shallowest node named `name` under `roots[root]`, staying out of
+/// `$ref` targets so a property is found in its own schema, not a referenced one.
+pub fn find_descendant(roots: &[TreeNode], root: usize, name: &str) -> Option<Vec<usize>> {
+    let mut queue = std::collections::VecDeque::from([vec![root]]);
+    while let Some(path) = queue.pop_front() {
+        let node = node_at(roots, &path)?;
+        if path.len() > 1 && node.name == name {
+            return Some(path);
         }
-        *counter += 1;
-        if node.expanded && set_expanded_at(&mut node.children, target, counter, expanded) {
-            return true;
+        if matches!(node.info.kind, NodeKind::Ref(_)) {
+            continue;
         }
+        for i in 0..node.children.len() {
+            let mut child = path.clone();
+            child.push(i);
+            queue.push_back(child);
+        }
+    }
+    None
+}
+
+fn node_at<'a>(roots: &'a [TreeNode], path: &[usize]) -> Option<&'a TreeNode> {
+    let (first, rest) = path.split_first()?;
+    let mut node = roots.get(*first)?;
+    for &i in rest {
+        node = node.children.get(i)?;
+    }
+    Some(node)
+}
+
+fn node_at_mut<'a>(roots: &'a mut [TreeNode], path: &[usize]) -> Option<&'a mut TreeNode> {
+    let (first, rest) = path.split_first()?;
+    let mut node = roots.get_mut(*first)?;
+    for &i in rest {
+        node = node.children.get_mut(i)?;
     }
-    false
+    Some(node)
 }
 
-fn set_expanded_all(nodes: &mut Vec<TreeNode>, expanded: bool) {
+fn set_expanded_all(nodes: &mut [TreeNode], expanded: bool) {
     for node in nodes.iter_mut() {
         if node.is_expandable() {
             node.expanded = expanded;
@@ -206,3 +472,83 @@ fn set_expanded_all(nodes: &mut Vec<TreeNode>, expanded: bool) {
         set_expanded_all(&mut node.children, expanded);
     }
 }
+
+// ── tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PETSTORE: &str = include_str!("../fixtures/petstore.yaml");
+
+    fn petstore_tree() -> TreeState {
+        let spec = crate::parser::parse_spec(PETSTORE).expect("should parse petstore fixture");
+        TreeState::new(spec.schema_nodes)
+    }
+
+    #[test]
+    fn flatten_tracks_paths() {
+        let mut tree = petstore_tree();
+        tree.cursor = 3; // Pets
+        tree.expand_at_cursor();
+        let paths: Vec<Vec<usize>> = tree.flatten().into_iter().map(|f| f.path).collect();
+        assert_eq!(paths, vec![vec![0], vec![1], vec![2], vec![3], vec![3, 0]]);
+    }
+
+    #[test]
+    fn select_path_expands_ancestors() {
+        let mut tree = petstore_tree();
+        // Pets › items → Pet › name
+        assert!(tree.select_path(&[3, 0, 1]));
+        assert_eq!(tree.selected_node().unwrap().name, "name");
+        assert_eq!(tree.cursor_path(), Some(vec![3, 0, 1]));
+        assert!(!tree.select_path(&[3, 9]));
+    }
+
+    #[test]
+    fn filter_shows_matches_and_ancestors_only() {
+        let mut tree = petstore_tree();
+        tree.set_filter("tag");
+        let paths: Vec<Vec<usize>> = tree.flatten().into_iter().map(|f| f.path).collect();
+        assert_eq!(
+            paths,
+            vec![
+                vec![1],
+                vec![1, 1],
+                vec![2],
+                vec![2, 2],
+                vec![3],
+                vec![3, 0],
+                vec![3, 0, 2]
+            ]
+        );
+        assert_eq!(tree.filter.as_ref().unwrap().matched_roots(), 3);
+        // Nothing was expanded underneath.
+        assert!(tree.expanded_paths().is_empty());
+    }
+
+    #[test]
+    fn clearing_filter_keeps_expansion_and_cursor() {
+        let mut tree = petstore_tree();
+        tree.select_path(&[2]);
+        tree.expand_at_cursor(); // Pet open
+        tree.set_filter("tag");
+        tree.select_path(&[3, 0, 2]);
+        tree.set_filter("");
+        assert_eq!(tree.expanded_paths(), vec![vec![2]]);
+        // Pets › items is collapsed again, so the cursor falls back to Pets.
+        assert_eq!(tree.selected_node().unwrap().name, "Pets");
+    }
+
+    #[test]
+    fn selected_chain_runs_root_to_cursor() {
+        let mut tree = petstore_tree();
+        tree.select_path(&[3, 0, 1]);
+        let names: Vec<&str> = tree
+            .selected_chain()
+            .iter()
+            .map(|n| n.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["Pets", "items", "name"]);
+    }
+}
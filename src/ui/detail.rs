@@ -6,8 +6,15 @@ use ratatui::{
     widgets::{Block, BorderType, Borders, Paragraph, Wrap},
 };
 
-use crate::app::{App, Pane};
-use crate::tree::{NodeKind, TreeNode};
+use crate::app::{App, Pane, View};
+use crate::breadcrumb;
+use crate::catalog::ServiceInfo;
+use crate::diagnostics::Diagnostic;
+use crate::diff::{Mark, Status};
+use crate::example;
+use crate::spec::{LoadedSpec, Operation};
+use crate::tree::{NodeInfo, NodeKind, TreeNode};
+use crate::ui::schema_list::{diff_marker, diff_style, marker, method_style};
 
 pub fn draw(frame: &mut Frame, app: &App, area: Rect) {
     let focused = app.focused_pane == Pane::Detail;
@@ -27,7 +34,7 @@ pub fn draw(frame: &mut Frame, app: &App, area: Rect) {
         .border_style(border_style)
         .title(" Detail ");
 
-    match app.tree.selected_node() {
+    match app.active_tree().selected_node() {
         None => {
             let paragraph = Paragraph::new("  No schema selected.")
                 .style(Style::default().fg(Color::DarkGray))
@@ -35,7 +42,41 @@ pub fn draw(frame: &mut Frame, app: &App, area: Rect) {
             frame.render_widget(paragraph, area);
         }
         Some(node) => {
-            let content = build_detail_lines(node);
+            let chain = app.active_tree().selected_chain();
+            let ancestors = breadcrumb::label(&chain[..chain.len().saturating_sub(1)]);
+            let pointer = app.selected_pointer();
+            let path = app.active_tree().cursor_path().unwrap_or_default();
+            let mark = app
+                .diff
+                .as_ref()
+                .and_then(|marks| marks.at(app.view, &path));
+            let content = match &node.info.kind {
+                NodeKind::Operation(index) => {
+                    build_operation_lines(&app.spec.operations[*index], pointer.as_deref(), mark)
+                }
+                NodeKind::Service(index) => match &app.catalog {
+                    Some(catalog) => build_service_lines(&catalog.services[*index]),
+                    None => Vec::new(),
+                },
+                _ => {
+                    let source = breadcrumb::source_file(&app.spec, &chain);
+                    let diagnostics = app.spec.diagnostics.at(app.view, &path);
+                    let example = example::generate(node, &app.spec.schema_nodes);
+                    let mut lines = build_detail_lines(
+                        node,
+                        &ancestors,
+                        pointer.as_deref(),
+                        source,
+                        &diagnostics,
+                        mark,
+                        example.as_ref(),
+                    );
+                    if let (View::Schemas, [index]) = (app.view, path.as_slice()) {
+                        lines.extend(build_reference_lines(&app.spec, *index));
+                    }
+                    lines
+                }
+            };
             let paragraph = Paragraph::new(content)
                 .block(block)
                 .wrap(Wrap { trim: false })
@@ -78,18 +119,354 @@ fn separator() -> Line<'static> {
     ))
 }
 
-fn build_detail_lines(node: &TreeNode) -> Vec<Line<'static>> {
+/// The JSON Pointer line under a detail header (`yp` copies it).
+fn pointer_line(pointer: &str) -> Line<'static> {
+    Line::from(Span::styled(
+        format!("  {pointer}"),
+        Style::default().fg(Color::DarkGray),
+    ))
+}
+
+fn section(title: &str) -> Line<'static> {
+    Line::from(Span::styled(
+        format!("  {title}"),
+        Style::default()
+            .fg(Color::Gray)
+            .add_modifier(Modifier::BOLD),
+    ))
+}
+
+/// Type badge for a schema node, with format and a trailing `*` when required.
+fn schema_badge(node: &TreeNode) -> Vec<Span<'static>> {
+    let mut label = node.type_label();
+    if let Some(f) = &node.info.format {
+        label = format!("{label} ({f})");
+    }
+    let mut spans = vec![Span::styled(label, Style::default().fg(Color::Cyan))];
+    if node.info.required {
+        spans.push(Span::styled(" *", Style::default().fg(Color::Yellow)));
+    }
+    spans
+}
+
+fn build_service_lines(service: &ServiceInfo) -> Vec<Line<'static>> {
+    let mut lines: Vec<Line<'static>> = vec![
+        Line::from(vec![
+            Span::raw("  "),
+            Span::styled(
+                service.name.clone(),
+                Style::default()
+                    .fg(Color::White)
+                    .add_modifier(Modifier::BOLD),
+            ),
+        ]),
+        separator(),
+    ];
+
+    let fields = [
+        ("title", &service.spec_title),
+        ("version", &service.spec_version),
+        ("openapi", &service.openapi_version),
+        ("team", &service.team),
+        ("domain", &service.domain),
+        ("repo", &service.repo),
+        ("cached", &service.cached_at),
+        ("indexed", &service.indexed_at),
+    ];
+    for (key, value) in fields {
+        if let Some(value) = value {
+            lines.push(kv_str(key, value.clone()));
+        }
+    }
+    lines.push(kv_str("schemas", service.schema_count.to_string()));
+    lines.push(kv_str("operations", service.path_count.to_string()));
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        "  l / Enter to open · S to come back here",
+        Style::default().fg(Color::DarkGray),
+    )));
+    lines
+}
+
+fn build_operation_lines(
+    op: &Operation,
+    pointer: Option<&str>,
+    mark: Option<&Mark>,
+) -> Vec<Line<'static>> {
+    let mut lines: Vec<Line<'static>> = Vec::new();
+    let method = op.method.to_uppercase();
+
+    // ── Header: method + path ────────────────────────────────────────────────
+    lines.push(Line::from(vec![
+        Span::raw("  "),
+        Span::styled(method.clone(), method_style(&method)),
+        Span::raw(" "),
+        Span::styled(
+            op.path.clone(),
+            Style::default()
+                .fg(Color::White)
+                .add_modifier(Modifier::BOLD),
+        ),
+    ]));
+    if let Some(pointer) = pointer {
+        lines.push(pointer_line(pointer));
+    }
+    lines.push(separator());
+    if let Some(mark) = mark {
+        lines.extend(build_comparison_lines(mark, false));
+    }
+
+    if let Some(id) = &op.operation_id {
+        lines.push(kv_str("operationId", id.clone()));
+    }
+    if let Some(summary) = &op.summary {
+        lines.push(kv_str("summary", summary.clone()));
+    }
+    if let Some(desc) = &op.description {
+        lines.push(kv_str("description", desc.clone()));
+    }
+    if !op.tags.is_empty() {
+        lines.push(kv_str("tags", op.tags.join(" · ")));
+    }
+    if op.deprecated {
+        lines.push(kv_line(
+            "deprecated",
+            vec![Span::styled("yes", Style::default().fg(Color::Red))],
+        ));
+    }
+
+    // ── Parameters ───────────────────────────────────────────────────────────
+    if !op.parameters.is_empty() {
+        lines.push(Line::from(""));
+        lines.push(section("Parameters"));
+        for param in &op.parameters {
+            let mut spans = vec![Span::styled(
+                format!("{:<8}", param.location),
+                Style::default().fg(Color::DarkGray),
+            )];
+            spans.extend(schema_badge(&param.schema));
+            lines.push(kv_line(&param.schema.name, spans));
+            if let Some(desc) = &param.schema.info.description {
+                lines.push(kv_str("", desc.clone()));
+            }
+        }
+    }
+
+    // ── Request body ─────────────────────────────────────────────────────────
+    if let Some(body) = &op.request_body {
+        lines.push(Line::from(""));
+        lines.push(section("Request body"));
+        if let Some(mime) = &body.content_type {
+            lines.push(kv_str("content", mime.clone()));
+        }
+        if body.required {
+            lines.push(kv_line(
+                "required",
+                vec![Span::styled("yes", Style::default().fg(Color::Yellow))],
+            ));
+        }
+        if let Some(schema) = &body.schema {
+            lines.push(kv_line("schema", schema_badge(schema)));
+        }
+        if let Some(desc) = &body.description {
+            lines.push(kv_str("description", desc.clone()));
+        }
+    }
+
+    // ── Responses ────────────────────────────────────────────────────────────
+    if !op.responses.is_empty() {
+        lines.push(Line::from(""));
+        lines.push(section("Responses"));
+        for resp in &op.responses {
+            let mut spans = match &resp.schema {
+                Some(schema) => schema_badge(schema),
+                None => vec![Span::styled(
+                    "no content",
+                    Style::default().fg(Color::DarkGray),
+                )],
+            };
+            if let Some(mime) = &resp.content_type {
+                spans.push(Span::styled(
+                    format!("  {mime}"),
+                    Style::default().fg(Color::DarkGray),
+                ));
+            }
+            lines.push(kv_line(&resp.status, spans));
+            if !resp.description.is_empty() {
+                lines.push(kv_str("", resp.description.clone()));
+            }
+        }
+    }
+
+    lines.push(Line::from(""));
+    lines
+}
+
+/// Diff mode: what happened to the node, then (for schema nodes) its fields
+/// in the old and the new spec side by side, differences coloured.
+fn build_comparison_lines(mark: &Mark, fields: bool) -> Vec<Line<'static>> {
+    let status = match mark.status {
+        Status::Added => "added — only in the new spec",
+        Status::Removed => "removed — only in the old spec",
+        Status::Changed => "changed",
+        Status::Inside => "changed below",
+    };
+    let mut lines = vec![kv_line(
+        "diff",
+        vec![
+            diff_marker(mark.status),
+            Span::styled(format!(" {status}"), diff_style(mark.status)),
+        ],
+    )];
+    if !fields || mark.status == Status::Inside {
+        return lines;
+    }
+
+    const COLUMN: usize = 28;
+    let heading = Style::default()
+        .fg(Color::Gray)
+        .add_modifier(Modifier::BOLD);
+    lines.push(kv_line(
+        "",
+        vec![
+            Span::styled(format!("{:<COLUMN$}", "old"), heading),
+            Span::styled("new", heading),
+        ],
+    ));
+    let old = comparison_fields(mark.old.as_ref());
+    let new = comparison_fields(mark.new.as_ref());
+    for ((key, old), (_, new)) in old.into_iter().zip(new) {
+        if old.is_empty() && new.is_empty() {
+            continue;
+        }
+        let (old_style, new_style) = if old == new {
+            (
+                Style::default().fg(Color::Gray),
+                Style::default().fg(Color::Gray),
+            )
+        } else {
+            (
+                Style::default().fg(Color::Red),
+                Style::default().fg(Color::Green),
+            )
+        };
+        let shown = |value: String| {
+            if value.is_empty() {
+                "—".to_string()
+            } else {
+                value
+            }
+        };
+        lines.push(kv_line(
+            key,
+            vec![
+                Span::styled(format!("{:<COLUMN$}", shown(old)), old_style),
+                Span::styled(shown(new), new_style),
+            ],
+        ));
+    }
+    lines.push(separator());
+    lines
+}
+
+/// The fields compared side by side; empty when unset (or the node is absent).
+fn comparison_fields(info: Option<&NodeInfo>) -> [(&'static str, String); 6] {
+    let Some(info) = info else {
+        return [
+            "type",
+            "format",
+            "required",
+            "nullable",
+            "constraints",
+            "enum",
+        ]
+        .map(|key| (key, String::new()));
+    };
+    let flag = |set: bool| {
+        if set {
+            "yes".to_string()
+        } else {
+            String::new()
+        }
+    };
+    [
+        ("type", info.kind.label()),
+        ("format", info.format.clone().unwrap_or_default()),
+        ("required", flag(info.required)),
+        ("nullable", flag(info.nullable)),
+        ("constraints", info.constraints.join(" · ")),
+        ("enum", info.enum_values.join(" · ")),
+    ]
+}
+
+/// The component schemas a top-level schema refers to and is referred to by;
+/// `↻` marks a reference on a cycle.
+fn build_reference_lines(spec: &LoadedSpec, index: usize) -> Vec<Line<'static>> {
+    let graph = &spec.graph;
+    let (Some(references), Some(referrers)) =
+        (graph.references.get(index), graph.referrers.get(index))
+    else {
+        return Vec::new();
+    };
+    let mut lines = Vec::new();
+    for (title, outgoing, others) in [
+        ("References", true, references),
+        ("Referenced by", false, referrers),
+    ] {
+        lines.push(section(title));
+        if others.is_empty() {
+            lines.push(Line::from(Span::styled(
+                "    none",
+                Style::default().fg(Color::DarkGray),
+            )));
+        }
+        for &other in others {
+            let (arrow, from, to) = if outgoing {
+                ("→", index, other)
+            } else {
+                ("←", other, index)
+            };
+            let mut spans = vec![Span::styled(
+                format!("    {arrow}{}", spec.schema_nodes[other].name),
+                Style::default().fg(Color::Cyan),
+            )];
+            if graph.on_cycle(from, to) {
+                spans.push(Span::styled(" ↻", Style::default().fg(Color::Yellow)));
+            }
+            lines.push(Line::from(spans));
+        }
+        lines.push(Line::from(""));
+    }
+    lines
+}
+
+fn build_detail_lines(
+    node: &TreeNode,
+    ancestors: &str,
+    pointer: Option<&str>,
+    source: Option<&str>,
+    diagnostics: &[&Diagnostic],
+    mark: Option<&Mark>,
+    example: Option<&serde_json::Value>,
+) -> Vec<Line<'static>> {
     let mut lines: Vec<Line<'static>> = Vec::new();
 
-    // ── Header: name + type ──────────────────────────────────────────────────
+    // ── Header: breadcrumb + type, the JSON Pointer, the file it came from ───
     let type_label = node.type_label();
     let format_suffix = match &node.info.format {
         Some(f) => format!(" ({})", f),
         None => String::new(),
     };
 
+    let trail = if ancestors.is_empty() {
+        String::new()
+    } else {
+        format!("{ancestors} › ")
+    };
+
     lines.push(Line::from(vec![
         Span::raw("  "),
+        Span::styled(trail, Style::default().fg(Color::DarkGray)),
         Span::styled(
             node.name.clone(),
             Style::default()
@@ -102,9 +479,33 @@ fn build_detail_lines(node: &TreeNode) -> Vec<Line<'static>> {
             Style::default().fg(Color::Cyan),
         ),
     ]));
+    if let Some(pointer) = pointer {
+        lines.push(pointer_line(pointer));
+    }
+    if let Some(source) = source {
+        lines.push(Line::from(Span::styled(
+            format!("  from {source}"),
+            Style::default().fg(Color::DarkGray),
+        )));
+    }
 
     lines.push(separator());
 
+    // ── Diff mode: old vs new ────────────────────────────────────────────────
+    if let Some(mark) = mark {
+        lines.extend(build_comparison_lines(mark, true));
+    }
+
+    // ── Diagnostics ──────────────────────────────────────────────────────────
+    for diagnostic in diagnostics {
+        let mut spans: Vec<Span<'static>> = marker(&[diagnostic]).into_iter().collect();
+        spans.push(Span::styled(
+            format!(" {}", diagnostic.message),
+            Style::default().fg(Color::Yellow),
+        ));
+        lines.push(kv_line(diagnostic.kind.label(), spans));
+    }
+
     // ── Required ─────────────────────────────────────────────────────────────
     if node.info.required {
         lines.push(kv_line(
@@ -113,6 +514,13 @@ fn build_detail_lines(node: &TreeNode) -> Vec<Line<'static>> {
         ));
     }
 
+    if node.info.nullable {
+        lines.push(kv_line(
+            "nullable",
+            vec![Span::styled("yes", Style::default().fg(Color::Yellow))],
+        ));
+    }
+
     // ── Description ──────────────────────────────────────────────────────────
     if let Some(desc) = &node.info.description {
         lines.push(kv_str("description", desc.clone()));
@@ -159,13 +567,50 @@ fn build_detail_lines(node: &TreeNode) -> Vec<Line<'static>> {
         }
 
         NodeKind::AllOf => {
-            lines.push(kv_str("combiner", format!("allOf ({} schemas)", node.children.len())));
+            lines.push(kv_str(
+                "combiner",
+                format!("allOf ({} schemas)", node.children.len()),
+            ));
         }
         NodeKind::OneOf => {
-            lines.push(kv_str("combiner", format!("oneOf ({} schemas)", node.children.len())));
+            lines.push(kv_str(
+                "combiner",
+                format!("oneOf ({} schemas)", node.children.len()),
+            ));
         }
         NodeKind::AnyOf => {
-            lines.push(kv_str("combiner", format!("anyOf ({} schemas)", node.children.len())));
+            lines.push(kv_str(
+                "combiner",
+                format!("anyOf ({} schemas)", node.children.len()),
+            ));
+        }
+
+        NodeKind::Multi(types) => {
+            lines.push(kv_str("types", types.join(" · ")));
+        }
+        NodeKind::Tuple => {
+            let positional = node
+                .children
+                .iter()
+                .filter(|c| c.name.starts_with('['))
+                .count();
+            lines.push(kv_str(
+                "prefixItems",
+                format!("{positional} positional schemas"),
+            ));
+        }
+        NodeKind::Conditional => {
+            let branches: Vec<String> = node.children.iter().map(|c| c.name.clone()).collect();
+            lines.push(kv_str("conditional", branches.join(" · ")));
+        }
+        NodeKind::Group(keyword) => {
+            lines.push(kv_str(keyword, format!("{} entries", node.children.len())));
+        }
+        NodeKind::Tag => {
+            lines.push(kv_str("operations", node.children.len().to_string()));
+        }
+        NodeKind::ServiceGroup => {
+            lines.push(kv_str("services", node.children.len().to_string()));
         }
 
         _ => {}
@@ -188,9 +633,169 @@ fn build_detail_lines(node: &TreeNode) -> Vec<Line<'static>> {
     if let Some(example) = &node.info.example {
         lines.push(kv_str("example", example.clone()));
     }
+    for example in &node.info.examples {
+        lines.push(kv_str("examples", example.clone()));
+    }
+
+    // ── Example payload (`ye` copies it) ─────────────────────────────────────
+    if let Some(example) = example {
+        lines.push(Line::from(""));
+        lines.push(section("Example"));
+        let json = serde_json::to_string_pretty(example).unwrap_or_default();
+        for line in json.lines() {
+            lines.push(Line::from(Span::styled(
+                format!("    {line}"),
+                Style::default().fg(Color::Green),
+            )));
+        }
+    }
 
     // Trailing blank line for breathing room
     lines.push(Line::from(""));
 
     lines
 }
+
+// ── tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diff::{Marks, union};
+    use crate::parser::parse_spec;
+
+    const OLD: &str = r##"
+openapi: 3.0.3
+info: { title: Accounts, version: "1" }
+paths:
+  /accounts:
+    get:
+      responses:
+        "200": { description: ok }
+components:
+  schemas:
+    Account:
+      type: object
+      required: [email]
+      properties:
+        email: { type: string, maxLength: 80 }
+        phone: { type: string }
+"##;
+
+    const NEW: &str = r##"
+openapi: 3.0.3
+info: { title: Accounts, version: "2" }
+paths:
+  /accounts:
+    get:
+      summary: List accounts
+      parameters:
+        - { name: page, in: query, schema: { type: integer } }
+      responses:
+        "200": { description: ok }
+components:
+  schemas:
+    Account:
+      type: object
+      properties:
+        email: { type: string, format: email, maxLength: 80 }
+        age: { type: integer }
+"##;
+
+    fn marks() -> (LoadedSpec, Marks) {
+        union(parse_spec(OLD).unwrap(), parse_spec(NEW).unwrap())
+    }
+
+    /// Each line's text, split into words.
+    fn words(lines: &[Line]) -> Vec<Vec<String>> {
+        lines
+            .iter()
+            .map(|line| {
+                let text: String = line.spans.iter().map(|s| s.content.as_ref()).collect();
+                text.split_whitespace().map(str::to_string).collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn compares_a_changed_node_side_by_side() {
+        let (_, marks) = marks();
+        let email = marks.at(View::Schemas, &[0, 0]).unwrap();
+        let lines = build_comparison_lines(email, true);
+        let rows = words(&lines);
+        assert_eq!(rows[0], ["diff", "~", "changed"]);
+        assert_eq!(rows[1], ["old", "new"]);
+        // Fields unset on both sides are left out; unset on one side show a dash.
+        assert_eq!(rows[2], ["type", "string", "string"]);
+        assert_eq!(rows[3], ["format", "—", "email"]);
+        assert_eq!(rows[4], ["required", "yes", "—"]);
+        assert_eq!(
+            rows[5],
+            ["constraints", "maxLength:", "80", "maxLength:", "80"]
+        );
+        assert_eq!(rows.len(), 7, "ends with a separator");
+
+        // Differing fields are red (old) and green (new), the same ones grey.
+        let colours = |line: &Line| (line.spans[2].style.fg, line.spans[3].style.fg);
+        assert_eq!(colours(&lines[2]), (Some(Color::Gray), Some(Color::Gray)));
+        assert_eq!(colours(&lines[3]), (Some(Color::Red), Some(Color::Green)));
+        assert_eq!(colours(&lines[4]), (Some(Color::Red), Some(Color::Green)));
+    }
+
+    #[test]
+    fn compares_added_and_removed_nodes_against_nothing() {
+        let (spec, marks) = marks();
+        let account = &spec.schema_nodes[0];
+        let names: Vec<&str> = account.children.iter().map(|n| n.name.as_str()).collect();
+        assert_eq!(names, ["email", "phone", "age"]);
+
+        let compare = |path: &[usize]| {
+            words(&build_comparison_lines(
+                marks.at(View::Schemas, path).unwrap(),
+                true,
+            ))
+        };
+        let removed = compare(&[0, 1]);
+        assert_eq!(
+            removed[0],
+            [
+                "diff", "−", "removed", "—", "only", "in", "the", "old", "spec"
+            ]
+        );
+        assert_eq!(removed[2], ["type", "string", "—"]);
+
+        let added = compare(&[0, 2]);
+        assert_eq!(
+            added[0],
+            [
+                "diff", "+", "added", "—", "only", "in", "the", "new", "spec"
+            ]
+        );
+        assert_eq!(added[2], ["type", "—", "integer"]);
+    }
+
+    #[test]
+    fn shows_only_the_status_for_changes_below_and_for_operations() {
+        let (spec, marks) = marks();
+        let account = marks.at(View::Schemas, &[0]).unwrap();
+        let rows = words(&build_comparison_lines(account, true));
+        assert_eq!(rows, [["diff", "~", "changed", "below"]]);
+
+        // Operations show no field table, even for a change of their own.
+        let email = marks.at(View::Schemas, &[0, 0]).unwrap();
+        assert_eq!(
+            words(&build_comparison_lines(email, false)),
+            [["diff", "~", "changed"]]
+        );
+
+        let operation = marks.at(View::Operations, &[0, 0]).unwrap();
+        let rows = words(&build_operation_lines(
+            &spec.operations[0],
+            None,
+            Some(operation),
+        ));
+        assert_eq!(rows[0], ["GET", "/accounts"]);
+        assert_eq!(rows[2], ["diff", "~", "changed", "below"]);
+        assert_eq!(rows[3], ["summary", "List", "accounts"]);
+    }
+}
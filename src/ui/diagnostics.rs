@@ -0,0 +1,58 @@
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Borders, List, ListItem, ListState},
+};
+
+use crate::app::App;
+use crate::ui::schema_list::marker;
+
+/// The `D` panel under the two panes: one row per diagnostic, `Enter` jumps.
+pub fn draw(frame: &mut Frame, app: &App, area: Rect) {
+    let Some(selected) = app.diagnostics_panel else {
+        return;
+    };
+    let diagnostics = &app.spec.diagnostics.list;
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(Color::Cyan))
+        .title(format!(" Diagnostics ({}) ", diagnostics.len()));
+
+    let items: Vec<ListItem> = diagnostics
+        .iter()
+        .map(|d| {
+            let mut spans = vec![Span::raw(" ")];
+            spans.extend(marker(&[d]));
+            spans.push(Span::styled(
+                format!(" {:<14}", d.kind.label()),
+                Style::default().fg(Color::Gray),
+            ));
+            spans.push(Span::raw(d.message.clone()));
+            spans.push(Span::styled(
+                format!("  {}", d.pointer),
+                Style::default().fg(Color::DarkGray),
+            ));
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+
+    let list = List::new(items).block(block).highlight_style(
+        Style::default()
+            .bg(Color::DarkGray)
+            .add_modifier(Modifier::BOLD),
+    );
+    let mut state = ListState::default();
+    state.select(Some(selected));
+    frame.render_stateful_widget(list, area, &mut state);
+}
+
+/// Rows the panel takes below the panes: its entries plus borders, at most 12.
+pub fn height(app: &App) -> u16 {
+    match app.diagnostics_panel {
+        Some(_) => (app.spec.diagnostics.list.len() as u16 + 2).min(12),
+        None => 0,
+    }
+}
@@ -0,0 +1,88 @@
+use ratatui::{
+    Frame,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Borders, Clear, List, ListItem, ListState, Paragraph},
+};
+
+use crate::app::App;
+use crate::fts::{self, GlobalSearch};
+use crate::ui::centered;
+
+/// The `gs` popup: a prompt over a ranked list of hits from every service.
+pub fn draw(frame: &mut Frame, app: &App, area: Rect) {
+    let Some(global) = &app.global else {
+        return;
+    };
+    let area = centered(area, 80, 70);
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(Color::Cyan))
+        .title(" Search all services ");
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(2), Constraint::Min(0)])
+        .split(inner);
+
+    let count = match global.hits.len() {
+        0 if global.query.is_empty() => String::new(),
+        0 => "  no match".to_string(),
+        n => format!("  {n} hits"),
+    };
+    let prompt = Paragraph::new(Line::from(vec![
+        Span::styled(" > ", Style::default().fg(Color::Cyan)),
+        Span::raw(global.query.clone()),
+        Span::styled("█", Style::default().fg(Color::Cyan)),
+        Span::styled(count, Style::default().fg(Color::DarkGray)),
+    ]));
+    frame.render_widget(prompt, rows[0]);
+
+    let list = List::new(hit_items(global)).highlight_style(
+        Style::default()
+            .bg(Color::DarkGray)
+            .add_modifier(Modifier::BOLD),
+    );
+    let mut state = ListState::default();
+    state.select((!global.hits.is_empty()).then_some(global.selected));
+    frame.render_stateful_widget(list, rows[1], &mut state);
+}
+
+fn hit_items(global: &GlobalSearch) -> Vec<ListItem<'static>> {
+    global
+        .hits
+        .iter()
+        .map(|hit| {
+            let mut label = vec![Span::raw(" ")];
+            label.extend(highlighted(&hit.label(), Style::default().fg(Color::White)));
+            let mut lines = vec![Line::from(label)];
+            if !hit.snippet.is_empty() {
+                let mut snippet = vec![Span::raw("     ")];
+                snippet.extend(highlighted(&hit.snippet, Style::default().fg(Color::Gray)));
+                lines.push(Line::from(snippet));
+            }
+            ListItem::new(lines)
+        })
+        .collect()
+}
+
+/// FTS5-marked text as spans, matched terms in bold yellow.
+fn highlighted(text: &str, base: Style) -> Vec<Span<'static>> {
+    fts::segments(text)
+        .into_iter()
+        .map(|(run, is_match)| {
+            let style = if is_match {
+                base.fg(Color::Yellow).add_modifier(Modifier::BOLD)
+            } else {
+                base
+            };
+            Span::styled(run, style)
+        })
+        .collect()
+}
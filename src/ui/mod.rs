@@ -1,13 +1,17 @@
 use ratatui::{
     Frame,
-    layout::{Constraint, Direction, Layout},
+    layout::{Constraint, Direction, Layout, Rect},
 };
 
 use crate::app::App;
 
 mod detail;
+mod diagnostics;
+mod global_search;
 mod schema_list;
 mod statusbar;
+mod usages;
+mod validation;
 
 pub fn draw(frame: &mut Frame, app: &App) {
     let area = frame.area();
@@ -25,15 +29,40 @@ pub fn draw(frame: &mut Frame, app: &App) {
     // Header
     crate::ui::schema_list::draw_header(frame, app, outer[0]);
 
+    // Diagnostics or validation panel (when open) under the panes
+    let panel = diagnostics::height(app).max(validation::height(app));
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(panel)])
+        .split(outer[1]);
+    diagnostics::draw(frame, app, rows[1]);
+    validation::draw(frame, app, rows[1]);
+
     // Body: left pane (35%) / right pane (65%)
     let body = Layout::default()
         .direction(Direction::Horizontal)
         .constraints([Constraint::Percentage(35), Constraint::Percentage(65)])
-        .split(outer[1]);
+        .split(rows[0]);
 
     schema_list::draw(frame, app, body[0]);
     detail::draw(frame, app, body[1]);
 
     // Status bar
     statusbar::draw(frame, app, outer[2]);
+
+    // Popups draw last, over everything else.
+    usages::draw(frame, app, area);
+    global_search::draw(frame, app, area);
+}
+
+/// A popup's area: `width_pct` × `height_pct` of `area`, centred in it.
+fn centered(area: Rect, width_pct: u16, height_pct: u16) -> Rect {
+    let width = area.width * width_pct / 100;
+    let height = area.height * height_pct / 100;
+    Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    }
 }
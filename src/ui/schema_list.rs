@@ -6,28 +6,64 @@ use ratatui::{
     widgets::{Block, BorderType, Borders, List, ListItem, ListState, Paragraph},
 };
 
-use crate::app::{App, Pane};
+use crate::app::{App, Pane, View};
+use crate::diagnostics::{Diagnostic, DiagnosticKind};
+use crate::diff::Status;
 use crate::tree::NodeKind;
 
 pub fn draw_header(frame: &mut Frame, app: &App, area: Rect) {
-    let header = Paragraph::new(Line::from(vec![
-        Span::styled(
-            " speq ",
-            Style::default()
-                .fg(Color::Black)
-                .bg(Color::Cyan)
-                .add_modifier(Modifier::BOLD),
-        ),
-        Span::styled(
-            format!(
-                "  {}  ·  {}",
-                app.spec.title,
-                app.spec.version.label()
-            ),
+    let mut spans = vec![Span::styled(
+        " speq ",
+        Style::default()
+            .fg(Color::Black)
+            .bg(Color::Cyan)
+            .add_modifier(Modifier::BOLD),
+    )];
+    // Multi-service mode: the open service, or the size of the index.
+    if let Some(catalog) = &app.catalog {
+        let label = match catalog.active {
+            Some(index) => catalog.services[index].name.clone(),
+            None => format!("{} services", catalog.services.len()),
+        };
+        spans.push(Span::styled(
+            format!(" {label} "),
+            Style::default().fg(Color::Black).bg(Color::Yellow),
+        ));
+    }
+    if !app.spec.title.is_empty() {
+        spans.push(Span::styled(
+            format!("  {}  ·  {}", app.spec.title, app.spec.version.label()),
             Style::default().fg(Color::Gray),
-        ),
-    ]));
-    frame.render_widget(header, area);
+        ));
+    }
+    let diagnostics = app.spec.diagnostics.list.len();
+    if diagnostics > 0 {
+        spans.push(Span::styled(
+            format!("  ⚠ {diagnostics}"),
+            Style::default().fg(Color::Yellow),
+        ));
+    }
+    // Diff mode: top-level schemas added / removed / changed.
+    if let Some(marks) = &app.diff {
+        let (added, removed, changed) = marks.counts();
+        spans.push(Span::styled(
+            format!("  +{added}"),
+            Style::default().fg(Color::Green),
+        ));
+        spans.push(Span::styled(
+            format!(" −{removed}"),
+            Style::default().fg(Color::Red),
+        ));
+        spans.push(Span::styled(
+            format!(" ~{changed}"),
+            Style::default().fg(Color::Yellow),
+        ));
+    }
+    spans.push(Span::styled(
+        format!("  [{}]", app.view.label()),
+        Style::default().fg(Color::DarkGray),
+    ));
+    frame.render_widget(Paragraph::new(Line::from(spans)), area);
 }
 
 pub fn draw(frame: &mut Frame, app: &App, area: Rect) {
@@ -38,7 +74,28 @@ pub fn draw(frame: &mut Frame, app: &App, area: Rect) {
         Style::default().fg(Color::DarkGray)
     };
 
-    let root_count = app.tree.roots.len();
+    let tree = app.active_tree();
+    let title = match (app.view, &tree.filter) {
+        (View::Schemas, None) => format!(" Schemas ({}) ", tree.roots.len()),
+        (View::Operations, None) => format!(" Operations ({}) ", app.spec.operations.len()),
+        (View::Services, None) => format!(
+            " Services ({}) ",
+            app.catalog.as_ref().map_or(0, |c| c.services.len())
+        ),
+        (view, Some(filter)) => {
+            let name = match view {
+                View::Schemas => "Schemas",
+                View::Operations => "Tags",
+                View::Services => "Groups",
+            };
+            format!(
+                " {name} ({}/{}) · {} ",
+                filter.matched_roots(),
+                tree.roots.len(),
+                filter.query
+            )
+        }
+    };
     let block = Block::default()
         .borders(Borders::ALL)
         .border_type(if focused {
@@ -47,18 +104,32 @@ pub fn draw(frame: &mut Frame, app: &App, area: Rect) {
             BorderType::Plain
         })
         .border_style(border_style)
-        .title(format!(" Schemas ({root_count}) "));
+        .title(title);
 
-    let flat = app.tree.flatten();
+    let flat = tree.flatten();
+    let search = app.search.as_ref().filter(|s| s.view == app.view);
 
     let items: Vec<ListItem> = flat
         .iter()
         .map(|fnode| {
             let node = fnode.node;
+            let hits = search
+                .and_then(|s| s.hits_at(&fnode.path))
+                .or_else(|| tree.filter.as_ref().and_then(|f| f.hits_at(&fnode.path)))
+                .unwrap_or(&[]);
             let indent = "  ".repeat(fnode.depth);
+            let status = app
+                .diff
+                .as_ref()
+                .and_then(|marks| marks.at(app.view, &fnode.path))
+                .map(|mark| mark.status);
+            let name_style = match status {
+                Some(status) if status != Status::Inside => diff_style(status),
+                _ => Style::default(),
+            };
 
             let icon = if node.is_expandable() {
-                if node.expanded { "▼" } else { "▶" }
+                if fnode.open { "▼" } else { "▶" }
             } else {
                 "·"
             };
@@ -83,32 +154,155 @@ pub fn draw(frame: &mut Frame, app: &App, area: Rect) {
                 }
                 _ => node.type_label(),
             };
+            let type_label = if node.info.nullable {
+                format!("{type_label}?")
+            } else {
+                type_label
+            };
 
-            let line = Line::from(vec![
+            let mut spans = vec![
                 Span::raw(indent),
                 Span::styled(icon, icon_style),
                 Span::raw(" "),
-                Span::raw(node.name.clone()),
-                Span::styled(req_star, req_style),
-                Span::raw("  "),
-                Span::styled(type_label, Style::default().fg(Color::DarkGray)),
-            ]);
+            ];
+            match &node.info.kind {
+                // Operations: coloured method, then the path; tags: bold name.
+                NodeKind::Operation(_) => {
+                    let (method, path) = node.name.split_once(' ').unwrap_or(("", &node.name));
+                    let method_len = method.chars().count();
+                    spans.extend(highlighted(method, hits, method_style(method)));
+                    spans.push(Span::raw(" "));
+                    let path_hits: Vec<usize> = hits
+                        .iter()
+                        .filter_map(|&i| i.checked_sub(method_len + 1))
+                        .collect();
+                    spans.extend(highlighted(path, &path_hits, name_style));
+                }
+                NodeKind::Tag | NodeKind::ServiceGroup => {
+                    spans.extend(highlighted(
+                        &node.name,
+                        hits,
+                        name_style.add_modifier(Modifier::BOLD),
+                    ));
+                }
+                // Services: name, then the spec title.
+                NodeKind::Service(_) => {
+                    spans.extend(highlighted(&node.name, hits, Style::default()));
+                    if let Some(title) = &node.info.description {
+                        spans.push(Span::raw("  "));
+                        spans.push(Span::styled(
+                            title.clone(),
+                            Style::default().fg(Color::DarkGray),
+                        ));
+                    }
+                }
+                _ => {
+                    spans.extend(highlighted(&node.name, hits, name_style));
+                    spans.push(Span::styled(req_star, req_style));
+                    // Keyword groups (`parameters`, `$defs`) are already named after their kind.
+                    if type_label != node.name {
+                        spans.push(Span::raw("  "));
+                        spans.push(Span::styled(
+                            type_label,
+                            Style::default().fg(Color::DarkGray),
+                        ));
+                    }
+                }
+            }
+            if let Some(status) = status {
+                spans.push(Span::raw(" "));
+                spans.push(diff_marker(status));
+            }
+            if let Some(marker) = marker(&app.spec.diagnostics.at(app.view, &fnode.path)) {
+                spans.push(Span::raw(" "));
+                spans.push(marker);
+            }
+            let line = Line::from(spans);
 
             ListItem::new(line)
         })
         .collect();
 
-    let list = List::new(items)
-        .block(block)
-        .highlight_style(
-            Style::default()
-                .fg(Color::Black)
-                .bg(Color::Cyan)
-                .add_modifier(Modifier::BOLD),
-        );
+    let list = List::new(items).block(block).highlight_style(
+        Style::default()
+            .fg(Color::Black)
+            .bg(Color::Cyan)
+            .add_modifier(Modifier::BOLD),
+    );
 
     let mut state = ListState::default();
-    state.select(Some(app.tree.cursor));
+    state.select(Some(tree.cursor));
 
     frame.render_stateful_widget(list, area, &mut state);
 }
+
+/// `⚠` on a node with diagnostics: red for a dangling `$ref`, else yellow.
+pub fn marker(diagnostics: &[&Diagnostic]) -> Option<Span<'static>> {
+    if diagnostics.is_empty() {
+        return None;
+    }
+    let dangling = diagnostics
+        .iter()
+        .any(|d| d.kind == DiagnosticKind::DanglingRef);
+    let color = if dangling { Color::Red } else { Color::Yellow };
+    Some(Span::styled("⚠", Style::default().fg(color)))
+}
+
+/// `+` / `−` / `~` after a node that differs between the two specs (diff mode).
+pub fn diff_marker(status: Status) -> Span<'static> {
+    let symbol = match status {
+        Status::Added => "+",
+        Status::Removed => "−",
+        Status::Changed | Status::Inside => "~",
+    };
+    Span::styled(symbol, diff_style(status))
+}
+
+/// Green for added, red for removed, yellow for changed; dim for a node with
+/// changes only below it.
+pub fn diff_style(status: Status) -> Style {
+    match status {
+        Status::Added => Style::default().fg(Color::Green),
+        Status::Removed => Style::default().fg(Color::Red),
+        Status::Changed => Style::default().fg(Color::Yellow),
+        Status::Inside => Style::default().fg(Color::DarkGray),
+    }
+}
+
+/// Split `text` into spans, picking out the search-matched char indices.
+fn highlighted(text: &str, hits: &[usize], base: Style) -> Vec<Span<'static>> {
+    if hits.is_empty() {
+        return vec![Span::styled(text.to_string(), base)];
+    }
+    let hit_style = base
+        .fg(Color::Yellow)
+        .add_modifier(Modifier::BOLD | Modifier::UNDERLINED);
+    let mut spans: Vec<Span<'static>> = Vec::new();
+    let mut run = String::new();
+    let mut run_is_hit = false;
+    for (i, c) in text.chars().enumerate() {
+        let is_hit = hits.contains(&i);
+        if is_hit != run_is_hit && !run.is_empty() {
+            let style = if run_is_hit { hit_style } else { base };
+            spans.push(Span::styled(std::mem::take(&mut run), style));
+        }
+        run_is_hit = is_hit;
+        run.push(c);
+    }
+    if !run.is_empty() {
+        spans.push(Span::styled(run, if run_is_hit { hit_style } else { base }));
+    }
+    spans
+}
+
+/// Colour HTTP methods the way most API tools do.
+pub fn method_style(method: &str) -> Style {
+    let color = match method {
+        "GET" => Color::Green,
+        "POST" => Color::Yellow,
+        "PUT" | "PATCH" => Color::Blue,
+        "DELETE" => Color::Red,
+        _ => Color::Magenta,
+    };
+    Style::default().fg(color).add_modifier(Modifier::BOLD)
+}
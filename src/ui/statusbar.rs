@@ -2,13 +2,84 @@ use ratatui::{
     Frame,
     layout::Rect,
     style::{Color, Style},
+    text::{Line, Span},
     widgets::Paragraph,
 };
 
 use crate::app::App;
 
-pub fn draw(frame: &mut Frame, _app: &App, area: Rect) {
-    let hints = " j/k up/down  ·  h/l collapse/expand  ·  ? help";
+pub fn draw(frame: &mut Frame, app: &App, area: Rect) {
+    if let Some(search) = app.search.as_ref().filter(|s| s.typing) {
+        let count = match search.matches.len() {
+            0 if search.query.is_empty() => String::new(),
+            0 => "  no match".to_string(),
+            n => format!("  [{}/{}]", search.current + 1, n),
+        };
+        let bar = Paragraph::new(Line::from(vec![
+            Span::raw(format!("/{}", search.query)),
+            Span::styled("█", Style::default().fg(Color::Cyan)),
+            Span::styled(count, Style::default().fg(Color::DarkGray)),
+        ]));
+        frame.render_widget(bar, area);
+        return;
+    }
+
+    if app.filter_typing {
+        let query = app.filter_query().unwrap_or_default();
+        let count = match &app.active_tree().filter {
+            Some(filter) if filter.matches.is_empty() => "  no match".to_string(),
+            Some(filter) => format!("  {} nodes", filter.matches.len()),
+            None => String::new(),
+        };
+        let bar = Paragraph::new(Line::from(vec![
+            Span::styled("filter: ", Style::default().fg(Color::DarkGray)),
+            Span::raw(query.to_string()),
+            Span::styled("█", Style::default().fg(Color::Cyan)),
+            Span::styled(count, Style::default().fg(Color::DarkGray)),
+        ]));
+        frame.render_widget(bar, area);
+        return;
+    }
+
+    if let Some(input) = &app.validate_prompt {
+        let schema = app
+            .active_tree()
+            .selected_node()
+            .map(|n| n.name.clone())
+            .unwrap_or_default();
+        let hint = if input.is_empty() {
+            "  (Enter: clipboard)"
+        } else {
+            ""
+        };
+        let bar = Paragraph::new(Line::from(vec![
+            Span::styled(
+                format!("validate against {schema}: "),
+                Style::default().fg(Color::DarkGray),
+            ),
+            Span::raw(input.clone()),
+            Span::styled("█", Style::default().fg(Color::Cyan)),
+            Span::styled(hint, Style::default().fg(Color::DarkGray)),
+        ]));
+        frame.render_widget(bar, area);
+        return;
+    }
+
+    if let Some(message) = &app.status_message {
+        let bar = Paragraph::new(format!(" {message}")).style(Style::default().fg(Color::Yellow));
+        frame.render_widget(bar, area);
+        return;
+    }
+
+    let mut hints = concat!(
+        " j/k up/down  ·  h/l collapse/expand  ·  gd follow $ref  ·  C-o back",
+        "  ·  / search  ·  f filter  ·  p schemas/operations",
+    )
+    .to_string();
+    if app.catalog.is_some() {
+        hints.push_str("  ·  S services  ·  gs search all");
+    }
+    hints.push_str("  ·  ? help");
     let bar = Paragraph::new(hints).style(Style::default().fg(Color::DarkGray));
     frame.render_widget(bar, area);
 }
@@ -0,0 +1,60 @@
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Borders, Clear, List, ListItem, ListState},
+};
+
+use crate::app::App;
+use crate::ui::centered;
+
+/// The `gr` popup: every use of a component schema, `Enter` jumps to it.
+pub fn draw(frame: &mut Frame, app: &App, area: Rect) {
+    let Some(popup) = &app.usages else {
+        return;
+    };
+    let usages = &app.spec.usages[popup.schema];
+    let area = centered(area, 80, 60);
+    frame.render_widget(Clear, area);
+
+    let places = if usages.len() == 1 { "place" } else { "places" };
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(Color::Cyan))
+        .title(format!(
+            " {} is used in {} {places} ",
+            app.spec.schema_nodes[popup.schema].name,
+            usages.len()
+        ));
+
+    let items: Vec<ListItem> = usages
+        .iter()
+        .map(|usage| {
+            let mut spans = vec![
+                Span::styled(
+                    format!(" {:<13}", usage.place.label()),
+                    Style::default().fg(Color::Gray),
+                ),
+                Span::raw(usage.label.clone()),
+            ];
+            if let Some(via) = &usage.via {
+                spans.push(Span::styled(
+                    format!("  via {via}"),
+                    Style::default().fg(Color::DarkGray),
+                ));
+            }
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+
+    let list = List::new(items).block(block).highlight_style(
+        Style::default()
+            .bg(Color::DarkGray)
+            .add_modifier(Modifier::BOLD),
+    );
+    let mut state = ListState::default();
+    state.select(Some(popup.selected));
+    frame.render_stateful_widget(list, area, &mut state);
+}
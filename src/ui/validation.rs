@@ -0,0 +1,65 @@
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Borders, List, ListItem, ListState},
+};
+
+use crate::app::App;
+
+/// The `V` panel under the two panes: one row per violation, with where it is
+/// in the payload and which schema it breaks.
+pub fn draw(frame: &mut Frame, app: &App, area: Rect) {
+    let Some(validation) = &app.validation else {
+        return;
+    };
+    let count = validation.violations.len();
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(Color::Cyan))
+        .title(format!(
+            " {} against {} ({count} error{}) ",
+            validation.source,
+            validation.schema,
+            if count == 1 { "" } else { "s" }
+        ));
+
+    let items: Vec<ListItem> = validation
+        .violations
+        .iter()
+        .map(|v| {
+            let pointer = if v.pointer.is_empty() {
+                "(root)"
+            } else {
+                &v.pointer
+            };
+            ListItem::new(Line::from(vec![
+                Span::styled(format!(" {pointer}  "), Style::default().fg(Color::Red)),
+                Span::raw(v.message.clone()),
+                Span::styled(
+                    format!("  {}", v.schema),
+                    Style::default().fg(Color::DarkGray),
+                ),
+            ]))
+        })
+        .collect();
+
+    let list = List::new(items).block(block).highlight_style(
+        Style::default()
+            .bg(Color::DarkGray)
+            .add_modifier(Modifier::BOLD),
+    );
+    let mut state = ListState::default();
+    state.select(Some(validation.selected));
+    frame.render_stateful_widget(list, area, &mut state);
+}
+
+/// Rows the panel takes below the panes: its entries plus borders, at most 12.
+pub fn height(app: &App) -> u16 {
+    match &app.validation {
+        Some(validation) => (validation.violations.len() as u16 + 2).min(12),
+        None => 0,
+    }
+}
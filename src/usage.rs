@@ -0,0 +1,237 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::app::View;
+use crate::breadcrumb;
+use crate::tree::{NodeKind, TreeNode};
+
+/// What kind of place a component schema is used in.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Place {
+    Property,  // inside another component schema
+    Parameter, // an operation's path / query / header / cookie parameter
+    RequestBody,
+    Response,
+}
+
+impl Place {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Place::Property => "property",
+            Place::Parameter => "parameter",
+            Place::RequestBody => "request body",
+            Place::Response => "response",
+        }
+    }
+}
+
+/// One `$ref` to a component schema, at the tree node that holds it.
+#[derive(Debug, Clone)]
+pub struct Usage {
+    pub place: Place,
+    pub label: String,       // breadcrumb of the `$ref` node, without the tag
+    pub via: Option<String>, // the schema it is reached through, when not direct
+    pub view: View,
+    pub path: Vec<usize>,
+}
+
+/// Every use of each component schema, by index into `schema_nodes`: each
+/// `$ref` naming it in either tree. `$ref` nodes hold their target's children,
+/// so a schema is found both where it is referred to directly and wherever a
+/// schema referring to it is used. An operation listed under several tags
+/// counts once, under its first.
+pub fn collect(schema_nodes: &[TreeNode], operation_nodes: &[TreeNode]) -> Vec<Vec<Usage>> {
+    let index: HashMap<&str, usize> = schema_nodes
+        .iter()
+        .enumerate()
+        .map(|(i, node)| (node.name.as_str(), i))
+        .collect();
+    let mut walk = Walk {
+        index,
+        usages: vec![Vec::new(); schema_nodes.len()],
+    };
+
+    for (i, root) in schema_nodes.iter().enumerate() {
+        walk.node(View::Schemas, root, &mut Vec::new(), &mut vec![i]);
+    }
+    let mut seen = HashSet::new();
+    for (t, tag) in operation_nodes.iter().enumerate() {
+        for (o, operation) in tag.children.iter().enumerate() {
+            if let NodeKind::Operation(op) = operation.info.kind
+                && !seen.insert(op)
+            {
+                continue;
+            }
+            walk.node(View::Operations, operation, &mut vec![tag], &mut vec![t, o]);
+        }
+    }
+    walk.usages
+}
+
+struct Walk<'a> {
+    index: HashMap<&'a str, usize>,
+    usages: Vec<Vec<Usage>>,
+}
+
+impl<'a> Walk<'a> {
+    fn node(
+        &mut self,
+        view: View,
+        node: &'a TreeNode,
+        ancestors: &mut Vec<&'a TreeNode>,
+        path: &mut Vec<usize>,
+    ) {
+        ancestors.push(node);
+        if let NodeKind::Ref(target) = &node.info.kind
+            && let Some(&schema) = self.index.get(target.as_str())
+        {
+            self.usages[schema].push(usage(view, ancestors, path));
+        }
+        for (i, child) in node.children.iter().enumerate() {
+            path.push(i);
+            self.node(view, child, ancestors, path);
+            path.pop();
+        }
+        ancestors.pop();
+    }
+}
+
+/// The use at the last node of `chain`, which is a `$ref`.
+fn usage(view: View, chain: &[&TreeNode], path: &[usize]) -> Usage {
+    let (place, shown) = match view {
+        View::Operations => {
+            // Tag › operation › parameters / requestBody / responses › …
+            let place = match chain.get(2).map(|node| &node.info.kind) {
+                Some(NodeKind::Group(group)) if group == "parameters" => Place::Parameter,
+                Some(NodeKind::Group(group)) if group == "responses" => Place::Response,
+                _ => Place::RequestBody,
+            };
+            (place, &chain[1..])
+        }
+        _ => (Place::Property, chain),
+    };
+    let via = chain[..chain.len() - 1]
+        .iter()
+        .rev()
+        .find_map(|node| match &node.info.kind {
+            NodeKind::Ref(target) => Some(target.clone()),
+            _ => None,
+        });
+    Usage {
+        place,
+        label: breadcrumb::label(shown),
+        via,
+        view,
+        path: path.to_vec(),
+    }
+}
+
+// ── tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_spec;
+
+    const SPEC: &str = r##"
+openapi: 3.0.3
+info: { title: Shop, version: "1" }
+paths:
+  /orders/{id}:
+    get:
+      tags: [orders, admin]
+      parameters:
+        - { name: id, in: path, required: true, schema: { type: string } }
+        - { name: max, in: query, schema: { $ref: "#/components/schemas/Money" } }
+      responses:
+        "200":
+          description: ok
+          content:
+            application/json:
+              schema: { $ref: "#/components/schemas/Order" }
+  /refunds:
+    post:
+      tags: [orders]
+      requestBody:
+        content:
+          application/json:
+            schema: { $ref: "#/components/schemas/Money" }
+      responses:
+        "204": { description: refunded }
+components:
+  schemas:
+    Money:
+      type: object
+      properties:
+        amount: { type: number }
+    Order:
+      type: object
+      properties:
+        total: { $ref: "#/components/schemas/Money" }
+        lines: { type: array, items: { $ref: "#/components/schemas/OrderLine" } }
+    OrderLine:
+      type: object
+      properties:
+        price: { $ref: "#/components/schemas/Money" }
+"##;
+
+    fn rows(usages: &[Usage]) -> Vec<(&'static str, &str, Option<&str>)> {
+        usages
+            .iter()
+            .map(|u| (u.place.label(), u.label.as_str(), u.via.as_deref()))
+            .collect()
+    }
+
+    #[test]
+    fn finds_direct_and_transitive_uses() {
+        let spec = parse_spec(SPEC).unwrap();
+        let money = spec.schema_names.iter().position(|n| n == "Money").unwrap();
+        assert_eq!(
+            rows(&spec.usages[money]),
+            [
+                ("property", "Order › total → Money", None),
+                (
+                    "property",
+                    "Order › lines › items → OrderLine › price → Money",
+                    Some("OrderLine")
+                ),
+                ("property", "OrderLine › price → Money", None),
+                (
+                    "parameter",
+                    "GET /orders/{id} › parameters › max → Money",
+                    None
+                ),
+                (
+                    "response",
+                    "GET /orders/{id} › responses › 200 → Order › total → Money",
+                    Some("Order")
+                ),
+                (
+                    "response",
+                    "GET /orders/{id} › responses › 200 → Order › lines › items → OrderLine › price → Money",
+                    Some("OrderLine"),
+                ),
+                ("request body", "POST /refunds › requestBody → Money", None),
+            ]
+        );
+        let order = spec.schema_names.iter().position(|n| n == "Order").unwrap();
+        assert_eq!(
+            spec.usages[order].len(),
+            1,
+            "listed under two tags, counted once"
+        );
+    }
+
+    #[test]
+    fn paths_lead_to_the_ref_node() {
+        let spec = parse_spec(SPEC).unwrap();
+        for usage in spec.usages.iter().flatten() {
+            let roots = match usage.view {
+                View::Schemas => &spec.schema_nodes,
+                _ => &spec.operation_nodes,
+            };
+            let chain = crate::tree::chain_at(roots, &usage.path);
+            assert_eq!(chain.len(), usage.path.len());
+            assert!(matches!(chain.last().unwrap().info.kind, NodeKind::Ref(_)));
+        }
+    }
+}
@@ -0,0 +1,964 @@
+use std::collections::{HashMap, HashSet};
+use std::io::{self, Write};
+use std::net::{Ipv4Addr, Ipv6Addr};
+use std::sync::LazyLock;
+
+use clap::ValueEnum;
+use regex::Regex;
+use serde::Serialize;
+use serde_json::{Map, Value, json};
+
+use crate::breadcrumb;
+use crate::example::{KEYWORD_CHILDREN, literal};
+use crate::spec::{LoadedSpec, SpecVersion};
+use crate::tree::{NodeKind, TreeNode};
+
+/// `speq validate --format`.
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum Format {
+    Text,
+    Json,
+}
+
+/// One way a payload breaks its schema.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Violation {
+    pub pointer: String, // JSON Pointer into the payload, "" for the whole of it
+    pub schema: String,  // JSON Pointer to the schema broken, in the spec
+    pub message: String,
+}
+
+/// `$ref`s followed without going into the payload before giving up (and
+/// reporting it), for schemas that only refer to each other.
+const MAX_REF_HOPS: usize = 16;
+
+/// Check `value` against the schema at `node`, defined at `pointer` in `spec`
+/// (what `breadcrumb::json_pointer` gives for it). `$ref`s are followed into
+/// the spec's component schemas.
+pub fn validate(
+    value: &Value,
+    node: &TreeNode,
+    spec: &LoadedSpec,
+    pointer: &str,
+) -> Vec<Violation> {
+    let mut validator = Validator {
+        spec,
+        start: node,
+        pointer,
+        chain: Vec::new(),
+        hops: 0,
+        patterns: HashMap::new(),
+        out: Vec::new(),
+    };
+    validator.check(value, node, "");
+    validator.out
+}
+
+struct Validator<'a> {
+    spec: &'a LoadedSpec,
+    start: &'a TreeNode,
+    pointer: &'a str,
+    chain: Vec<&'a TreeNode>, // nodes from below `start` down to the one being checked
+    hops: usize,              // `$ref`s followed since the last step into the payload
+    patterns: HashMap<String, Option<Regex>>, // compiled `pattern`s; None for ones `regex` rejects
+    out: Vec<Violation>,
+}
+
+impl<'a> Validator<'a> {
+    fn check(&mut self, value: &Value, node: &'a TreeNode, at: &str) {
+        if let NodeKind::Ref(target) = &node.info.kind {
+            let root = self.spec.schema_nodes.iter().find(|s| s.name == *target);
+            match root {
+                Some(root) if self.hops < MAX_REF_HOPS => {
+                    self.hops += 1;
+                    self.check(value, root, at);
+                    self.hops -= 1;
+                }
+                Some(_) => self.fail(at, "reference depth limit reached".to_string()),
+                // A 3.1 `$defs` entry: what the tree expanded under the reference.
+                None => {
+                    if let Some(map) = value.as_object() {
+                        self.object(map, node, at);
+                    }
+                }
+            }
+            return;
+        }
+        if value.is_null() && node.info.nullable {
+            return;
+        }
+        if !node.info.enum_values.is_empty() {
+            if !node
+                .info
+                .enum_values
+                .iter()
+                .any(|e| same(&literal(e), value))
+            {
+                let allowed = node.info.enum_values.join(" · ");
+                self.fail(at, format!("{} is not one of {allowed}", brief(value)));
+            }
+            return;
+        }
+        if let Some(constant) = node.info.constraint("const") {
+            if !same(&literal(constant), value) {
+                self.fail(at, format!("{} is not {constant}", brief(value)));
+            }
+            return;
+        }
+        self.typed(&node.info.kind, value, node, at);
+        if self.spec.version == SpecVersion::V31 && !is_combiner(&node.info.kind) {
+            self.keywords(value, node, at);
+        }
+    }
+
+    fn typed(&mut self, kind: &NodeKind, value: &Value, node: &'a TreeNode, at: &str) {
+        match kind {
+            NodeKind::Object => match value.as_object() {
+                Some(map) => self.object(map, node, at),
+                None => self.mismatch("object", value, at),
+            },
+            NodeKind::Array | NodeKind::Tuple => match value.as_array() {
+                Some(items) => self.array(items, node, at),
+                None => self.mismatch("array", value, at),
+            },
+            NodeKind::Str => match value.as_str() {
+                Some(s) => self.string(s, node, at),
+                None => self.mismatch("string", value, at),
+            },
+            NodeKind::Integer if is_integer(value) => self.number(value, node, at),
+            NodeKind::Integer => self.mismatch("integer", value, at),
+            NodeKind::Number if value.is_number() => self.number(value, node, at),
+            NodeKind::Number => self.mismatch("number", value, at),
+            NodeKind::Boolean if !value.is_boolean() => self.mismatch("boolean", value, at),
+            NodeKind::Null if !value.is_null() => self.mismatch("null", value, at),
+            NodeKind::Multi(types) => match types.iter().find(|t| has_type(value, t)) {
+                Some(t) => self.typed(&type_kind(t), value, node, at),
+                None => self.mismatch(&types.join(" or "), value, at),
+            },
+            NodeKind::AllOf => {
+                for branch in &node.children {
+                    self.enter(branch, |v| v.check(value, branch, at));
+                }
+            }
+            NodeKind::OneOf => self.choice(value, &node.children, at, true),
+            NodeKind::AnyOf => self.choice(value, &node.children, at, false),
+            // No type: only the properties it lists, if the payload has any.
+            NodeKind::Unknown | NodeKind::Schema => {
+                if let Some(map) = value.as_object() {
+                    self.object(map, node, at);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// 3.1 keywords kept as children alongside the type's own:
+    /// `allOf` / `oneOf` / `anyOf`, `if` / `then` / `else` and `not`.
+    fn keywords(&mut self, value: &Value, node: &'a TreeNode, at: &str) {
+        for child in &node.children {
+            match (child.name.as_str(), &child.info.kind) {
+                ("allOf", NodeKind::AllOf)
+                | ("oneOf", NodeKind::OneOf)
+                | ("anyOf", NodeKind::AnyOf) => {
+                    self.enter(child, |v| v.typed(&child.info.kind, value, child, at));
+                }
+                ("if", _) => {
+                    let holds = self.attempt(value, child, at).is_empty();
+                    let branch = if holds { "then" } else { "else" };
+                    if let Some(branch) = node.children.iter().find(|c| c.name == branch) {
+                        self.enter(branch, |v| v.check(value, branch, at));
+                    }
+                }
+                ("not", _) if self.attempt(value, child, at).is_empty() => {
+                    self.enter(child, |v| {
+                        v.fail(at, "matches the schema under `not`".to_string())
+                    });
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn object(&mut self, map: &Map<String, Value>, node: &'a TreeNode, at: &str) {
+        let mut listed: HashSet<&str> = HashSet::new();
+        let mut additional = None;
+        let mut patterns = Vec::new();
+        for child in &node.children {
+            match (child.name.as_str(), &child.info.kind) {
+                ("additionalProperties", _) => additional = Some(child),
+                ("patternProperties", NodeKind::Group(_)) => patterns.extend(&child.children),
+                (_, NodeKind::Group(_)) => {}
+                ("allOf", NodeKind::AllOf)
+                | ("oneOf", NodeKind::OneOf)
+                | ("anyOf", NodeKind::AnyOf)
+                    if self.spec.version == SpecVersion::V31 => {}
+                (name, _)
+                    if self.spec.version == SpecVersion::V31
+                        && KEYWORD_CHILDREN.contains(&name) => {}
+                (name, _) => {
+                    listed.insert(name);
+                    match map.get(name) {
+                        Some(member) => {
+                            let at = format!("{at}/{}", breadcrumb::escape(name));
+                            self.enter(child, |v| v.check(member, child, &at));
+                        }
+                        None if child.info.required => {
+                            self.enter(child, |v| {
+                                v.fail(at, format!("missing required property {name}"))
+                            });
+                        }
+                        None => {}
+                    }
+                }
+            }
+        }
+
+        let count = map.len();
+        if let Some(min) = node.info.bound("minProperties")
+            && count < min
+        {
+            self.fail(
+                at,
+                format!("has {count} properties, expected at least {min}"),
+            );
+        }
+        if let Some(max) = node.info.bound("maxProperties")
+            && count > max
+        {
+            self.fail(
+                at,
+                format!("has {count} properties, expected at most {max}"),
+            );
+        }
+
+        let closed = node
+            .info
+            .constraints
+            .iter()
+            .any(|c| c == "additionalProperties: false");
+        // 3.1: members no keyword here evaluates fall to `unevaluatedProperties`.
+        let sealed = node
+            .info
+            .constraints
+            .iter()
+            .any(|c| c == "unevaluatedProperties: false");
+        let unevaluated = node
+            .children
+            .iter()
+            .find(|c| c.name == "unevaluatedProperties")
+            .filter(|_| self.spec.version == SpecVersion::V31);
+        let evaluated = match unevaluated {
+            Some(_) => evaluated(node),
+            None if sealed => evaluated(node),
+            None => HashSet::new(),
+        };
+        for (name, member) in map {
+            if listed.contains(name.as_str()) {
+                continue;
+            }
+            let at = format!("{at}/{}", breadcrumb::escape(name));
+            let mut matched = false;
+            for pattern in &patterns {
+                if self
+                    .regex(&pattern.name)
+                    .is_some_and(|re| re.is_match(name))
+                {
+                    matched = true;
+                    self.enter(pattern, |v| v.check(member, pattern, &at));
+                }
+            }
+            match (additional, unevaluated) {
+                _ if matched => {}
+                (Some(schema), _) => self.enter(schema, |v| v.check(member, schema, &at)),
+                (None, _) if closed => self.fail(&at, format!("unexpected property {name}")),
+                _ if evaluated.contains(name.as_str()) => {}
+                (None, Some(schema)) => self.enter(schema, |v| v.check(member, schema, &at)),
+                (None, None) if sealed => self.fail(&at, format!("unexpected property {name}")),
+                (None, None) => {}
+            }
+        }
+    }
+
+    fn array(&mut self, items: &[Value], node: &'a TreeNode, at: &str) {
+        let count = items.len();
+        if let Some(min) = node.info.bound("minItems")
+            && count < min
+        {
+            self.fail(at, format!("has {count} items, expected at least {min}"));
+        }
+        if let Some(max) = node.info.bound("maxItems")
+            && count > max
+        {
+            self.fail(at, format!("has {count} items, expected at most {max}"));
+        }
+        if node
+            .info
+            .constraints
+            .iter()
+            .any(|c| c == "uniqueItems: true")
+        {
+            let duplicate = (1..count)
+                .find_map(|j| (0..j).find(|&i| same(&items[i], &items[j])).map(|i| (i, j)));
+            if let Some((i, j)) = duplicate {
+                self.fail(
+                    at,
+                    format!("items {i} and {j} are equal, expected unique items"),
+                );
+            }
+        }
+        // A tuple's positional schemas first, then `items` for the rest.
+        let positional: Vec<&'a TreeNode> = node
+            .children
+            .iter()
+            .filter(|c| c.name.starts_with('['))
+            .collect();
+        let rest = node.children.iter().find(|c| c.name == "items");
+        for (i, item) in items.iter().enumerate() {
+            let Some(schema) = positional.get(i).copied().or(rest) else {
+                continue;
+            };
+            let at = format!("{at}/{i}");
+            self.enter(schema, |v| v.check(item, schema, &at));
+        }
+    }
+
+    fn string(&mut self, s: &str, node: &'a TreeNode, at: &str) {
+        let length = s.chars().count();
+        if let Some(min) = node.info.bound("minLength")
+            && length < min
+        {
+            self.fail(
+                at,
+                format!("{} is shorter than {min} characters", brief(&json!(s))),
+            );
+        }
+        if let Some(max) = node.info.bound("maxLength")
+            && length > max
+        {
+            self.fail(
+                at,
+                format!("{} is longer than {max} characters", brief(&json!(s))),
+            );
+        }
+        if let Some(pattern) = node.info.constraint("pattern")
+            && self.regex(pattern).is_some_and(|re| !re.is_match(s))
+        {
+            self.fail(at, format!("{} does not match {pattern}", brief(&json!(s))));
+        }
+        if let Some(format) = &node.info.format
+            && !has_format(format, s)
+        {
+            self.fail(at, format!("{} is not a valid {format}", brief(&json!(s))));
+        }
+    }
+
+    fn number(&mut self, value: &Value, node: &'a TreeNode, at: &str) {
+        let Some(n) = value.as_f64() else {
+            return;
+        };
+        if let Some((min, exclusive)) = node.info.limit("min")
+            && (n < min || (exclusive && n == min))
+        {
+            let bound = if exclusive {
+                "greater than"
+            } else {
+                "at least"
+            };
+            self.fail(
+                at,
+                format!("{value} is out of range, expected {bound} {min}"),
+            );
+        }
+        if let Some((max, exclusive)) = node.info.limit("max")
+            && (n > max || (exclusive && n == max))
+        {
+            let bound = if exclusive { "less than" } else { "at most" };
+            self.fail(
+                at,
+                format!("{value} is out of range, expected {bound} {max}"),
+            );
+        }
+        if let Some(step) = node
+            .info
+            .constraint("multipleOf")
+            .and_then(|s| s.parse::<f64>().ok())
+            && step > 0.0
+            && ((n / step).round() * step - n).abs() > 1e-9 * n.abs().max(1.0)
+        {
+            self.fail(at, format!("{value} is not a multiple of {step}"));
+        }
+        let range = match node.info.format.as_deref() {
+            Some("int32") => Some((i32::MIN as f64, i32::MAX as f64)),
+            Some("int64") => Some((i64::MIN as f64, i64::MAX as f64)),
+            _ => None,
+        };
+        if let Some((lo, hi)) = range
+            && !(lo..=hi).contains(&n)
+        {
+            let format = node.info.format.as_deref().unwrap_or_default();
+            self.fail(at, format!("{value} does not fit in {format}"));
+        }
+    }
+
+    /// `oneOf` (`exactly`) or `anyOf`. When no branch fits, the errors of the
+    /// one that came closest are reported after the summary.
+    fn choice(&mut self, value: &Value, branches: &'a [TreeNode], at: &str, exactly: bool) {
+        let keyword = if exactly { "oneOf" } else { "anyOf" };
+        let results: Vec<Vec<Violation>> = branches
+            .iter()
+            .map(|branch| {
+                self.chain.push(branch);
+                let errors = self.attempt(value, branch, at);
+                self.chain.pop();
+                errors
+            })
+            .collect();
+        let fitting: Vec<&str> = branches
+            .iter()
+            .zip(&results)
+            .filter(|(_, errors)| errors.is_empty())
+            .map(|(branch, _)| branch.name.as_str())
+            .collect();
+        match fitting.len() {
+            0 => {
+                self.fail(
+                    at,
+                    format!("matches none of the {} {keyword} branches", branches.len()),
+                );
+                if let Some(closest) = results.into_iter().min_by_key(Vec::len) {
+                    self.out.extend(closest);
+                }
+            }
+            1 => {}
+            _ if exactly => {
+                let names = fitting.join(", ");
+                self.fail(
+                    at,
+                    format!(
+                        "matches {} oneOf branches ({names}), expected exactly one",
+                        fitting.len()
+                    ),
+                );
+            }
+            _ => {}
+        }
+    }
+
+    /// The violations of `value` against `node`, without reporting them.
+    fn attempt(&mut self, value: &Value, node: &'a TreeNode, at: &str) -> Vec<Violation> {
+        let outer = std::mem::take(&mut self.out);
+        self.check(value, node, at);
+        std::mem::replace(&mut self.out, outer)
+    }
+
+    /// Run `f` one schema level down, at `child`.
+    fn enter(&mut self, child: &'a TreeNode, f: impl FnOnce(&mut Self)) {
+        let hops = std::mem::take(&mut self.hops);
+        self.chain.push(child);
+        f(self);
+        self.chain.pop();
+        self.hops = hops;
+    }
+
+    fn mismatch(&mut self, expected: &str, value: &Value, at: &str) {
+        self.fail(at, format!("expected {expected}, got {}", type_name(value)));
+    }
+
+    fn fail(&mut self, at: &str, message: String) {
+        let schema =
+            breadcrumb::descend(self.spec, self.pointer.to_string(), self.start, &self.chain);
+        self.out.push(Violation {
+            pointer: at.to_string(),
+            schema,
+            message,
+        });
+    }
+
+    fn regex(&mut self, pattern: &str) -> Option<&Regex> {
+        self.patterns
+            .entry(pattern.to_string())
+            .or_insert_with(|| Regex::new(pattern).ok())
+            .as_ref()
+    }
+}
+
+/// Members listed by a 3.1 object's in-place keywords: `allOf` / `oneOf` /
+/// `anyOf` branches, `if` / `then` / `else` and `dependentSchemas`. They count
+/// as evaluated for `unevaluatedProperties` whether or not the keyword applied.
+fn evaluated(node: &TreeNode) -> HashSet<&str> {
+    let mut names = HashSet::new();
+    for child in &node.children {
+        let schemas: Vec<&TreeNode> = match (child.name.as_str(), &child.info.kind) {
+            ("allOf", NodeKind::AllOf)
+            | ("oneOf", NodeKind::OneOf)
+            | ("anyOf", NodeKind::AnyOf)
+            | ("dependentSchemas", NodeKind::Group(_)) => child.children.iter().collect(),
+            ("if" | "then" | "else", _) => vec![child],
+            _ => continue,
+        };
+        let members = schemas.into_iter().flat_map(|schema| &schema.children);
+        names.extend(
+            members
+                .filter(|m| !matches!(m.info.kind, NodeKind::Group(_)))
+                .filter(|m| !KEYWORD_CHILDREN.contains(&m.name.as_str()))
+                .map(|m| m.name.as_str()),
+        );
+    }
+    names
+}
+
+fn is_combiner(kind: &NodeKind) -> bool {
+    matches!(kind, NodeKind::AllOf | NodeKind::OneOf | NodeKind::AnyOf)
+}
+
+/// Equal as JSON values, with `1` and `1.0` the same number.
+fn same(a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        (Value::Number(x), Value::Number(y)) => x.as_f64() == y.as_f64(),
+        (Value::Array(xs), Value::Array(ys)) => {
+            xs.len() == ys.len() && xs.iter().zip(ys).all(|(x, y)| same(x, y))
+        }
+        (Value::Object(xs), Value::Object(ys)) => {
+            xs.len() == ys.len()
+                && xs
+                    .iter()
+                    .all(|(k, x)| ys.get(k).is_some_and(|y| same(x, y)))
+        }
+        _ => a == b,
+    }
+}
+
+fn is_integer(value: &Value) -> bool {
+    value.is_i64() || value.is_u64() || value.as_f64().is_some_and(|n| n.fract() == 0.0)
+}
+
+fn has_type(value: &Value, name: &str) -> bool {
+    match name {
+        "integer" => is_integer(value),
+        "number" => value.is_number(),
+        other => type_name(value) == other,
+    }
+}
+
+fn type_kind(name: &str) -> NodeKind {
+    match name {
+        "object" => NodeKind::Object,
+        "array" => NodeKind::Array,
+        "string" => NodeKind::Str,
+        "integer" => NodeKind::Integer,
+        "number" => NodeKind::Number,
+        "boolean" => NodeKind::Boolean,
+        "null" => NodeKind::Null,
+        _ => NodeKind::Unknown,
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// A value for a message: scalars as JSON, at most 40 characters of them.
+fn brief(value: &Value) -> String {
+    match value {
+        Value::Array(_) => "array".to_string(),
+        Value::Object(_) => "object".to_string(),
+        scalar => {
+            let text = scalar.to_string();
+            match text.char_indices().nth(40) {
+                Some((cut, _)) => format!("{}…", &text[..cut]),
+                None => text,
+            }
+        }
+    }
+}
+
+static FORMATS: LazyLock<Vec<(&'static str, Regex)>> = LazyLock::new(|| {
+    let date = r"\d{4}-(0[1-9]|1[0-2])-(0[1-9]|[12]\d|3[01])";
+    let time = r"([01]\d|2[0-3]):[0-5]\d:([0-5]\d|60)(\.\d+)?([Zz]|[+-]([01]\d|2[0-3]):[0-5]\d)";
+    let hex = "[0-9a-fA-F]";
+    let label = r"[A-Za-z0-9]([A-Za-z0-9\-]{0,61}[A-Za-z0-9])?";
+    let clock = r"(?:T(?:\d+H)?(?:\d+M)?(?:\d+(?:\.\d+)?S)?)?";
+    [
+        ("date-time", format!("^{date}[Tt ]{time}$")),
+        ("date", format!("^{date}$")),
+        ("time", format!("^{time}$")),
+        (
+            "uuid",
+            format!("^{hex}{{8}}-{hex}{{4}}-{hex}{{4}}-{hex}{{4}}-{hex}{{12}}$"),
+        ),
+        ("email", r"^[^\s@]+@[^\s@]+\.[^\s@]+$".to_string()),
+        ("uri", r"^[A-Za-z][A-Za-z0-9+.\-]*:[^\s]*$".to_string()),
+        ("hostname", format!(r"^{label}(\.{label})*$")),
+        (
+            "byte",
+            r"^([A-Za-z0-9+/]{4})*([A-Za-z0-9+/]{2}==|[A-Za-z0-9+/]{3}=)?$".to_string(),
+        ),
+        (
+            "duration",
+            format!(r"^P(?:\d+W|(?:\d+Y)?(?:\d+M)?(?:\d+D)?{clock})$"),
+        ),
+    ]
+    .into_iter()
+    .map(|(name, pattern)| (name, Regex::new(&pattern).expect("format pattern")))
+    .collect()
+});
+
+/// Whether `s` is a valid `format`; formats not checked here always are.
+fn has_format(format: &str, s: &str) -> bool {
+    match format {
+        "ipv4" => s.parse::<Ipv4Addr>().is_ok(),
+        "ipv6" => s.parse::<Ipv6Addr>().is_ok(),
+        "url" => has_format("uri", s),
+        "duration" if s == "P" || s.ends_with('T') => false,
+        _ => FORMATS
+            .iter()
+            .find(|(name, _)| *name == format)
+            .is_none_or(|(_, re)| re.is_match(s)),
+    }
+}
+
+// ── output ────────────────────────────────────────────────────────────────────
+
+pub fn write_text(violations: &[Violation], out: &mut impl Write, color: bool) -> io::Result<()> {
+    for v in violations {
+        let pointer = if v.pointer.is_empty() {
+            "(root)"
+        } else {
+            &v.pointer
+        };
+        let pointer = if color {
+            format!("\x1b[1;31m{pointer}\x1b[0m")
+        } else {
+            pointer.to_string()
+        };
+        writeln!(out, "{pointer}: {}", v.message)?;
+        writeln!(out, "    {}", v.schema)?;
+    }
+    match violations.len() {
+        0 => writeln!(out, "valid"),
+        1 => writeln!(out, "\n1 error"),
+        n => writeln!(out, "\n{n} errors"),
+    }
+}
+
+pub fn to_json(violations: &[Violation]) -> Value {
+    json!({ "valid": violations.is_empty(), "errors": violations })
+}
+
+// ── tests ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SPEC: &str = r##"
+openapi: 3.0.3
+info: { title: Shop, version: "1" }
+paths: {}
+components:
+  schemas:
+    Order:
+      type: object
+      required: [id, lines, status]
+      additionalProperties: false
+      properties:
+        id: { type: string, format: uuid }
+        status: { type: string, enum: [pending, shipped] }
+        reference: { type: string, pattern: "^ORD-[0-9]{4}$" }
+        quantity: { type: integer, minimum: 1, maximum: 10, multipleOf: 2 }
+        placedAt: { type: string, format: date-time }
+        lines:
+          type: array
+          minItems: 1
+          uniqueItems: true
+          items: { $ref: "#/components/schemas/Line" }
+        payment:
+          oneOf:
+            - { $ref: "#/components/schemas/Card" }
+            - { $ref: "#/components/schemas/Transfer" }
+        tags:
+          type: object
+          additionalProperties: { type: string, maxLength: 3 }
+    Line:
+      allOf:
+        - type: object
+          required: [sku]
+          properties:
+            sku: { type: string, minLength: 3 }
+        - type: object
+          properties:
+            price: { type: number, exclusiveMinimum: true, minimum: 0 }
+    Card:
+      type: object
+      required: [last4]
+      properties:
+        last4: { type: string, pattern: "^[0-9]{4}$" }
+    Transfer:
+      type: object
+      required: [iban]
+      properties:
+        iban: { type: string }
+"##;
+
+    fn check(payload: Value) -> Vec<(String, String, String)> {
+        let spec = crate::parser::parse_spec(SPEC).unwrap();
+        let order = spec
+            .schema_nodes
+            .iter()
+            .find(|n| n.name == "Order")
+            .unwrap();
+        validate(&payload, order, &spec, "#/components/schemas/Order")
+            .into_iter()
+            .map(|v| (v.pointer, v.schema, v.message))
+            .collect()
+    }
+
+    #[test]
+    fn accepts_a_conforming_payload() {
+        let payload = json!({
+            "id": "3fa85f64-5717-4562-b3fc-2c963f66afa6",
+            "status": "shipped",
+            "quantity": 4,
+            "placedAt": "2024-01-15T09:30:00.5+01:00",
+            "lines": [{ "sku": "abc", "price": 2.5 }],
+            "payment": { "iban": "DE89" },
+            "tags": { "env": "dev" }
+        });
+        assert_eq!(check(payload), vec![]);
+    }
+
+    #[test]
+    fn reports_payload_and_schema_pointers() {
+        let payload = json!({
+            "id": "not-a-uuid",
+            "status": "lost",
+            "reference": "ORD-12",
+            "quantity": 11,
+            "lines": [{ "sku": "ab", "price": 0 }, { "sku": "ab", "price": 0 }],
+            "tags": { "env": "production" },
+            "extra": true
+        });
+        let errors = check(payload);
+        let schemas = "#/components/schemas";
+        let expected = [
+            (
+                "/id",
+                format!("{schemas}/Order/properties/id"),
+                "\"not-a-uuid\" is not a valid uuid",
+            ),
+            (
+                "/status",
+                format!("{schemas}/Order/properties/status"),
+                "\"lost\" is not one of \"pending\" · \"shipped\"",
+            ),
+            (
+                "/reference",
+                format!("{schemas}/Order/properties/reference"),
+                "\"ORD-12\" does not match ^ORD-[0-9]{4}$",
+            ),
+            (
+                "/quantity",
+                format!("{schemas}/Order/properties/quantity"),
+                "11 is out of range, expected at most 10",
+            ),
+            (
+                "/quantity",
+                format!("{schemas}/Order/properties/quantity"),
+                "11 is not a multiple of 2",
+            ),
+            (
+                "/lines",
+                format!("{schemas}/Order/properties/lines"),
+                "items 0 and 1 are equal, expected unique items",
+            ),
+            (
+                "/lines/0/sku",
+                format!("{schemas}/Line/allOf/0/properties/sku"),
+                "\"ab\" is shorter than 3 characters",
+            ),
+            (
+                "/lines/0/price",
+                format!("{schemas}/Line/allOf/1/properties/price"),
+                "0 is out of range, expected greater than 0",
+            ),
+            (
+                "/lines/1/sku",
+                format!("{schemas}/Line/allOf/0/properties/sku"),
+                "\"ab\" is shorter than 3 characters",
+            ),
+            (
+                "/lines/1/price",
+                format!("{schemas}/Line/allOf/1/properties/price"),
+                "0 is out of range, expected greater than 0",
+            ),
+            (
+                "/tags/env",
+                format!("{schemas}/Order/properties/tags/additionalProperties"),
+                "\"production\" is longer than 3 characters",
+            ),
+            (
+                "/extra",
+                format!("{schemas}/Order"),
+                "unexpected property extra",
+            ),
+        ];
+        let expected: Vec<(String, String, String)> = expected
+            .into_iter()
+            .map(|(p, s, m)| (p.to_string(), s, m.to_string()))
+            .collect();
+        assert_eq!(errors, expected);
+    }
+
+    #[test]
+    fn reports_missing_properties_types_and_combiners() {
+        let errors = check(json!({ "id": 7, "lines": [], "payment": { "last4": "12" } }));
+        let messages: Vec<(&str, &str)> = errors
+            .iter()
+            .map(|(p, _, m)| (p.as_str(), m.as_str()))
+            .collect();
+        assert_eq!(
+            messages,
+            vec![
+                ("/id", "expected string, got number"),
+                ("", "missing required property status"),
+                ("/lines", "has 0 items, expected at least 1"),
+                ("/payment", "matches none of the 2 oneOf branches"),
+                ("/payment/last4", "\"12\" does not match ^[0-9]{4}$"),
+            ]
+        );
+        // The missing property points at its own schema.
+        assert_eq!(errors[1].1, "#/components/schemas/Order/properties/status");
+    }
+
+    #[test]
+    fn generated_examples_conform() {
+        for fixture in [
+            include_str!("../fixtures/petstore.yaml"),
+            include_str!("../fixtures/petstore-3.1.yaml"),
+            include_str!("../fixtures/petstore-v2.yaml"),
+            SPEC,
+        ] {
+            let spec = crate::parser::parse_spec(fixture).unwrap();
+            for (i, schema) in spec.schema_nodes.iter().enumerate() {
+                let example = crate::example::generate(schema, &spec.schema_nodes).unwrap();
+                let pointer =
+                    breadcrumb::json_pointer(&spec, crate::app::View::Schemas, &[schema], &[i])
+                        .unwrap();
+                let violations = validate(&example, schema, &spec, &pointer);
+                assert_eq!(violations, vec![], "{} example {example}", schema.name);
+            }
+        }
+    }
+
+    #[test]
+    fn applies_unevaluated_properties() {
+        let fixture = include_str!("../fixtures/petstore-3.1.yaml");
+        let spec = crate::parser::parse_spec(fixture).unwrap();
+        let shape = spec
+            .schema_nodes
+            .iter()
+            .find(|n| n.name == "Shape")
+            .unwrap();
+        let payload = json!({ "kind": "square", "side": 2, "id": "x", "name": 5, "extra": 1 });
+        let errors: Vec<(String, String, String)> =
+            validate(&payload, shape, &spec, "#/components/schemas/Shape")
+                .into_iter()
+                .map(|v| (v.pointer, v.schema, v.message))
+                .collect();
+        let schema = "#/components/schemas/Shape/unevaluatedProperties".to_string();
+        assert_eq!(
+            errors,
+            vec![
+                (
+                    "/name".to_string(),
+                    schema.clone(),
+                    "expected string, got number".to_string()
+                ),
+                (
+                    "/extra".to_string(),
+                    schema,
+                    "expected string, got number".to_string()
+                ),
+            ],
+            "`side` is evaluated by `else`, `id` is a string"
+        );
+    }
+
+    #[test]
+    fn unevaluated_properties_false_closes_the_object() {
+        let spec = crate::parser::parse_spec(
+            r#"
+openapi: 3.1.0
+info: { title: Sealed, version: "1" }
+paths: {}
+components:
+  schemas:
+    Point:
+      type: object
+      properties:
+        x: { type: number }
+      allOf:
+        - properties:
+            y: { type: number }
+      unevaluatedProperties: false
+"#,
+        )
+        .unwrap();
+        let point = &spec.schema_nodes[0];
+        let payload = json!({ "x": 1, "y": 2, "z": 3 });
+        let errors = validate(&payload, point, &spec, "#/components/schemas/Point");
+        let found: Vec<(&str, &str)> = errors
+            .iter()
+            .map(|v| (v.pointer.as_str(), v.message.as_str()))
+            .collect();
+        assert_eq!(found, vec![("/z", "unexpected property z")]);
+    }
+
+    #[test]
+    fn reports_refs_that_never_reach_a_schema() {
+        let spec = crate::parser::parse_spec(
+            r##"
+openapi: 3.0.3
+info: { title: Loop, version: "1" }
+paths: {}
+components:
+  schemas:
+    Ping: { $ref: "#/components/schemas/Pong" }
+    Pong: { $ref: "#/components/schemas/Ping" }
+"##,
+        )
+        .unwrap();
+        let ping = &spec.schema_nodes[0];
+        let errors = validate(
+            &json!({ "deep": true }),
+            ping,
+            &spec,
+            "#/components/schemas/Ping",
+        );
+        let found: Vec<(&str, &str)> = errors
+            .iter()
+            .map(|v| (v.pointer.as_str(), v.message.as_str()))
+            .collect();
+        assert_eq!(found, vec![("", "reference depth limit reached")]);
+    }
+
+    #[test]
+    fn checks_formats() {
+        assert!(has_format("date", "2024-02-29"));
+        assert!(!has_format("date", "2024-13-01"));
+        assert!(has_format("email", "a@b.io"));
+        assert!(!has_format("email", "a@b"));
+        assert!(has_format("ipv6", "2001:db8::1"));
+        assert!(!has_format("ipv4", "256.0.0.1"));
+        assert!(has_format("duration", "PT1H30M"));
+        assert!(!has_format("duration", "P"));
+        assert!(has_format("made-up", "anything"));
+    }
+}